@@ -1,83 +1,47 @@
 // This example demonstrates how you might go about managing multiple animations,
-// and switching between them based on the player's input. Something along these
-// lines may be added to Tetra itself later, but for now it's not too hard to
-// roll your own!
+// and switching between them based on the player's input, using an
+// `AnimationController`.
 
 use std::time::Duration;
 
-use firecore_tetra::graphics::animation::Animation;
+use firecore_tetra::graphics::animation::{Animation, AnimationController, Condition, Transition};
 use firecore_tetra::graphics::{self, Color, DrawParams, Rectangle, Texture};
 use firecore_tetra::input::{self, Key};
 use firecore_tetra::math::Vec2;
-use firecore_tetra::{Context, ContextBuilder, DefaultContext, State};
-
-#[derive(PartialEq)]
-enum PlayerState {
-    Idle,
-    Running,
-}
-
-struct PlayerAnimation {
-    state: PlayerState,
-    idle: Animation,
-    running: Animation,
-}
-
-impl PlayerAnimation {
-    fn new(ctx: &mut DefaultContext) -> firecore_tetra::Result<PlayerAnimation> {
-        let texture = Texture::new(ctx, "./examples/resources/tiles.png")?;
-
-        Ok(PlayerAnimation {
-            state: PlayerState::Idle,
-            idle: Animation::new(
-                // Remember, textures are cheap to clone, as they just point at GPU data.
-                texture.clone(),
-                Rectangle::row(0.0, 256.0, 16.0, 16.0).take(8).collect(),
-                Duration::from_secs_f64(0.1),
-            ),
-            running: Animation::new(
-                texture,
-                Rectangle::row(0.0, 272.0, 16.0, 16.0).take(8).collect(),
-                Duration::from_secs_f64(0.1),
-            ),
-        })
-    }
-
-    fn draw<P>(&self, ctx: &mut Context, params: P)
-    where
-        P: Into<DrawParams>,
-    {
-        self.current().draw(ctx, params)
-    }
-
-    fn current(&self) -> &Animation {
-        match self.state {
-            PlayerState::Idle => &self.idle,
-            PlayerState::Running => &self.running,
-        }
-    }
-
-    fn current_mut(&mut self) -> &mut Animation {
-        match self.state {
-            PlayerState::Idle => &mut self.idle,
-            PlayerState::Running => &mut self.running,
-        }
-    }
-
-    fn advance(&mut self, ctx: &Context) {
-        self.current_mut().advance(ctx);
-    }
-
-    fn set_state(&mut self, state: PlayerState) {
-        if self.state != state {
-            self.state = state;
-            self.current_mut().restart();
-        }
-    }
+use firecore_tetra::{ContextBuilder, DefaultContext, State};
+
+fn player_animation(ctx: &mut DefaultContext) -> firecore_tetra::Result<AnimationController> {
+    let texture = Texture::new(ctx, "./examples/resources/tiles.png")?;
+
+    let mut controller = AnimationController::new(
+        "idle",
+        Animation::new(
+            // Remember, textures are cheap to clone, as they just point at GPU data.
+            texture.clone(),
+            Rectangle::row(0.0, 256.0, 16.0, 16.0).take(8).collect(),
+            Duration::from_secs_f64(0.1),
+        ),
+    );
+
+    controller.add_state(
+        "running",
+        Animation::new(
+            texture,
+            Rectangle::row(0.0, 272.0, 16.0, 16.0).take(8).collect(),
+            Duration::from_secs_f64(0.1),
+        ),
+    );
+
+    controller
+        .add_transition(Transition::new("idle", "running").when(Condition::True("moving".into())));
+    controller
+        .add_transition(Transition::new("running", "idle").when(Condition::False("moving".into())));
+
+    Ok(controller)
 }
 
 struct GameState {
-    animation: PlayerAnimation,
+    animation: AnimationController,
     position: Vec2<f32>,
     velocity: Vec2<f32>,
 }
@@ -85,7 +49,7 @@ struct GameState {
 impl GameState {
     fn new(ctx: &mut DefaultContext) -> firecore_tetra::Result<GameState> {
         Ok(GameState {
-            animation: PlayerAnimation::new(ctx)?,
+            animation: player_animation(ctx)?,
             position: Vec2::new(240.0, 160.0),
             velocity: Vec2::new(0.0, 0.0),
         })
@@ -104,11 +68,8 @@ impl State for GameState {
 
         self.position += self.velocity;
 
-        if self.velocity.x.abs() > 0.0 {
-            self.animation.set_state(PlayerState::Running);
-        } else {
-            self.animation.set_state(PlayerState::Idle);
-        }
+        self.animation
+            .set_bool("moving", self.velocity.x.abs() > 0.0);
 
         Ok(())
     }
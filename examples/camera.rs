@@ -1,5 +1,5 @@
 use firecore_tetra::graphics::scaling::{ScalingMode, ScreenScaler};
-use firecore_tetra::graphics::{self, Camera, Color, DrawParams, Texture};
+use firecore_tetra::graphics::{self, Camera, CameraShake, Color, DrawParams, Texture};
 use firecore_tetra::input::{self, Key};
 use firecore_tetra::math::Vec2;
 use firecore_tetra::{DefaultContext, ContextBuilder, Event, State};
@@ -16,6 +16,10 @@ struct GameState {
 
 impl GameState {
     fn new(ctx: &mut DefaultContext) -> firecore_tetra::Result<GameState> {
+        let mut camera = Camera::new(640.0, 480.0);
+        camera.pixel_snapping = true;
+        camera.set_shake_settings(CameraShake::new().max_offset(Vec2::new(16.0, 16.0)));
+
         Ok(GameState {
             texture: Texture::new(ctx, "./examples/resources/player.png")?,
             scaler: ScreenScaler::with_window_size(
@@ -27,7 +31,7 @@ impl GameState {
 
             // The camera's viewport size should match the target you're rendering
             // to - in this case, the ScreenScaler:
-            camera: Camera::new(640.0, 480.0),
+            camera,
         })
     }
 }
@@ -58,16 +62,35 @@ impl State for GameState {
             self.camera.rotation += ROTATION_SPEED;
         }
 
-        if input::is_key_down(ctx, Key::R) || input::is_mouse_scrolled_up(ctx) {
+        if input::is_key_down(ctx, Key::R) {
             self.camera.scale += ZOOM_SPEED;
         }
 
-        if input::is_key_down(ctx, Key::F) || input::is_mouse_scrolled_down(ctx) {
+        if input::is_key_down(ctx, Key::F) {
             self.camera.scale -= ZOOM_SPEED;
         }
 
+        // Zooming with the mouse wheel keeps the point under the cursor in place:
+        let wheel = input::get_mouse_wheel_movement(ctx).y;
+
+        if wheel != 0 {
+            let scale = self.camera.scale + ZOOM_SPEED * wheel as f32;
+            let mouse_position = self.scaler.mouse_position(ctx);
+
+            self.camera.zoom_around(mouse_position, scale);
+        }
+
+        if input::is_key_pressed(ctx, Key::Space) {
+            self.camera.add_trauma(0.5);
+        }
+
         self.camera.update();
 
+        // The camera snaps to whole pixels on the canvas - passing the remainder to the
+        // scaler keeps the movement smooth when the canvas is scaled up:
+        self.scaler
+            .set_subpixel_offset(self.camera.subpixel_offset());
+
         Ok(())
     }
 
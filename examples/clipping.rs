@@ -0,0 +1,109 @@
+use std::f32::consts::FRAC_PI_4;
+
+use firecore_tetra::graphics::mesh::ShapeStyle;
+use firecore_tetra::graphics::{self, Color, DrawParams, Rectangle, Texture};
+use firecore_tetra::math::{Mat4, Vec2};
+use firecore_tetra::time;
+use firecore_tetra::{ContextBuilder, DefaultContext, State};
+
+struct GameState {
+    texture: Texture,
+}
+
+impl GameState {
+    fn new(ctx: &mut DefaultContext) -> firecore_tetra::Result<GameState> {
+        Ok(GameState {
+            texture: Texture::new(ctx, "./examples/resources/wabbit_alpha.png")?,
+        })
+    }
+
+    fn draw_panel(&self, ctx: &mut DefaultContext, x: f32, y: f32) -> firecore_tetra::Result {
+        // Transforms are relative to their parent, so the panel's contents can be
+        // positioned from its top left corner.
+        graphics::push_transform(ctx, Mat4::translation_2d(Vec2::new(x, y)));
+
+        graphics::draw_rect(
+            ctx,
+            ShapeStyle::Fill,
+            Rectangle::new(0.0, 0.0, 240.0, 240.0),
+            Color::rgb(0.2, 0.2, 0.3),
+        )?;
+
+        // Scissor rectangles are not affected by the transform, so they need to be
+        // specified in screen co-ordinates. This one will be clipped to the window's
+        // left half by the scissor that was pushed in `draw`.
+        graphics::push_scissor(ctx, Rectangle::new(x as i32 + 20, y as i32 + 20, 200, 200));
+
+        // Masks are drawn with the current transform, and are clipped to
+        // any masks that are already active.
+        graphics::push_mask(ctx, |ctx| {
+            graphics::draw_circle(
+                ctx,
+                ShapeStyle::Fill,
+                Vec2::new(120.0, 120.0),
+                100.0,
+                Color::WHITE,
+            )
+        })?;
+
+        let offset = (time::get_elapsed_time(ctx).as_secs_f32() * 2.0).sin() * 40.0;
+
+        self.texture.draw(
+            ctx,
+            DrawParams::new()
+                .position(Vec2::new(120.0 + offset, 120.0))
+                .scale(Vec2::broadcast(8.0))
+                .origin(Vec2::new(
+                    self.texture.width() as f32 / 2.0,
+                    self.texture.height() as f32 / 2.0,
+                )),
+        );
+
+        graphics::pop_mask(ctx);
+        graphics::pop_scissor(ctx);
+        graphics::pop_transform(ctx);
+
+        Ok(())
+    }
+}
+
+impl State for GameState {
+    fn draw(&mut self, ctx: &mut DefaultContext) -> firecore_tetra::Result {
+        graphics::clear(ctx, Color::BLACK);
+
+        graphics::push_scissor(ctx, Rectangle::new(0, 0, 400, 600));
+
+        // The outer mask is a diamond, which the circular masks inside of each
+        // panel will be clipped to.
+        graphics::push_transform(
+            ctx,
+            Mat4::rotation_z(FRAC_PI_4).translated_2d(Vec2::new(400.0, 300.0)),
+        );
+
+        graphics::push_mask(ctx, |ctx| {
+            graphics::draw_rect(
+                ctx,
+                ShapeStyle::Fill,
+                Rectangle::new(-200.0, -200.0, 400.0, 400.0),
+                Color::WHITE,
+            )
+        })?;
+
+        graphics::pop_transform(ctx);
+
+        self.draw_panel(ctx, 140.0, 60.0)?;
+        self.draw_panel(ctx, 280.0, 300.0)?;
+
+        graphics::pop_mask(ctx);
+        graphics::pop_scissor(ctx);
+
+        Ok(())
+    }
+}
+
+fn main() -> firecore_tetra::Result {
+    ContextBuilder::new("Nested Clipping", 800, 600)
+        .stencil_buffer(true)
+        .build()?
+        .run(GameState::new)
+}
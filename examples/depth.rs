@@ -0,0 +1,94 @@
+use firecore_tetra::graphics::{self, Color, DepthState, DepthTest, DrawParams, Texture};
+use firecore_tetra::input::{self, Key};
+use firecore_tetra::math::Vec2;
+use firecore_tetra::{ContextBuilder, DefaultContext, State};
+
+const MOVEMENT_SPEED: f32 = 4.0;
+
+struct GameState {
+    block: Texture,
+    player: Texture,
+    player_position: Vec2<f32>,
+}
+
+impl GameState {
+    fn new(ctx: &mut DefaultContext) -> firecore_tetra::Result<GameState> {
+        Ok(GameState {
+            block: Texture::new(ctx, "./examples/resources/block.png")?,
+            player: Texture::new(ctx, "./examples/resources/player.png")?,
+            player_position: Vec2::new(320.0, 240.0),
+        })
+    }
+}
+
+// Things that are further down the screen should be drawn in front.
+fn depth_for(y: f32) -> f32 {
+    y / 480.0 * 2.0 - 1.0
+}
+
+impl State for GameState {
+    fn update(&mut self, ctx: &mut DefaultContext) -> firecore_tetra::Result {
+        if input::is_key_down(ctx, Key::W) {
+            self.player_position.y -= MOVEMENT_SPEED;
+        }
+
+        if input::is_key_down(ctx, Key::S) {
+            self.player_position.y += MOVEMENT_SPEED;
+        }
+
+        if input::is_key_down(ctx, Key::A) {
+            self.player_position.x -= MOVEMENT_SPEED;
+        }
+
+        if input::is_key_down(ctx, Key::D) {
+            self.player_position.x += MOVEMENT_SPEED;
+        }
+
+        Ok(())
+    }
+
+    fn draw(&mut self, ctx: &mut DefaultContext) -> firecore_tetra::Result {
+        graphics::clear(ctx, Color::rgb(0.769, 0.812, 0.631));
+        graphics::clear_depth(ctx, -1.0);
+
+        graphics::set_depth_state(ctx, DepthState::read_write(DepthTest::GreaterThanOrEqualTo));
+
+        // The player is drawn first, but will still appear behind any
+        // blocks that are further down the screen.
+        self.player.draw(
+            ctx,
+            DrawParams::new()
+                .position(self.player_position)
+                .origin(Vec2::new(8.0, 16.0))
+                .scale(Vec2::new(4.0, 4.0))
+                .depth(depth_for(self.player_position.y)),
+        );
+
+        for y in 0..6 {
+            for x in 0..8 {
+                let position = Vec2::new(48.0 + x as f32 * 80.0, 64.0 + y as f32 * 80.0);
+
+                self.block.draw(
+                    ctx,
+                    DrawParams::new()
+                        .position(position)
+                        .origin(Vec2::new(8.0, 16.0))
+                        .scale(Vec2::new(2.0, 2.0))
+                        .depth(depth_for(position.y)),
+                );
+            }
+        }
+
+        graphics::set_depth_state(ctx, DepthState::disabled());
+
+        Ok(())
+    }
+}
+
+fn main() -> firecore_tetra::Result {
+    ContextBuilder::new("Depth Testing", 640, 480)
+        .depth_buffer(true)
+        .quit_on_escape(true)
+        .build()?
+        .run(GameState::new)
+}
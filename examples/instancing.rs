@@ -1,44 +1,80 @@
-use firecore_tetra::graphics::mesh::{BorderRadii, GeometryBuilder, Mesh, ShapeStyle};
-use firecore_tetra::graphics::{self, Color, Rectangle, Shader};
+use firecore_tetra::graphics::mesh::{
+    BorderRadii, GeometryBuilder, Instance, InstanceBuffer, Mesh, ShapeStyle,
+};
+use firecore_tetra::graphics::{self, Color, Rectangle};
 use firecore_tetra::math::Vec2;
-use firecore_tetra::{DefaultContext, ContextBuilder, State};
+use firecore_tetra::{ContextBuilder, DefaultContext, State};
+
+const COLUMNS: usize = 128;
+const ROWS: usize = 72;
 
 struct GameState {
     mesh: Mesh,
+    instance_buffer: InstanceBuffer,
+    instances: Vec<Instance>,
+    timer: f32,
 }
 
 impl GameState {
     fn new(ctx: &mut DefaultContext) -> firecore_tetra::Result<GameState> {
-        let mesh = GeometryBuilder::new()
+        let mut mesh = GeometryBuilder::new()
             .rounded_rectangle(
-                ShapeStyle::Stroke(2.0),
-                Rectangle::new(0.0, 0.0, 16.0, 16.0),
-                BorderRadii::new(4.0),
+                ShapeStyle::Fill,
+                Rectangle::new(-4.0, -4.0, 8.0, 8.0),
+                BorderRadii::new(2.0),
             )?
             .build_mesh(ctx)?;
 
-        let mut offsets = Vec::with_capacity(256);
+        let mut instances = Vec::with_capacity(COLUMNS * ROWS);
+
+        for y in 0..ROWS {
+            for x in 0..COLUMNS {
+                let color = Color::rgb(
+                    x as f32 / COLUMNS as f32,
+                    y as f32 / ROWS as f32,
+                    1.0 - x as f32 / COLUMNS as f32,
+                );
 
-        for y in 0..16 {
-            for x in 0..16 {
-                offsets.push(Vec2::new(x as f32 * 32.0, y as f32 * 32.0));
+                instances.push(
+                    Instance::new(Vec2::new(x as f32 * 10.0 + 5.0, y as f32 * 10.0 + 5.0))
+                        .color(color),
+                );
             }
         }
 
-        let shader = Shader::from_vertex_file(ctx, "./examples/resources/instanced.vert")?;
-        shader.set_uniform(ctx, "u_offsets", offsets.as_slice());
-
-        graphics::set_shader(ctx, &shader);
+        let instance_buffer = InstanceBuffer::new(ctx, &instances)?;
+        mesh.set_instance_buffer(instance_buffer.clone());
 
-        Ok(GameState { mesh })
+        Ok(GameState {
+            mesh,
+            instance_buffer,
+            instances,
+            timer: 0.0,
+        })
     }
 }
 
 impl State for GameState {
+    fn update(&mut self, ctx: &mut DefaultContext) -> firecore_tetra::Result {
+        self.timer += 0.05;
+
+        for (i, instance) in self.instances.iter_mut().enumerate() {
+            let wave = (self.timer + i as f32 * 0.01).sin();
+
+            instance.rotation = wave * std::f32::consts::PI;
+            instance.scale = Vec2::broadcast(0.75 + wave * 0.25);
+        }
+
+        self.instance_buffer.set_data(ctx, &self.instances, 0);
+
+        Ok(())
+    }
+
     fn draw(&mut self, ctx: &mut DefaultContext) -> firecore_tetra::Result {
         graphics::clear(ctx, Color::rgb(0.094, 0.11, 0.16));
 
-        self.mesh.draw_instanced(ctx, 256, Vec2::new(16.0, 16.0));
+        self.mesh
+            .draw_instanced(ctx, self.instances.len(), Vec2::zero());
 
         Ok(())
     }
@@ -46,6 +82,7 @@ impl State for GameState {
 
 fn main() -> firecore_tetra::Result {
     ContextBuilder::new("Instanced Mesh Rendering", 1280, 720)
+        .quit_on_escape(true)
         .build()?
         .run(GameState::new)
 }
@@ -1,19 +1,27 @@
-use firecore_tetra::graphics::{self, Color, NineSlice, Rectangle, Texture};
+use firecore_tetra::graphics::{self, Color, NineSlice, Rectangle, SliceMode, Texture};
 use firecore_tetra::math::Vec2;
 use firecore_tetra::{DefaultContext, ContextBuilder, State};
 
 struct GameState {
     texture: Texture,
     config: NineSlice,
+    tiled_config: NineSlice,
 }
 
 impl GameState {
     fn new(ctx: &mut DefaultContext) -> firecore_tetra::Result<GameState> {
         let texture = Texture::new(ctx, "./examples/resources/panel.png")?;
 
+        let config = NineSlice::with_border(Rectangle::new(0.0, 0.0, 32.0, 32.0), 4.0);
+
+        // The edges of this panel are repeated rather than stretched, and the center
+        // is left empty, so it can be used as a frame.
+        let tiled_config = config.clone().edge_mode(SliceMode::Tile).draw_center(false);
+
         Ok(GameState {
             texture,
-            config: NineSlice::with_border(Rectangle::new(0.0, 0.0, 32.0, 32.0), 4.0),
+            config,
+            tiled_config,
         })
     }
 }
@@ -25,6 +33,14 @@ impl State for GameState {
         self.texture
             .draw_nine_slice(ctx, &self.config, 640.0, 480.0, Vec2::zero());
 
+        self.texture.draw_nine_slice(
+            ctx,
+            &self.tiled_config,
+            320.0,
+            240.0,
+            Vec2::new(160.0, 120.0),
+        );
+
         Ok(())
     }
 }
@@ -0,0 +1,94 @@
+use std::f32::consts::PI;
+
+use firecore_tetra::graphics::particles::{Curve, EmitterShape, ParticleEmitter, ParticleSettings};
+use firecore_tetra::graphics::{self, Color, Texture};
+use firecore_tetra::input::{self, MouseButton};
+use firecore_tetra::math::Vec2;
+use firecore_tetra::time::Timestep;
+use firecore_tetra::{ContextBuilder, DefaultContext, State};
+
+struct GameState {
+    fountain: ParticleEmitter,
+    sparks: ParticleEmitter,
+}
+
+impl GameState {
+    fn new(ctx: &mut DefaultContext) -> firecore_tetra::Result<GameState> {
+        let texture = Texture::new(ctx, "./examples/resources/block.png")?;
+
+        let mut fountain = ParticleEmitter::new(
+            texture.clone(),
+            ParticleSettings {
+                shape: EmitterShape::Rectangle {
+                    width: 32.0,
+                    height: 0.0,
+                },
+                rate: 120.0,
+                lifetime: 1.5..2.5,
+                speed: 250.0..350.0,
+                direction: -PI / 2.0,
+                spread: 0.4,
+                gravity: Vec2::new(0.0, 300.0),
+                angular_velocity: -4.0..4.0,
+                size: Curve::linear(0.5, 0.1),
+                color: Curve::new(Color::rgb(0.4, 0.7, 1.0))
+                    .with_key(1.0, Color::rgba(1.0, 1.0, 1.0, 0.0)),
+                ..ParticleSettings::default()
+            },
+        );
+
+        fountain.set_position(Vec2::new(320.0, 440.0));
+
+        // This emitter only produces particles when `emit` is called. As the
+        // game uses a fixed timestep and the emitter is seeded, the bursts will
+        // look the same every time the example is run.
+        let mut sparks = ParticleEmitter::with_seed(
+            texture,
+            ParticleSettings {
+                shape: EmitterShape::Circle { radius: 8.0 },
+                lifetime: 0.3..0.8,
+                speed: 100.0..400.0,
+                drag: 3.0,
+                size: Curve::new(0.4).with_key(1.0, 0.0),
+                color: Curve::linear(Color::rgb(1.0, 0.9, 0.3), Color::rgb(1.0, 0.2, 0.0)),
+                ..ParticleSettings::default()
+            },
+            12345,
+        );
+
+        sparks.set_active(false);
+
+        Ok(GameState { fountain, sparks })
+    }
+}
+
+impl State for GameState {
+    fn update(&mut self, ctx: &mut DefaultContext) -> firecore_tetra::Result {
+        if input::is_mouse_button_pressed(ctx, MouseButton::Left) {
+            self.sparks.set_position(input::get_mouse_position(ctx));
+            self.sparks.emit(100);
+        }
+
+        self.fountain.update(ctx);
+        self.sparks.update(ctx);
+
+        Ok(())
+    }
+
+    fn draw(&mut self, ctx: &mut DefaultContext) -> firecore_tetra::Result {
+        graphics::clear(ctx, Color::rgb(0.094, 0.11, 0.16));
+
+        self.fountain.draw(ctx);
+        self.sparks.draw(ctx);
+
+        Ok(())
+    }
+}
+
+fn main() -> firecore_tetra::Result {
+    ContextBuilder::new("Particles (click to emit a burst)", 640, 480)
+        .timestep(Timestep::Fixed(60.0))
+        .quit_on_escape(true)
+        .build()?
+        .run(GameState::new)
+}
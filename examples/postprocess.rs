@@ -0,0 +1,109 @@
+use firecore_tetra::graphics::postprocess::PostProcess;
+use firecore_tetra::graphics::{self, Color, DrawParams, Shader, Texture};
+use firecore_tetra::input::{self, Key};
+use firecore_tetra::math::Vec2;
+use firecore_tetra::{ContextBuilder, DefaultContext, Event, State};
+
+struct GameState {
+    texture: Texture,
+    post: PostProcess,
+    grayscale: usize,
+    vignette: usize,
+    timer: f32,
+}
+
+impl GameState {
+    fn new(ctx: &mut DefaultContext) -> firecore_tetra::Result<GameState> {
+        let texture = Texture::new(ctx, "./examples/resources/player.png")?;
+
+        // The scene canvas is multisampled, and will be resolved automatically
+        // before the passes are run.
+        let mut post = PostProcess::with_window_size(ctx, 4)?;
+
+        let grayscale = post.add_pass(Shader::from_fragment_file(
+            ctx,
+            "./examples/resources/grayscale.frag",
+        )?);
+
+        let vignette = post.add_pass(Shader::from_fragment_file(
+            ctx,
+            "./examples/resources/vignette.frag",
+        )?);
+
+        post.set_uniform(vignette, "u_radius", 0.75f32);
+
+        Ok(GameState {
+            texture,
+            post,
+            grayscale,
+            vignette,
+            timer: 0.0,
+        })
+    }
+}
+
+impl State for GameState {
+    fn update(&mut self, ctx: &mut DefaultContext) -> firecore_tetra::Result {
+        self.timer += 1.0;
+
+        if input::is_key_pressed(ctx, Key::Num1) {
+            let enabled = self.post.is_pass_enabled(self.grayscale);
+            self.post.set_pass_enabled(self.grayscale, !enabled);
+        }
+
+        if input::is_key_pressed(ctx, Key::Num2) {
+            let enabled = self.post.is_pass_enabled(self.vignette);
+            self.post.set_pass_enabled(self.vignette, !enabled);
+        }
+
+        // Uniforms are stored per-pass, and applied every time the chain is drawn.
+        let strength = ((self.timer / 60.0).sin() + 1.0) / 2.0;
+        self.post
+            .set_uniform(self.grayscale, "u_strength", strength);
+
+        Ok(())
+    }
+
+    fn draw(&mut self, ctx: &mut DefaultContext) -> firecore_tetra::Result {
+        graphics::set_canvas(ctx, self.post.canvas());
+        graphics::clear(ctx, Color::rgb(0.392, 0.584, 0.929));
+
+        let (width, height) = self.post.canvas().size();
+
+        self.texture.draw(
+            ctx,
+            DrawParams::new()
+                .position(Vec2::new(width as f32 / 2.0, height as f32 / 2.0))
+                .origin(Vec2::new(8.0, 8.0))
+                .rotation(self.timer / 60.0)
+                .scale(Vec2::new(16.0, 16.0)),
+        );
+
+        graphics::reset_canvas(ctx);
+        graphics::clear(ctx, Color::BLACK);
+
+        self.post.draw(ctx, DrawParams::new());
+
+        Ok(())
+    }
+
+    fn event(&mut self, ctx: &mut DefaultContext, event: Event) -> firecore_tetra::Result {
+        if let Event::Resized { width, height } = event {
+            self.post.set_size(ctx, width, height)?;
+        }
+
+        Ok(())
+    }
+}
+
+fn main() -> firecore_tetra::Result {
+    ContextBuilder::new(
+        "Post-Processing (press 1 and 2 to toggle passes)",
+        1280,
+        720,
+    )
+    .resizable(true)
+    .quit_on_escape(true)
+    .build()?
+    .run(GameState::new)
+}
@@ -0,0 +1,69 @@
+use firecore_tetra::graphics::{self, Canvas, Color, DrawParams, Shader, Texture, TextureFormat};
+use firecore_tetra::math::Vec2;
+use firecore_tetra::{ContextBuilder, DefaultContext, State};
+
+struct GameState {
+    texture: Texture,
+    shader: Shader,
+    canvas: Canvas,
+    rotation: f32,
+}
+
+impl GameState {
+    fn new(ctx: &mut DefaultContext) -> firecore_tetra::Result<GameState> {
+        // The canvas' main texture stores the color of the scene, and the additional
+        // attachment stores the bright areas, which could be used for a bloom effect.
+        let canvas = Canvas::builder(320, 240)
+            .add_attachment(TextureFormat::Rgba16F)
+            .build(ctx)?;
+
+        Ok(GameState {
+            texture: Texture::new(ctx, "./examples/resources/player.png")?,
+            shader: Shader::from_fragment_file(ctx, "./examples/resources/mrt.frag")?,
+            canvas,
+            rotation: 0.0,
+        })
+    }
+}
+
+impl State for GameState {
+    fn update(&mut self, _: &mut DefaultContext) -> firecore_tetra::Result {
+        self.rotation += 0.02;
+
+        Ok(())
+    }
+
+    fn draw(&mut self, ctx: &mut DefaultContext) -> firecore_tetra::Result {
+        graphics::set_canvas(ctx, &self.canvas);
+        graphics::clear(ctx, Color::rgb(0.2, 0.2, 0.3));
+
+        // A single draw call writes to both attachments:
+        graphics::set_shader(ctx, &self.shader);
+
+        self.texture.draw(
+            ctx,
+            DrawParams::new()
+                .position(Vec2::new(160.0, 120.0))
+                .origin(Vec2::new(8.0, 8.0))
+                .rotation(self.rotation)
+                .scale(Vec2::new(8.0, 8.0)),
+        );
+
+        graphics::reset_shader(ctx);
+        graphics::reset_canvas(ctx);
+
+        graphics::clear(ctx, Color::BLACK);
+
+        self.canvas.attachment(0).draw(ctx, Vec2::new(0.0, 120.0));
+        self.canvas.attachment(1).draw(ctx, Vec2::new(320.0, 120.0));
+
+        Ok(())
+    }
+}
+
+fn main() -> firecore_tetra::Result {
+    ContextBuilder::new("Multiple Render Targets", 640, 480)
+        .quit_on_escape(true)
+        .build()?
+        .run(GameState::new)
+}
@@ -0,0 +1,16 @@
+#version 150
+
+in vec2 v_uv;
+in vec4 v_color;
+
+uniform sampler2D u_texture;
+uniform float u_strength;
+
+out vec4 o_color;
+
+void main() {
+    vec4 color = texture(u_texture, v_uv);
+    float luma = dot(color.rgb, vec3(0.299, 0.587, 0.114));
+
+    o_color = v_color * vec4(mix(color.rgb, vec3(luma), u_strength), color.a);
+}
@@ -0,0 +1,20 @@
+#version 150
+#extension GL_ARB_explicit_attrib_location : require
+
+in vec2 v_uv;
+in vec4 v_color;
+
+uniform sampler2D u_texture;
+
+layout(location = 0) out vec4 o_color;
+layout(location = 1) out vec4 o_emissive;
+
+void main() {
+    vec4 color = v_color * texture(u_texture, v_uv);
+
+    o_color = color;
+
+    // Only the brightest parts of the image are written to the second attachment.
+    float brightness = dot(color.rgb, vec3(0.299, 0.587, 0.114));
+    o_emissive = vec4(color.rgb * step(0.6, brightness), color.a);
+}
@@ -0,0 +1,16 @@
+#version 150
+
+in vec2 v_uv;
+in vec4 v_color;
+
+uniform sampler2D u_texture;
+uniform float u_radius;
+
+out vec4 o_color;
+
+void main() {
+    vec4 color = texture(u_texture, v_uv);
+    float vignette = smoothstep(u_radius, u_radius - 0.4, distance(v_uv, vec2(0.5)));
+
+    o_color = v_color * vec4(color.rgb * vignette, color.a);
+}
@@ -0,0 +1,20 @@
+#version 150
+
+in vec2 a_position;
+in vec4 a_color;
+in float a_phase;
+
+uniform mat4 u_projection;
+uniform float u_time;
+
+out vec2 v_uv;
+out vec4 v_color;
+
+void main() {
+    v_color = a_color;
+    v_uv = vec2(0.0, 0.0);
+
+    float offset = sin(u_time * 3.0 + a_phase) * 24.0;
+
+    gl_Position = u_projection * vec4(a_position.x, a_position.y + offset, 0.0, 1.0);
+}
@@ -1,11 +1,14 @@
-use firecore_tetra::graphics::mesh::{GeometryBuilder, Mesh, ShapeStyle};
-use firecore_tetra::graphics::{self, Color};
+use firecore_tetra::graphics::mesh::{
+    GeometryBuilder, Gradient, LineCap, Mesh, ShapeStyle, StrokeOptions,
+};
+use firecore_tetra::graphics::{self, Color, Curve, Rectangle};
 use firecore_tetra::math::Vec2;
 use firecore_tetra::{DefaultContext, ContextBuilder, State};
 
 struct GameState {
     simple: Mesh,
     complex: Mesh,
+    health_bar: Gradient,
 }
 
 impl GameState {
@@ -25,9 +28,32 @@ impl GameState {
             .circle(ShapeStyle::Fill, Vec2::new(-16.0, -16.0), 8.0)?
             .circle(ShapeStyle::Fill, Vec2::new(16.0, -16.0), 8.0)?
             .polyline(8.0, &[Vec2::new(-16.0, 24.0), Vec2::new(16.0, 24.0)])?
+            // Outline - strokes can be customized via `StrokeOptions`.
+            .set_stroke_options(
+                StrokeOptions::new()
+                    .line_cap(LineCap::Round)
+                    .dashes(&[12.0, 8.0], 0.0),
+            )
+            .circle(ShapeStyle::Stroke(4.0), Vec2::zero(), 72.0)?
             .build_mesh(ctx)?;
 
-        Ok(GameState { simple, complex })
+        // Gradients can be used to color shapes - they can be applied to a `GeometryBuilder`
+        // via `set_gradient`, or to the `graphics::draw_*` functions via `set_shape_gradient`.
+        // Subdividing the shape makes sure that every color in the gradient is visible.
+        let health_bar = Gradient::linear(
+            Vec2::new(32.0, 0.0),
+            Vec2::new(352.0, 0.0),
+            Curve::new(Color::RED)
+                .with_key(0.5, Color::rgb(1.0, 1.0, 0.0))
+                .with_key(1.0, Color::GREEN),
+        )
+        .with_subdivision(32.0);
+
+        Ok(GameState {
+            simple,
+            complex,
+            health_bar,
+        })
     }
 }
 
@@ -38,6 +64,45 @@ impl State for GameState {
         self.simple.draw(ctx, Vec2::new(64.0, 64.0));
         self.complex.draw(ctx, Vec2::new(256.0, 64.0));
 
+        // For shapes that change every frame, the `graphics::draw_*` functions can be used
+        // instead. These are added to the same batch as sprites, rather than creating a new
+        // mesh each time.
+        graphics::draw_rect(
+            ctx,
+            ShapeStyle::Stroke(4.0),
+            Rectangle::new(32.0, 192.0, 320.0, 160.0),
+            Color::WHITE,
+        )?;
+
+        graphics::draw_line(
+            ctx,
+            Vec2::new(32.0, 192.0),
+            Vec2::new(352.0, 352.0),
+            2.0,
+            Color::BLACK,
+        )?;
+
+        for i in 0..8 {
+            graphics::draw_circle(
+                ctx,
+                ShapeStyle::Fill,
+                Vec2::new(48.0 + i as f32 * 40.0, 400.0),
+                4.0 + i as f32 * 2.0,
+                Color::rgb(1.0, i as f32 / 8.0, 0.0),
+            )?;
+        }
+
+        graphics::set_shape_gradient(ctx, self.health_bar.clone());
+
+        graphics::draw_rect(
+            ctx,
+            ShapeStyle::Fill,
+            Rectangle::new(32.0, 448.0, 320.0, 32.0),
+            Color::WHITE,
+        )?;
+
+        graphics::reset_shape_gradient(ctx);
+
         Ok(())
     }
 }
@@ -0,0 +1,137 @@
+use firecore_tetra::graphics::{
+    self, CameraFollow, CameraView, Color, DrawParams, Rectangle, Texture,
+};
+use firecore_tetra::input::{self, Key};
+use firecore_tetra::math::Vec2;
+use firecore_tetra::{ContextBuilder, DefaultContext, State};
+
+const MOVEMENT_SPEED: f32 = 4.0;
+
+struct Player {
+    position: Vec2<f32>,
+    color: Color,
+    keys: [Key; 4],
+    view: CameraView,
+}
+
+impl Player {
+    fn new(position: Vec2<f32>, color: Color, keys: [Key; 4], viewport: Rectangle<i32>) -> Player {
+        let mut view = CameraView::new(viewport);
+        view.camera.position = position;
+        view.camera
+            .set_follow_settings(CameraFollow::new().smoothing(0.1));
+
+        Player {
+            position,
+            color,
+            keys,
+            view,
+        }
+    }
+
+    fn update(&mut self, ctx: &mut DefaultContext) {
+        let [up, down, left, right] = self.keys;
+
+        if input::is_key_down(ctx, up) {
+            self.position.y -= MOVEMENT_SPEED;
+        }
+
+        if input::is_key_down(ctx, down) {
+            self.position.y += MOVEMENT_SPEED;
+        }
+
+        if input::is_key_down(ctx, left) {
+            self.position.x -= MOVEMENT_SPEED;
+        }
+
+        if input::is_key_down(ctx, right) {
+            self.position.x += MOVEMENT_SPEED;
+        }
+
+        self.view.camera.set_follow_target(self.position);
+        self.view.camera.update();
+    }
+}
+
+struct GameState {
+    texture: Texture,
+    players: Vec<Player>,
+}
+
+impl GameState {
+    fn new(ctx: &mut DefaultContext) -> firecore_tetra::Result<GameState> {
+        Ok(GameState {
+            texture: Texture::new(ctx, "./examples/resources/player.png")?,
+            players: vec![
+                Player::new(
+                    Vec2::new(-64.0, 0.0),
+                    Color::rgb(1.0, 0.5, 0.5),
+                    [Key::W, Key::S, Key::A, Key::D],
+                    Rectangle::new(0, 0, 319, 480),
+                ),
+                Player::new(
+                    Vec2::new(64.0, 0.0),
+                    Color::rgb(0.5, 0.5, 1.0),
+                    [Key::Up, Key::Down, Key::Left, Key::Right],
+                    Rectangle::new(321, 0, 319, 480),
+                ),
+            ],
+        })
+    }
+
+    fn draw_scene(&self, ctx: &mut DefaultContext) {
+        // Draw a grid, so that the movement of the cameras is visible:
+        for x in -8..8 {
+            for y in -8..8 {
+                self.texture.draw(
+                    ctx,
+                    DrawParams::new()
+                        .position(Vec2::new(x as f32 * 64.0, y as f32 * 64.0))
+                        .color(Color::rgba(1.0, 1.0, 1.0, 0.2)),
+                );
+            }
+        }
+
+        for player in &self.players {
+            self.texture.draw(
+                ctx,
+                DrawParams::new()
+                    .position(player.position)
+                    .origin(Vec2::new(8.0, 8.0))
+                    .scale(Vec2::new(2.0, 2.0))
+                    .color(player.color),
+            );
+        }
+    }
+}
+
+impl State for GameState {
+    fn update(&mut self, ctx: &mut DefaultContext) -> firecore_tetra::Result {
+        for player in &mut self.players {
+            player.update(ctx);
+        }
+
+        Ok(())
+    }
+
+    fn draw(&mut self, ctx: &mut DefaultContext) -> firecore_tetra::Result {
+        graphics::clear(ctx, Color::BLACK);
+
+        // Each player's view is drawn to a different part of the window, using
+        // their own camera:
+        for player in &self.players {
+            player.view.begin(ctx);
+            self.draw_scene(ctx);
+            player.view.end(ctx);
+        }
+
+        Ok(())
+    }
+}
+
+fn main() -> firecore_tetra::Result {
+    ContextBuilder::new("Split-Screen", 640, 480)
+        .quit_on_escape(true)
+        .build()?
+        .run(GameState::new)
+}
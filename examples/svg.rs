@@ -0,0 +1,64 @@
+use firecore_tetra::graphics::mesh::{Mesh, Path, ShapeStyle};
+use firecore_tetra::graphics::{self, Color, DrawParams};
+use firecore_tetra::math::Vec2;
+use firecore_tetra::{ContextBuilder, DefaultContext, State};
+
+struct GameState {
+    icon: Mesh,
+    heart: Path,
+    wave: Path,
+}
+
+impl GameState {
+    fn new(ctx: &mut DefaultContext) -> firecore_tetra::Result<GameState> {
+        // Whole SVG documents can be imported as a mesh (this requires the `svg` feature).
+        let icon = Mesh::from_svg_file(ctx, "./examples/resources/icon.svg")?;
+
+        // Paths can be built from lines, curves and arcs...
+        let heart = Path::builder()
+            .move_to(Vec2::new(544.0, 144.0))
+            .cubic_to(
+                Vec2::new(496.0, 112.0),
+                Vec2::new(520.0, 72.0),
+                Vec2::new(544.0, 100.0),
+            )
+            .cubic_to(
+                Vec2::new(568.0, 72.0),
+                Vec2::new(592.0, 112.0),
+                Vec2::new(544.0, 144.0),
+            )
+            .close()
+            .build();
+
+        // ...or parsed from SVG path data.
+        let wave = Path::from_svg("M 64 400 q 64 -64 128 0 t 128 0 t 128 0 t 128 0")?;
+
+        Ok(GameState { icon, heart, wave })
+    }
+}
+
+impl State for GameState {
+    fn draw(&mut self, ctx: &mut DefaultContext) -> firecore_tetra::Result {
+        graphics::clear(ctx, Color::rgb(0.392, 0.584, 0.929));
+
+        self.icon.draw(ctx, Vec2::new(64.0, 64.0));
+        self.icon.draw(
+            ctx,
+            DrawParams::new()
+                .position(Vec2::new(256.0, 64.0))
+                .scale(Vec2::new(2.0, 2.0)),
+        );
+
+        graphics::draw_path(ctx, ShapeStyle::Fill, &self.heart, Color::RED)?;
+        graphics::draw_path(ctx, ShapeStyle::Stroke(8.0), &self.wave, Color::WHITE)?;
+
+        Ok(())
+    }
+}
+
+fn main() -> firecore_tetra::Result {
+    ContextBuilder::new("SVG Paths", 640, 480)
+        .quit_on_escape(true)
+        .build()?
+        .run(GameState::new)
+}
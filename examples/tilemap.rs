@@ -0,0 +1,117 @@
+use firecore_tetra::graphics::mesh::{GeometryBuilder, Mesh, ShapeStyle};
+use firecore_tetra::graphics::tilemap::{ObjectShape, PropertyValue, Tilemap};
+use firecore_tetra::graphics::{self, Camera, Color, DrawParams};
+use firecore_tetra::input::{self, Key};
+use firecore_tetra::{ContextBuilder, DefaultContext, State};
+
+const MOVEMENT_SPEED: f32 = 4.0;
+
+struct GameState {
+    map: Tilemap,
+    objects: Mesh,
+    camera: Camera,
+}
+
+impl GameState {
+    fn new(ctx: &mut DefaultContext) -> firecore_tetra::Result<GameState> {
+        let map = Tilemap::new(ctx, "./examples/resources/tilemap.tmx")?;
+
+        // Object layers are just data - here we're drawing outlines for them,
+        // but in a real game you might use them for collision or spawning entities.
+        let mut builder = GeometryBuilder::new();
+
+        for object in map.data().objects() {
+            let color = match object.properties.get("damage") {
+                Some(PropertyValue::Int(_)) => Color::RED,
+                _ => Color::BLUE,
+            };
+
+            builder.set_color(color);
+
+            match &object.shape {
+                ObjectShape::Rectangle(rect) => {
+                    builder.rectangle(ShapeStyle::Stroke(1.0), *rect)?;
+                }
+                ObjectShape::Ellipse(bounds) => {
+                    builder.ellipse(
+                        ShapeStyle::Stroke(1.0),
+                        bounds.center(),
+                        [bounds.width / 2.0, bounds.height / 2.0].into(),
+                    )?;
+                }
+                ObjectShape::Point(point) => {
+                    builder.circle(ShapeStyle::Fill, *point, 4.0)?;
+                }
+                ObjectShape::Polygon(points) => {
+                    builder.polygon(ShapeStyle::Stroke(1.0), points)?;
+                }
+                ObjectShape::Polyline(points) => {
+                    builder.polyline(1.0, points)?;
+                }
+            }
+        }
+
+        let spawn = map
+            .data()
+            .objects()
+            .find(|o| o.name == "spawn")
+            .map(|o| o.position)
+            .unwrap_or_default();
+
+        let mut camera = Camera::with_window_size(ctx);
+        camera.position = spawn;
+
+        Ok(GameState {
+            objects: builder.build_mesh(ctx)?,
+            map,
+            camera,
+        })
+    }
+}
+
+impl State for GameState {
+    fn update(&mut self, ctx: &mut DefaultContext) -> firecore_tetra::Result {
+        if input::is_key_down(ctx, Key::W) {
+            self.camera.position.y -= MOVEMENT_SPEED;
+        }
+
+        if input::is_key_down(ctx, Key::S) {
+            self.camera.position.y += MOVEMENT_SPEED;
+        }
+
+        if input::is_key_down(ctx, Key::A) {
+            self.camera.position.x -= MOVEMENT_SPEED;
+        }
+
+        if input::is_key_down(ctx, Key::D) {
+            self.camera.position.x += MOVEMENT_SPEED;
+        }
+
+        self.camera.update();
+        self.map.advance(ctx);
+
+        Ok(())
+    }
+
+    fn draw(&mut self, ctx: &mut DefaultContext) -> firecore_tetra::Result {
+        let background = self.map.data().background_color.unwrap_or(Color::BLACK);
+
+        graphics::clear(ctx, background);
+        graphics::set_transform_matrix(ctx, self.camera.as_matrix());
+
+        // Only the chunks of the map that the camera can see will be drawn:
+        self.map.draw(ctx, self.camera.visible_rect());
+        self.objects.draw(ctx, DrawParams::new());
+
+        graphics::reset_transform_matrix(ctx);
+
+        Ok(())
+    }
+}
+
+fn main() -> firecore_tetra::Result {
+    ContextBuilder::new("Rendering a Tiled map", 640, 480)
+        .quit_on_escape(true)
+        .build()?
+        .run(GameState::new)
+}
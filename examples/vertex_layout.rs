@@ -0,0 +1,114 @@
+use bytemuck::{Pod, Zeroable};
+use firecore_tetra::graphics::mesh::{
+    BufferUsage, IndexBuffer, Mesh, VertexAttribute, VertexAttributeType, VertexBuffer,
+    VertexLayout,
+};
+use firecore_tetra::graphics::{self, Color, DrawParams, Shader};
+use firecore_tetra::{ContextBuilder, DefaultContext, State};
+
+const COLUMNS: usize = 64;
+
+// Custom vertex types must be `#[repr(C)]`, so that their layout is predictable.
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+struct WaveVertex {
+    position: [f32; 2],
+    color: [u8; 4],
+    phase: f32,
+}
+
+unsafe impl Pod for WaveVertex {}
+unsafe impl Zeroable for WaveVertex {}
+
+fn wave_layout() -> VertexLayout {
+    VertexLayout::new(std::mem::size_of::<WaveVertex>())
+        .with_attribute(VertexAttribute::new(
+            "a_position",
+            2,
+            VertexAttributeType::F32,
+            0,
+        ))
+        .with_attribute(
+            VertexAttribute::new("a_color", 4, VertexAttributeType::U8, 8).normalized(true),
+        )
+        .with_attribute(VertexAttribute::new(
+            "a_phase",
+            1,
+            VertexAttributeType::F32,
+            12,
+        ))
+}
+
+struct GameState {
+    mesh: Mesh,
+    shader: Shader,
+}
+
+impl GameState {
+    fn new(ctx: &mut DefaultContext) -> firecore_tetra::Result<GameState> {
+        let mut vertices = Vec::with_capacity(COLUMNS * 2);
+        let mut indices = Vec::with_capacity((COLUMNS - 1) * 6);
+
+        for i in 0..COLUMNS {
+            let t = i as f32 / (COLUMNS - 1) as f32;
+            let x = 64.0 + t * (1280.0 - 128.0);
+            let color = [(t * 255.0) as u8, 128, 255 - (t * 255.0) as u8, 255];
+            let phase = t * std::f32::consts::PI * 4.0;
+
+            vertices.push(WaveVertex {
+                position: [x, 300.0],
+                color,
+                phase,
+            });
+
+            vertices.push(WaveVertex {
+                position: [x, 420.0],
+                color: [255, 255, 255, 255],
+                phase,
+            });
+
+            if i > 0 {
+                let top_left = (i as u32 - 1) * 2;
+
+                indices.extend_from_slice(&[
+                    top_left,
+                    top_left + 1,
+                    top_left + 2,
+                    top_left + 2,
+                    top_left + 1,
+                    top_left + 3,
+                ]);
+            }
+        }
+
+        let vertex_buffer =
+            VertexBuffer::with_layout(ctx, &vertices, wave_layout(), BufferUsage::Static)?;
+
+        let mut mesh = Mesh::indexed(vertex_buffer, IndexBuffer::new(ctx, &indices)?);
+        mesh.set_backface_culling(false);
+
+        Ok(GameState {
+            mesh,
+            shader: Shader::from_vertex_file(ctx, "./examples/resources/wave.vert")?,
+        })
+    }
+}
+
+impl State for GameState {
+    fn draw(&mut self, ctx: &mut DefaultContext) -> firecore_tetra::Result {
+        graphics::clear(ctx, Color::rgb(0.094, 0.11, 0.16));
+
+        graphics::set_shader(ctx, &self.shader);
+        self.mesh.draw(ctx, DrawParams::new());
+        graphics::reset_shader(ctx);
+
+        Ok(())
+    }
+}
+
+fn main() -> firecore_tetra::Result {
+    ContextBuilder::new("Custom Vertex Layouts", 1280, 720)
+        .quit_on_escape(true)
+        .build()?
+        .run(GameState::new)
+}
@@ -0,0 +1,73 @@
+use firecore_tetra::graphics::{self, Color, DrawParams, FilterMode, Rectangle, Texture, WrapMode};
+use firecore_tetra::input::{self, Key};
+use firecore_tetra::math::Vec2;
+use firecore_tetra::{ContextBuilder, DefaultContext, State};
+
+const SCROLL_SPEED: f32 = 2.0;
+
+struct GameState {
+    texture: Texture,
+    offset: Vec2<f32>,
+    zoom: f32,
+}
+
+impl GameState {
+    fn new(ctx: &mut DefaultContext) -> firecore_tetra::Result<GameState> {
+        let mut texture = Texture::new(ctx, "./examples/resources/block.png")?;
+
+        // Repeating the texture on the horizontal axis and mirroring it on the
+        // vertical axis means that any region of it can be drawn, even if it
+        // goes outside the texture's bounds.
+        texture.set_wrap_mode(ctx, WrapMode::Repeat, WrapMode::MirroredRepeat);
+
+        // Mipmaps stop the pattern from shimmering when zoomed out:
+        texture.generate_mipmaps(ctx);
+        texture.set_filter_mode(ctx, FilterMode::NearestMipmap);
+
+        Ok(GameState {
+            texture,
+            offset: Vec2::zero(),
+            zoom: 4.0,
+        })
+    }
+}
+
+impl State for GameState {
+    fn update(&mut self, ctx: &mut DefaultContext) -> firecore_tetra::Result {
+        self.offset += Vec2::new(SCROLL_SPEED, SCROLL_SPEED / 2.0) / self.zoom;
+
+        if input::is_key_down(ctx, Key::Up) {
+            self.zoom = (self.zoom * 1.02).min(8.0);
+        }
+
+        if input::is_key_down(ctx, Key::Down) {
+            self.zoom = (self.zoom / 1.02).max(0.1);
+        }
+
+        Ok(())
+    }
+
+    fn draw(&mut self, ctx: &mut DefaultContext) -> firecore_tetra::Result {
+        graphics::clear(ctx, Color::BLACK);
+
+        self.texture.draw_region(
+            ctx,
+            Rectangle::new(
+                self.offset.x,
+                self.offset.y,
+                640.0 / self.zoom,
+                480.0 / self.zoom,
+            ),
+            DrawParams::new().scale(Vec2::new(self.zoom, self.zoom)),
+        );
+
+        Ok(())
+    }
+}
+
+fn main() -> firecore_tetra::Result {
+    ContextBuilder::new("Wrap Modes (press up/down to zoom)", 640, 480)
+        .quit_on_escape(true)
+        .build()?
+        .run(GameState::new)
+}
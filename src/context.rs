@@ -215,6 +215,8 @@ where
         let diff_time = curr_time - last_time;
         last_time = curr_time;
 
+        ctx.time.elapsed_time += diff_time;
+
         // Since we fill the buffer when we create the context, we can cycle it
         // here and it shouldn't reallocate.
         ctx.time.fps_tracker.pop_front();
@@ -249,6 +251,8 @@ where
 
         graphics::present(ctx);
 
+        ctx.time.frame_count += 1;
+
         // This provides a sensible FPS limit when running without vsync, and
         // avoids CPU usage skyrocketing on some systems.
         thread::sleep(Duration::from_millis(1));
@@ -287,6 +291,7 @@ pub struct ContextBuilder {
     pub(crate) borderless: bool,
     pub(crate) multisampling: u8,
     pub(crate) stencil_buffer: bool,
+    pub(crate) depth_buffer: bool,
     pub(crate) high_dpi: bool,
     pub(crate) screen_saver_enabled: bool,
     pub(crate) key_repeat: bool,
@@ -416,6 +421,20 @@ impl ContextBuilder {
         self
     }
 
+    /// Sets whether or not the window should have a depth buffer.
+    ///
+    /// If this is enabled, you can use [`graphics::set_depth_state`](crate::graphics::set_depth_state)
+    /// when rendering to the main backbuffer.
+    ///
+    /// Note that this setting only applies to the main backbuffer - to create a canvas with
+    /// a depth buffer, use [`Canvas::builder`](crate::graphics::Canvas::builder).
+    ///
+    /// Defaults to `false`.
+    pub fn depth_buffer(&mut self, depth_buffer: bool) -> &mut ContextBuilder {
+        self.depth_buffer = depth_buffer;
+        self
+    }
+
     /// Sets whether or not the window should use a high-DPI backbuffer, on platforms
     /// that support it (e.g. MacOS with a retina display).
     ///
@@ -535,6 +554,7 @@ impl Default for ContextBuilder {
             borderless: false,
             multisampling: 0,
             stencil_buffer: false,
+            depth_buffer: false,
             high_dpi: false,
             screen_saver_enabled: false,
             key_repeat: false,
@@ -73,6 +73,16 @@ pub enum TetraError {
 
     /// Returned when a shape cannot be tessellated.
     TessellationError(TessellationError),
+
+    /// Returned when a tilemap's data is invalid or unsupported.
+    InvalidTilemap(String),
+
+    /// Returned when trying to access a shader uniform that does not exist, or
+    /// that has a different type to the provided value.
+    InvalidUniform(String),
+
+    /// Returned when SVG path data or an SVG document is invalid or unsupported.
+    InvalidSvg(String),
 }
 
 impl Display for TetraError {
@@ -98,6 +108,9 @@ impl Display for TetraError {
             TetraError::TessellationError(_) => {
                 write!(f, "An error occurred while tessellating a shape")
             }
+            TetraError::InvalidTilemap(_) => write!(f, "Invalid tilemap data"),
+            TetraError::InvalidUniform(_) => write!(f, "Invalid shader uniform"),
+            TetraError::InvalidSvg(_) => write!(f, "Invalid SVG data"),
         }
     }
 }
@@ -120,6 +133,9 @@ impl Error for TetraError {
             // This should return the inner error, but Lyon doesn't implement Error for some reason,
             // so we can't :(
             TetraError::TessellationError(_) => None,
+            TetraError::InvalidTilemap(_) => None,
+            TetraError::InvalidUniform(_) => None,
+            TetraError::InvalidSvg(_) => None,
         }
     }
 }
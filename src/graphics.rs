@@ -9,28 +9,36 @@ pub mod animation;
 mod camera;
 mod canvas;
 mod color;
+mod curve;
 mod drawparams;
 pub mod mesh;
+pub mod particles;
+pub mod postprocess;
 mod rectangle;
+mod rng;
 pub mod scaling;
 mod shader;
+mod shapes;
 pub mod text;
+pub mod tilemap;
 mod texture;
 
 pub use camera::*;
 pub use canvas::*;
 pub use color::*;
+pub use curve::*;
 pub use drawparams::*;
 pub use rectangle::*;
 pub use shader::*;
+pub use shapes::*;
 pub use texture::*;
 
 use crate::math::{FrustumPlanes, Mat4, Vec2};
 use crate::platform::{GraphicsDevice, RawIndexBuffer, RawVertexBuffer};
-use crate::window;
+use crate::{time, window};
 use crate::{context::Context, error::Result};
 
-use self::mesh::{BufferUsage, Vertex, VertexWinding};
+use self::mesh::{BufferUsage, GeometryBuilder, Vertex, VertexWinding};
 
 const MAX_SPRITES: usize = 2048;
 const MAX_VERTICES: usize = MAX_SPRITES * 4; // Cannot be greater than 32767!
@@ -65,14 +73,24 @@ pub(crate) struct GraphicsContext {
 
     shader: ActiveShader,
     default_shader: Shader,
+    default_instanced_shader: Shader,
 
     canvas: ActiveCanvas,
 
+    viewport: Option<Rectangle<i32>>,
     projection_matrix: Mat4<f32>,
     transform_matrix: Mat4<f32>,
+    transform_stack: Vec<Mat4<f32>>,
+
+    scissor: Option<Rectangle<i32>>,
+    scissor_stack: Vec<Option<Rectangle<i32>>>,
+
+    mask_depth: u8,
+    depth_state: DepthState,
 
     vertex_data: Vec<Vertex>,
-    element_count: usize,
+    index_data: Vec<u32>,
+    shape_builder: GeometryBuilder,
 
     blend_mode: BlendMode,
 }
@@ -83,21 +101,19 @@ impl GraphicsContext {
         window_width: i32,
         window_height: i32,
     ) -> Result<GraphicsContext> {
-        let vertex_buffer = device.new_vertex_buffer(MAX_VERTICES, BufferUsage::Dynamic)?;
-        let index_buffer = device.new_index_buffer(MAX_INDICES, BufferUsage::Static)?;
-
-        let indices: Vec<u32> = INDEX_ARRAY
-            .iter()
-            .cycle()
-            .take(MAX_INDICES)
-            .enumerate()
-            .map(|(i, vertex)| vertex + i as u32 / 6 * 4)
-            .collect();
-
-        device.set_index_buffer_data(&index_buffer, &indices, 0);
+        let vertex_buffer =
+            device.new_vertex_buffer(MAX_VERTICES, Vertex::layout(), BufferUsage::Dynamic)?;
+        let index_buffer = device.new_index_buffer(MAX_INDICES, BufferUsage::Dynamic)?;
 
         let default_texture =
-            Texture::with_device(device, 1, 1, &[255, 255, 255, 255], FilterMode::Nearest)?;
+            Texture::with_device(
+                device,
+                1,
+                1,
+                TextureFormat::Rgba8,
+                &[255, 255, 255, 255],
+                FilterMode::Nearest,
+            )?;
 
         let default_filter_mode = FilterMode::Nearest;
 
@@ -107,6 +123,12 @@ impl GraphicsContext {
             shader::DEFAULT_FRAGMENT_SHADER,
         )?;
 
+        let default_instanced_shader = Shader::with_device(
+            device,
+            shader::DEFAULT_INSTANCED_VERTEX_SHADER,
+            shader::DEFAULT_FRAGMENT_SHADER,
+        )?;
+
         Ok(GraphicsContext {
             vertex_buffer,
             index_buffer,
@@ -117,14 +139,24 @@ impl GraphicsContext {
 
             shader: ActiveShader::Default,
             default_shader,
+            default_instanced_shader,
 
             canvas: ActiveCanvas::Window,
 
+            viewport: None,
             projection_matrix: ortho(window_width as f32, window_height as f32, false),
             transform_matrix: Mat4::identity(),
+            transform_stack: Vec::new(),
+
+            scissor: None,
+            scissor_stack: Vec::new(),
+
+            mask_depth: 0,
+            depth_state: DepthState::disabled(),
 
             vertex_data: Vec::with_capacity(MAX_VERTICES),
-            element_count: 0,
+            index_data: Vec::with_capacity(MAX_INDICES),
+            shape_builder: GeometryBuilder::new(),
 
             blend_mode: BlendMode::default(),
         })
@@ -156,60 +188,117 @@ pub fn push_quad(
     //
     // TODO: This function really needs cleaning up before it can be exposed publicly.
 
-    if ctx.graphics.element_count + 6 > MAX_INDICES {
+    if ctx.graphics.vertex_data.len() + 4 > MAX_VERTICES
+        || ctx.graphics.index_data.len() + 6 > MAX_INDICES
+    {
         flush(ctx);
     }
 
-    let mut fx = (x1 - params.origin.x) * params.scale.x;
-    let mut fy = (y1 - params.origin.y) * params.scale.y;
-    let mut fx2 = (x2 - params.origin.x) * params.scale.x;
-    let mut fy2 = (y2 - params.origin.y) * params.scale.y;
+    let scale = params.flipped_scale();
+
+    let mut fx = (x1 - params.origin.x) * scale.x;
+    let mut fy = (y1 - params.origin.y) * scale.y;
+    let mut fx2 = (x2 - params.origin.x) * scale.x;
+    let mut fy2 = (y2 - params.origin.y) * scale.y;
+
+    // The corner colors are specified clockwise from the top left, but the vertices
+    // are pushed counter-clockwise.
+    let [mut c1, mut c4, mut c3, mut c2] = match params.corner_colors {
+        Some(colors) => [
+            params.color * colors[0],
+            params.color * colors[1],
+            params.color * colors[2],
+            params.color * colors[3],
+        ],
+        None => [params.color; 4],
+    };
 
     if fx2 < fx {
         std::mem::swap(&mut fx, &mut fx2);
         std::mem::swap(&mut u1, &mut u2);
+        std::mem::swap(&mut c1, &mut c4);
+        std::mem::swap(&mut c2, &mut c3);
     }
 
     if fy2 < fy {
         std::mem::swap(&mut fy, &mut fy2);
         std::mem::swap(&mut v1, &mut v2);
+        std::mem::swap(&mut c1, &mut c2);
+        std::mem::swap(&mut c4, &mut c3);
     }
 
     // Branching here might be a bit of a premature optimization...
-    let (ox1, oy1, ox2, oy2, ox3, oy3, ox4, oy4) = if params.rotation == 0.0 {
-        (
-            params.position.x + fx,
-            params.position.y + fy,
-            params.position.x + fx,
-            params.position.y + fy2,
-            params.position.x + fx2,
-            params.position.y + fy2,
-            params.position.x + fx2,
-            params.position.y + fy,
-        )
-    } else {
-        let sin = params.rotation.sin();
-        let cos = params.rotation.cos();
-        (
-            params.position.x + (cos * fx) - (sin * fy),
-            params.position.y + (sin * fx) + (cos * fy),
-            params.position.x + (cos * fx) - (sin * fy2),
-            params.position.y + (sin * fx) + (cos * fy2),
-            params.position.x + (cos * fx2) - (sin * fy2),
-            params.position.y + (sin * fx2) + (cos * fy2),
-            params.position.x + (cos * fx2) - (sin * fy),
-            params.position.y + (sin * fx2) + (cos * fy),
-        )
-    };
+    let (ox1, oy1, ox2, oy2, ox3, oy3, ox4, oy4) =
+        if params.rotation == 0.0 && params.skew == Vec2::zero() {
+            (
+                params.position.x + fx,
+                params.position.y + fy,
+                params.position.x + fx,
+                params.position.y + fy2,
+                params.position.x + fx2,
+                params.position.y + fy2,
+                params.position.x + fx2,
+                params.position.y + fy,
+            )
+        } else {
+            let sin = params.rotation.sin();
+            let cos = params.rotation.cos();
+
+            let transform = |x: f32, y: f32| {
+                let sx = x + params.skew.x * y;
+                let sy = y + params.skew.y * x;
+
+                (
+                    params.position.x + (cos * sx) - (sin * sy),
+                    params.position.y + (sin * sx) + (cos * sy),
+                )
+            };
+
+            let (ox1, oy1) = transform(fx, fy);
+            let (ox2, oy2) = transform(fx, fy2);
+            let (ox3, oy3) = transform(fx2, fy2);
+            let (ox4, oy4) = transform(fx2, fy);
+
+            (ox1, oy1, ox2, oy2, ox3, oy3, ox4, oy4)
+        };
+
+    let base = ctx.graphics.vertex_data.len() as u32;
 
     ctx.graphics.vertex_data.extend_from_slice(&[
-        Vertex::new(Vec2::new(ox1, oy1), Vec2::new(u1, v1), params.color),
-        Vertex::new(Vec2::new(ox2, oy2), Vec2::new(u1, v2), params.color),
-        Vertex::new(Vec2::new(ox3, oy3), Vec2::new(u2, v2), params.color),
-        Vertex::new(Vec2::new(ox4, oy4), Vec2::new(u2, v1), params.color),
+        Vertex::new(Vec2::new(ox1, oy1), Vec2::new(u1, v1), c1).with_depth(params.depth),
+        Vertex::new(Vec2::new(ox2, oy2), Vec2::new(u1, v2), c2).with_depth(params.depth),
+        Vertex::new(Vec2::new(ox3, oy3), Vec2::new(u2, v2), c3).with_depth(params.depth),
+        Vertex::new(Vec2::new(ox4, oy4), Vec2::new(u2, v1), c4).with_depth(params.depth),
     ]);
 
-    ctx.graphics.element_count += 6;
+    ctx.graphics
+        .index_data
+        .extend(INDEX_ARRAY.iter().map(|i| base + i));
+}
+
+/// Adds arbitrary indexed geometry to the current batch.
+///
+/// Returns `false` if the geometry is too large to fit into the batch, in which case
+/// it will need to be drawn seperately.
+pub(crate) fn push_geometry(ctx: &mut Context, vertices: &[Vertex], indices: &[u32]) -> bool {
+    if vertices.len() > MAX_VERTICES || indices.len() > MAX_INDICES {
+        return false;
+    }
+
+    if ctx.graphics.vertex_data.len() + vertices.len() > MAX_VERTICES
+        || ctx.graphics.index_data.len() + indices.len() > MAX_INDICES
+    {
+        flush(ctx);
+    }
+
+    let base = ctx.graphics.vertex_data.len() as u32;
+
+    ctx.graphics.vertex_data.extend_from_slice(vertices);
+    ctx.graphics
+        .index_data
+        .extend(indices.iter().map(|i| base + i));
+
+    true
 }
 
 /// Unstable function - Sets current texture
@@ -281,33 +370,30 @@ pub(crate) fn set_canvas_ex(ctx: &mut Context, canvas: ActiveCanvas) {
         resolve_canvas(ctx);
 
         ctx.graphics.canvas = canvas;
+        ctx.graphics.viewport = None;
 
         match &ctx.graphics.canvas {
-            ActiveCanvas::Window => {
-                let (width, height) = window::get_size(ctx);
-                let (physical_width, physical_height) = window::get_physical_size(ctx);
-
-                ctx.graphics.projection_matrix = ortho(width as f32, height as f32, false);
-                ctx.device.viewport(0, 0, physical_width, physical_height);
-
-                ctx.device.set_canvas(None);
-            }
-            ActiveCanvas::User(r) => {
-                let (width, height) = r.size();
-
-                ctx.graphics.projection_matrix = ortho(width as f32, height as f32, true);
-                ctx.device.viewport(0, 0, width, height);
-
-                ctx.device.set_canvas(Some(&r.handle));
-            }
+            ActiveCanvas::Window => ctx.device.set_canvas(None),
+            ActiveCanvas::User(r) => ctx.device.set_canvas(Some(&r.handle)),
         }
+
+        apply_viewport(ctx);
     }
 }
 
 fn resolve_canvas(ctx: &mut Context) {
     if let ActiveCanvas::User(c) = &ctx.graphics.canvas {
-        if c.multisample.is_some() {
-            ctx.device.resolve(&c.handle, &c.texture.data.handle);
+        let textures = std::iter::once(&c.texture).chain(&c.attachments);
+
+        if !c.multisample.is_empty() {
+            let handles: Vec<_> = textures.clone().map(|t| &t.data.handle).collect();
+            ctx.device.resolve(&c.handle, &handles);
+        }
+
+        for texture in textures {
+            if texture.has_mipmaps() {
+                ctx.device.generate_texture_mipmaps(&texture.data.handle);
+            }
         }
     }
 }
@@ -320,6 +406,8 @@ fn resolve_canvas(ctx: &mut Context) {
 /// graphics device.
 pub fn flush(ctx: &mut Context) {
     if !ctx.graphics.vertex_data.is_empty() {
+        let frame = frame_uniforms(ctx);
+
         let texture = match &ctx.graphics.texture {
             ActiveTexture::Default => return,
             ActiveTexture::User(t) => t,
@@ -336,6 +424,7 @@ pub fn flush(ctx: &mut Context) {
             &mut ctx.device,
             ctx.graphics.projection_matrix * ctx.graphics.transform_matrix,
             Color::WHITE,
+            frame,
         );
 
         ctx.device.cull_face(true);
@@ -353,17 +442,20 @@ pub fn flush(ctx: &mut Context) {
             0,
         );
 
+        ctx.device
+            .set_index_buffer_data(&ctx.graphics.index_buffer, &ctx.graphics.index_data, 0);
+
         ctx.device.draw(
             &ctx.graphics.vertex_buffer,
             Some(&ctx.graphics.index_buffer),
             &texture.data.handle,
             &shader.data.handle,
             0,
-            ctx.graphics.element_count,
+            ctx.graphics.index_data.len(),
         );
 
         ctx.graphics.vertex_data.clear();
-        ctx.graphics.element_count = 0;
+        ctx.graphics.index_data.clear();
     }
 }
 
@@ -435,6 +527,29 @@ pub fn reset_transform_matrix(ctx: &mut Context) {
     set_transform_matrix(ctx, Mat4::identity());
 }
 
+/// Pushes a transform matrix onto the transform stack.
+///
+/// The matrix will be combined with the current transform matrix, so that nested
+/// transformations (e.g. for UI widgets inside of a scrolling panel) are applied
+/// relative to their parent. Call [`pop_transform`] to restore the previous
+/// transform matrix.
+pub fn push_transform(ctx: &mut Context, matrix: Mat4<f32>) {
+    let current = ctx.graphics.transform_matrix;
+
+    ctx.graphics.transform_stack.push(current);
+    set_transform_matrix(ctx, current * matrix);
+}
+
+/// Restores the transform matrix that was active before the last call to
+/// [`push_transform`].
+///
+/// If the transform stack is empty, this does nothing.
+pub fn pop_transform(ctx: &mut Context) {
+    if let Some(matrix) = ctx.graphics.transform_stack.pop() {
+        set_transform_matrix(ctx, matrix);
+    }
+}
+
 /// Sets the scissor rectangle.
 ///
 /// While the scissor is enabled, any rendering that falls outside the specified rectangle of
@@ -476,6 +591,7 @@ pub fn set_scissor(ctx: &mut Context, scissor_rect: Rectangle<i32>) {
     }
 
     ctx.device.scissor_test(true);
+    ctx.graphics.scissor = Some(scissor_rect);
 }
 
 /// Disables the scissor rectangle.
@@ -483,6 +599,40 @@ pub fn reset_scissor(ctx: &mut Context) {
     flush(ctx);
 
     ctx.device.scissor_test(false);
+    ctx.graphics.scissor = None;
+}
+
+/// Pushes a scissor rectangle onto the scissor stack.
+///
+/// If a scissor rectangle is already active, the new scissor will be the area where the two
+/// rectangles intersect, so nested UI elements can never draw outside of their parent.
+/// Call [`pop_scissor`] to restore the previous scissor rectangle.
+///
+/// Like [`set_scissor`], the rectangle is not affected by the transform matrix.
+pub fn push_scissor(ctx: &mut Context, scissor_rect: Rectangle<i32>) {
+    let current = ctx.graphics.scissor;
+
+    let scissor_rect = match current {
+        Some(current) => current
+            .intersection(&scissor_rect)
+            .unwrap_or_else(|| Rectangle::new(0, 0, 0, 0)),
+        None => scissor_rect,
+    };
+
+    ctx.graphics.scissor_stack.push(current);
+    set_scissor(ctx, scissor_rect);
+}
+
+/// Restores the scissor rectangle that was active before the last call to
+/// [`push_scissor`].
+///
+/// If the scissor stack is empty, this does nothing.
+pub fn pop_scissor(ctx: &mut Context) {
+    match ctx.graphics.scissor_stack.pop() {
+        Some(Some(scissor_rect)) => set_scissor(ctx, scissor_rect),
+        Some(None) => reset_scissor(ctx),
+        None => {}
+    }
 }
 
 /// Sets the global stencil behavior.
@@ -510,6 +660,196 @@ pub fn clear_stencil(ctx: &mut Context, value: u8) {
     ctx.device.clear_stencil(value);
 }
 
+/// Pushes a mask onto the mask stack.
+///
+/// Anything drawn by the `draw_mask` closure will be written to the stencil buffer
+/// rather than to the screen. Until [`pop_mask`] is called, all drawing will then be
+/// clipped to the area covered by the mask. If a mask is already active, the new mask
+/// will be clipped to it, so masks can be nested (up to a maximum of 255 levels).
+///
+/// The stencil reference values are managed automatically, and the stencil buffer is
+/// cleared when the first mask is pushed. While masks are active, the
+/// [stencil state](set_stencil_state) and [color mask](set_color_mask) should not be
+/// changed. Depth testing is disabled while the mask is being drawn, so masks are
+/// not affected by (and do not write to) the depth buffer.
+///
+/// Like [`set_stencil_state`], this requires the current render target to have a
+/// stencil buffer.
+///
+/// The return value of the closure will be passed through, which can be useful for
+/// propagating errors from drawing operations.
+pub fn push_mask<F, R>(ctx: &mut Context, draw_mask: F) -> R
+where
+    F: FnOnce(&mut Context) -> R,
+{
+    let depth = ctx.graphics.mask_depth;
+
+    if depth == 0 {
+        clear_stencil(ctx, 0);
+    }
+
+    // Only pixels inside of the parent mask will be incremented, so the
+    // new mask is automatically clipped.
+    set_color_mask(ctx, false, false, false, false);
+    disable_depth_for_mask(ctx);
+    set_stencil_state(
+        ctx,
+        StencilState {
+            enabled: true,
+            action: StencilAction::Increment,
+            test: StencilTest::EqualTo,
+            reference_value: depth,
+            write_mask: 0xFF,
+            read_mask: 0xFF,
+        },
+    );
+
+    let result = draw_mask(ctx);
+
+    set_color_mask(ctx, true, true, true, true);
+    ctx.device.set_depth_state(ctx.graphics.depth_state);
+
+    ctx.graphics.mask_depth = depth.saturating_add(1);
+    set_stencil_state(
+        ctx,
+        StencilState::read(StencilTest::EqualTo, ctx.graphics.mask_depth),
+    );
+
+    result
+}
+
+/// Removes the mask that was added by the last call to [`push_mask`], restoring
+/// the previous mask (if there was one).
+///
+/// If the mask stack is empty, this does nothing.
+pub fn pop_mask(ctx: &mut Context) {
+    if ctx.graphics.mask_depth == 0 {
+        return;
+    }
+
+    ctx.graphics.mask_depth -= 1;
+    let depth = ctx.graphics.mask_depth;
+
+    // Any pixels that were covered by the popped mask need to be reset back to the
+    // parent's reference value, so that they don't affect sibling masks that are
+    // pushed later on. This is done by drawing over the entire render target.
+    set_color_mask(ctx, false, false, false, false);
+    disable_depth_for_mask(ctx);
+    set_stencil_state(
+        ctx,
+        StencilState {
+            enabled: true,
+            action: StencilAction::Replace,
+            test: StencilTest::LessThan,
+            reference_value: depth,
+            write_mask: 0xFF,
+            read_mask: 0xFF,
+        },
+    );
+
+    fill_render_target(ctx);
+
+    set_color_mask(ctx, true, true, true, true);
+    ctx.device.set_depth_state(ctx.graphics.depth_state);
+
+    if depth == 0 {
+        set_stencil_state(ctx, StencilState::disabled());
+    } else {
+        set_stencil_state(ctx, StencilState::read(StencilTest::EqualTo, depth));
+    }
+}
+
+/// Disables depth testing and writing while a mask is drawn or reset, as the stencil
+/// is only updated for pixels that pass the depth test. The caller should restore
+/// `ctx.graphics.depth_state` afterwards.
+fn disable_depth_for_mask(ctx: &mut Context) {
+    flush(ctx);
+    ctx.device.set_depth_state(DepthState {
+        enabled: false,
+        test: DepthTest::Always,
+        write: false,
+    });
+}
+
+/// Draws a quad over the entire render target, ignoring the current transform matrix,
+/// scissor rectangle, texture and shader.
+fn fill_render_target(ctx: &mut Context) {
+    flush(ctx);
+
+    let (width, height) = match &ctx.graphics.canvas {
+        ActiveCanvas::Window => window::get_size(ctx),
+        ActiveCanvas::User(c) => c.size(),
+    };
+
+    let default_texture = ctx.graphics.default_texture.clone();
+    let texture = std::mem::replace(
+        &mut ctx.graphics.texture,
+        ActiveTexture::User(default_texture),
+    );
+    let shader = std::mem::replace(&mut ctx.graphics.shader, ActiveShader::Default);
+    let transform_matrix = std::mem::replace(&mut ctx.graphics.transform_matrix, Mat4::identity());
+
+    if ctx.graphics.scissor.is_some() {
+        ctx.device.scissor_test(false);
+    }
+
+    push_quad(
+        ctx,
+        0.0,
+        0.0,
+        width as f32,
+        height as f32,
+        0.0,
+        0.0,
+        1.0,
+        1.0,
+        &DrawParams::new(),
+    );
+
+    flush(ctx);
+
+    if ctx.graphics.scissor.is_some() {
+        ctx.device.scissor_test(true);
+    }
+
+    ctx.graphics.texture = texture;
+    ctx.graphics.shader = shader;
+    ctx.graphics.transform_matrix = transform_matrix;
+}
+
+/// Sets the global depth testing behavior.
+///
+/// The depth buffer is an invisible drawing target that stores how close each
+/// pixel is to the viewer. When depth testing is enabled, drawn pixels will be
+/// compared to the contents of the depth buffer, which allows graphics to be sorted
+/// on the GPU (e.g. for isometric games) rather than relying on draw order. The
+/// depth of a graphic is set via [`DrawParams::depth`] or [`Vertex::depth`](mesh::Vertex::depth).
+///
+/// Note that depth is tested and written for every drawn pixel, including fully
+/// transparent ones - if you are drawing sprites with transparent areas, you
+/// may need to use a shader that `discard`s them.
+///
+/// In order to use depth testing, you must be rendering to a target that was
+/// created with a depth buffer attached. To enable this for the main
+/// backbuffer, set [`ContextBuilder::depth_buffer`](crate::ContextBuilder::depth_buffer)
+/// to `true` when creating your context. To enable this for a canvas,
+/// initialize it via [`Canvas::builder`], with [`depth_buffer`](CanvasBuilder::depth_buffer)
+/// set to true.
+pub fn set_depth_state(ctx: &mut Context, state: DepthState) {
+    flush(ctx);
+    ctx.graphics.depth_state = state;
+    ctx.device.set_depth_state(state);
+}
+
+/// Clears the depth buffer to the specified value.
+///
+/// Usually you will want to clear to `-1.0` (the furthest possible depth) at the
+/// start of each frame.
+pub fn clear_depth(ctx: &mut Context, value: f32) {
+    flush(ctx);
+    ctx.device.clear_depth(value);
+}
+
 /// Sets which color components are drawn to the screen.
 ///
 /// This is useful in conjunction with [`set_stencil_state`]
@@ -520,13 +860,114 @@ pub fn set_color_mask(ctx: &mut Context, red: bool, green: bool, blue: bool, alp
     ctx.device.set_color_mask(red, green, blue, alpha);
 }
 
+pub(crate) fn frame_uniforms(ctx: &Context) -> FrameUniforms {
+    let (width, height) = match &ctx.graphics.canvas {
+        ActiveCanvas::Window => window::get_physical_size(ctx),
+        ActiveCanvas::User(r) => r.size(),
+    };
+
+    FrameUniforms {
+        time: time::get_elapsed_time(ctx).as_secs_f32(),
+        delta_time: time::get_delta_time(ctx).as_secs_f32(),
+        resolution: Vec2::new(width as f32, height as f32),
+        frame: time::get_frame_count(ctx) as i32,
+    }
+}
+
+/// Sets the viewport, restricting rendering to a rectangle of the screen (or the current
+/// canvas, if one is active).
+///
+/// While the viewport is set, the projection is adjusted to match it - the top left of
+/// the viewport will be at `(0.0, 0.0)`, and anything drawn outside of the viewport's
+/// size will be clipped. This makes it possible to render several views of a scene
+/// (e.g. for local multiplayer split-screen) without using a separate canvas for each.
+/// [`CameraView`] can be used to pair a viewport with a [`Camera`].
+///
+/// The rectangle is specified in screen/canvas co-ordinates, and is not affected by the
+/// transform matrix. Note that [`clear`] is not affected by the viewport - use
+/// [`set_scissor`] if you only want to clear part of the screen.
+///
+/// The viewport will be reset when the active canvas changes. To reset it manually,
+/// call [`reset_viewport`].
+pub fn set_viewport(ctx: &mut Context, viewport: Rectangle<i32>) {
+    flush(ctx);
+
+    ctx.graphics.viewport = Some(viewport);
+    apply_viewport(ctx);
+}
+
+/// Resets the viewport, so that it covers the entire screen (or the current canvas,
+/// if one is active).
+pub fn reset_viewport(ctx: &mut Context) {
+    flush(ctx);
+
+    ctx.graphics.viewport = None;
+    apply_viewport(ctx);
+}
+
+/// Returns the current viewport.
+///
+/// If a viewport has not been set, this will return a rectangle covering the entire
+/// screen (or the current canvas, if one is active).
+pub fn get_viewport(ctx: &Context) -> Rectangle<i32> {
+    ctx.graphics.viewport.unwrap_or_else(|| {
+        let (width, height) = match &ctx.graphics.canvas {
+            ActiveCanvas::Window => window::get_size(ctx),
+            ActiveCanvas::User(c) => c.size(),
+        };
+
+        Rectangle::new(0, 0, width, height)
+    })
+}
+
 pub(crate) fn set_viewport_size(ctx: &mut Context) {
     if let ActiveCanvas::Window = ctx.graphics.canvas {
-        let (width, height) = window::get_size(ctx);
-        let (physical_width, physical_height) = window::get_physical_size(ctx);
+        apply_viewport(ctx);
+    }
+}
+
+/// Updates the projection matrix and the device's viewport to match the current viewport.
+fn apply_viewport(ctx: &mut Context) {
+    let viewport = get_viewport(ctx);
 
-        ctx.graphics.projection_matrix = ortho(width as f32, height as f32, false);
-        ctx.device.viewport(0, 0, physical_width, physical_height);
+    match &ctx.graphics.canvas {
+        ActiveCanvas::Window => {
+            let (width, height) = window::get_size(ctx);
+            let (physical_width, physical_height) = window::get_physical_size(ctx);
+
+            // The viewport is specified in logical pixels, but the device expects
+            // physical pixels, so it needs scaling on high-DPI displays.
+            let scale_x = physical_width as f32 / width as f32;
+            let scale_y = physical_height as f32 / height as f32;
+
+            let x = (viewport.x as f32 * scale_x).round() as i32;
+            let y = (viewport.y as f32 * scale_y).round() as i32;
+            let viewport_width = (viewport.width as f32 * scale_x).round() as i32;
+            let viewport_height = (viewport.height as f32 * scale_y).round() as i32;
+
+            ctx.graphics.projection_matrix =
+                ortho(viewport.width as f32, viewport.height as f32, false);
+
+            // OpenGL uses bottom-left co-ordinates, while Tetra uses
+            // top-left co-ordinates - to present a consistent API, we
+            // flip the Y component here.
+            ctx.device.viewport(
+                x,
+                physical_height - (y + viewport_height),
+                viewport_width,
+                viewport_height,
+            );
+        }
+
+        ActiveCanvas::User(_) => {
+            ctx.graphics.projection_matrix =
+                ortho(viewport.width as f32, viewport.height as f32, true);
+
+            // Canvas rendering is effectively done upside-down, so we don't
+            // need to flip the co-ordinates here.
+            ctx.device
+                .viewport(viewport.x, viewport.y, viewport.width, viewport.height);
+        }
     }
 }
 
@@ -536,8 +977,10 @@ pub(crate) fn ortho(width: f32, height: f32, flipped: bool) -> Mat4<f32> {
         right: width,
         bottom: if flipped { 0.0 } else { height },
         top: if flipped { height } else { 0.0 },
-        near: -1.0,
-        far: 1.0,
+        // These are the opposite way round to what you might expect, so that
+        // higher depth values are closer to the viewer.
+        near: 1.0,
+        far: -1.0,
     })
 }
 
@@ -570,6 +1013,75 @@ pub enum BlendMode {
     /// The pixel colors of the drawn content will be multiplied with the pixel colors
     /// already in the target. The alpha component will also be multiplied.
     Multiply,
+
+    /// The inverse of the drawn content's pixel colors will be multiplied with the inverse
+    /// of the pixel colors already in the target, and then inverted again. This has
+    /// the effect of brightening the target.
+    Screen(BlendAlphaMode),
+
+    /// For each component, the higher of the drawn content's value and the target's
+    /// value will be used.
+    ///
+    /// This mode expects colors to have [premultiplied alpha](BlendAlphaMode::Premultiplied).
+    Lighten,
+
+    /// For each component, the lower of the drawn content's value and the target's
+    /// value will be used.
+    ///
+    /// This mode expects colors to have [premultiplied alpha](BlendAlphaMode::Premultiplied).
+    Darken,
+
+    /// The pixel colors of the drawn content will replace the pixel colors already in
+    /// the target, including the alpha component.
+    Replace,
+
+    /// A custom blend configuration.
+    ///
+    /// This gives you full control over the blend equations and factors that are
+    /// used. All of the other blend modes can be expressed this way - see
+    /// [`BlendMode::to_state`] for their equivalent configurations.
+    Custom(BlendState),
+}
+
+impl BlendMode {
+    /// Returns the blend configuration that is equivalent to this blend mode.
+    pub fn to_state(&self) -> BlendState {
+        use self::BlendEquation as Eq;
+        use self::BlendFactor as F;
+
+        let src_rgb = |alpha_mode: &BlendAlphaMode| match alpha_mode {
+            BlendAlphaMode::Multiply => F::SrcAlpha,
+            BlendAlphaMode::Premultiplied => F::One,
+        };
+
+        match self {
+            BlendMode::Alpha(a) => BlendState::new(
+                Eq::Add,
+                src_rgb(a),
+                F::OneMinusSrcAlpha,
+                F::One,
+                F::OneMinusSrcAlpha,
+            ),
+            BlendMode::Add(a) => BlendState::new(Eq::Add, src_rgb(a), F::One, F::Zero, F::One),
+            BlendMode::Subtract(a) => {
+                BlendState::new(Eq::ReverseSubtract, src_rgb(a), F::One, F::Zero, F::One)
+            }
+            BlendMode::Multiply => {
+                BlendState::new(Eq::Add, F::DstColor, F::Zero, F::DstColor, F::Zero)
+            }
+            BlendMode::Screen(a) => BlendState::new(
+                Eq::Add,
+                src_rgb(a),
+                F::OneMinusSrcColor,
+                F::One,
+                F::OneMinusSrcColor,
+            ),
+            BlendMode::Lighten => BlendState::new(Eq::Max, F::One, F::One, F::One, F::One),
+            BlendMode::Darken => BlendState::new(Eq::Min, F::One, F::One, F::One, F::One),
+            BlendMode::Replace => BlendState::new(Eq::Add, F::One, F::Zero, F::One, F::Zero),
+            BlendMode::Custom(state) => *state,
+        }
+    }
 }
 
 impl Default for BlendMode {
@@ -602,6 +1114,118 @@ impl Default for BlendAlphaMode {
     }
 }
 
+/// The equations that can be used to combine colors when blending.
+///
+/// In the descriptions below, 'source' refers to the drawn content, and 'destination'
+/// refers to the content already in the target. Both are multiplied by their
+/// respective [`BlendFactor`]s before the equation is applied, except for `Min`
+/// and `Max`, which ignore the factors.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BlendEquation {
+    /// The source and destination are added together.
+    Add,
+
+    /// The destination is subtracted from the source.
+    Subtract,
+
+    /// The source is subtracted from the destination.
+    ReverseSubtract,
+
+    /// The lower of the source and destination is used.
+    Min,
+
+    /// The higher of the source and destination is used.
+    Max,
+}
+
+/// The factors that colors can be multiplied by when blending.
+///
+/// In the descriptions below, 'source' refers to the drawn content, and 'destination'
+/// refers to the content already in the target.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BlendFactor {
+    /// Multiply by zero.
+    Zero,
+
+    /// Multiply by one.
+    One,
+
+    /// Multiply by the source color.
+    SrcColor,
+
+    /// Multiply by one minus the source color.
+    OneMinusSrcColor,
+
+    /// Multiply by the destination color.
+    DstColor,
+
+    /// Multiply by one minus the destination color.
+    OneMinusDstColor,
+
+    /// Multiply by the source alpha.
+    SrcAlpha,
+
+    /// Multiply by one minus the source alpha.
+    OneMinusSrcAlpha,
+
+    /// Multiply by the destination alpha.
+    DstAlpha,
+
+    /// Multiply by one minus the destination alpha.
+    OneMinusDstAlpha,
+
+    /// Multiply by the lower of the source alpha and one minus the destination alpha.
+    /// The alpha component is multiplied by one.
+    SrcAlphaSaturate,
+}
+
+/// Represents a custom blend configuration.
+///
+/// This can be used via [`BlendMode::Custom`]. The RGB components and the alpha
+/// component of colors are blended separately, and so they each have their own
+/// equation and factors.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BlendState {
+    /// The equation used to combine the RGB components.
+    pub rgb_equation: BlendEquation,
+
+    /// The equation used to combine the alpha component.
+    pub alpha_equation: BlendEquation,
+
+    /// The factor that the drawn content's RGB components will be multiplied by.
+    pub src_rgb: BlendFactor,
+
+    /// The factor that the target's RGB components will be multiplied by.
+    pub dst_rgb: BlendFactor,
+
+    /// The factor that the drawn content's alpha component will be multiplied by.
+    pub src_alpha: BlendFactor,
+
+    /// The factor that the target's alpha component will be multiplied by.
+    pub dst_alpha: BlendFactor,
+}
+
+impl BlendState {
+    /// Creates a new blend configuration, using the same equation for the RGB
+    /// and alpha components.
+    pub fn new(
+        equation: BlendEquation,
+        src_rgb: BlendFactor,
+        dst_rgb: BlendFactor,
+        src_alpha: BlendFactor,
+        dst_alpha: BlendFactor,
+    ) -> BlendState {
+        BlendState {
+            rgb_equation: equation,
+            alpha_equation: equation,
+            src_rgb,
+            dst_rgb,
+            src_alpha,
+            dst_alpha,
+        }
+    }
+}
+
 /// The test for whether a pixel is visible when using
 /// a stencil.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -754,3 +1378,101 @@ impl StencilState {
         }
     }
 }
+
+/// The test for whether a pixel is visible when using
+/// depth testing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DepthTest {
+    /// The pixel is never visible.
+    Never,
+
+    /// The pixel is visible if its depth is less than
+    /// the value in the depth buffer.
+    LessThan,
+
+    /// The pixel is visible if its depth is less than
+    /// or equal to the value in the depth buffer.
+    LessThanOrEqualTo,
+
+    /// The pixel is visible if its depth is equal to
+    /// the value in the depth buffer.
+    EqualTo,
+
+    /// The pixel is visible if its depth is not equal to
+    /// the value in the depth buffer.
+    NotEqualTo,
+
+    /// The pixel is visible if its depth is greater than
+    /// the value in the depth buffer.
+    GreaterThan,
+
+    /// The pixel is visible if its depth is greater than
+    /// or equal to the value in the depth buffer.
+    GreaterThanOrEqualTo,
+
+    /// The pixel is always visible.
+    Always,
+}
+
+/// Represents a global depth testing configuration.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DepthState {
+    /// Whether depth testing is enabled.
+    ///
+    /// When set to `true`, pixels drawn will be hidden
+    /// or visible depending on the depth test and the
+    /// contents of the depth buffer.
+    pub enabled: bool,
+
+    /// How drawn pixels will be compared to the contents
+    /// of the depth buffer to determine if they're visible.
+    pub test: DepthTest,
+
+    /// Whether visible pixels will write their depth to
+    /// the depth buffer.
+    ///
+    /// This also controls whether [`clear_depth`] has
+    /// any effect.
+    pub write: bool,
+}
+
+impl DepthState {
+    /// Creates a depth configuration that will disable use
+    /// of the depth buffer.
+    pub fn disabled() -> Self {
+        Self {
+            enabled: false,
+            test: DepthTest::Always,
+            write: true,
+        }
+    }
+
+    /// Creates a depth configuration that will compare drawn
+    /// pixels to the contents of the depth buffer, without
+    /// modifying it.
+    ///
+    /// This can be useful for drawing translucent graphics
+    /// on top of an already depth-sorted scene.
+    pub fn read(test: DepthTest) -> Self {
+        Self {
+            enabled: true,
+            test,
+            write: false,
+        }
+    }
+
+    /// Creates a depth configuration that will compare drawn
+    /// pixels to the contents of the depth buffer, and then
+    /// write the depth of the visible pixels to it.
+    ///
+    /// `DepthState::read_write(DepthTest::GreaterThanOrEqualTo)`
+    /// will draw graphics with a higher depth in front, with
+    /// later draws winning ties.
+    pub fn read_write(test: DepthTest) -> Self {
+        Self {
+            enabled: true,
+            test,
+            write: true,
+        }
+    }
+}
@@ -1,5 +1,8 @@
 //! Functions and types relating to animations.
 
+mod controller;
+
+use std::convert::TryFrom;
 use std::time::Duration;
 
 use crate::graphics::texture::Texture;
@@ -7,12 +10,48 @@ use crate::graphics::{DrawParams, Rectangle};
 use crate::time;
 use crate::context::Context;
 
+pub use crate::graphics::animation::controller::{AnimationController, Condition, Transition};
+
+/// The order in which the frames of an [`Animation`] are played.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PlaybackMode {
+    /// The frames are played from first to last.
+    ///
+    /// This is the default behaviour.
+    Forward,
+
+    /// The frames are played from last to first.
+    Reverse,
+
+    /// The frames are played from first to last, and then back again.
+    ///
+    /// A single play of a ping-pong animation starts and ends on the first frame.
+    PingPong,
+}
+
+impl Default for PlaybackMode {
+    fn default() -> PlaybackMode {
+        PlaybackMode::Forward
+    }
+}
+
 /// An animation, cycling between regions of a texture at a regular interval.
 ///
 /// Calling [`advance`](Self::advance) or [`advance`](Self::advance_by) within [`State::draw`](crate::State::draw)
 /// will drive the animation, switching the texture region once the specified
 /// time has passed.
 ///
+/// By default, every frame of the animation is displayed for the same amount of time, but
+/// individual frames can be given their own length via [`with_frame_lengths`](Self::with_frame_lengths)
+/// or [`set_frame_length_of`](Self::set_frame_length_of).
+///
+/// # Frame Events
+///
+/// After the animation has been advanced, [`entered_frames`](Self::entered_frames) can be used
+/// to find out which frames were switched to. This includes any frames that were skipped over
+/// because the animation advanced by more than their length, which makes it a reliable way
+/// of triggering gameplay events (e.g. footstep sounds) on specific frames.
+///
 /// # Examples
 ///
 /// The [`animation`](https://github.com/17cupsofcoffee/tetra/blob/main/examples/animation.rs)
@@ -25,37 +64,88 @@ use crate::context::Context;
 pub struct Animation {
     texture: Texture,
     frames: Vec<Rectangle>,
+    timeline: Timeline,
+}
+
+/// The timing state of an [`Animation`], which is kept separate from the texture
+/// so that it only needs to know how many frames there are.
+#[derive(Debug, Clone)]
+struct Timeline {
+    frame_count: usize,
     frame_length: Duration,
+    frame_lengths: Option<Vec<Duration>>,
+
+    mode: PlaybackMode,
+    play_count: Option<u32>,
+    speed: f32,
 
     current_frame: usize,
     timer: Duration,
-    repeating: bool,
+    plays: u32,
+    reversing: bool,
+    entered_frames: Vec<usize>,
+}
+
+// The number of whole plays a repeating animation can step through in a single advance,
+// before the rest are skipped over.
+const MAX_PLAYS_PER_ADVANCE: u128 = 64;
+
+struct Step {
+    frame: usize,
+    reversing: bool,
+    completed_play: bool,
 }
 
 impl Animation {
     /// Creates a new looping animation.
     pub fn new(texture: Texture, frames: Vec<Rectangle>, frame_length: Duration) -> Animation {
-        Animation {
-            texture,
-            frames,
-            frame_length,
-
-            current_frame: 0,
-            timer: Duration::from_secs(0),
-            repeating: true,
-        }
+        Animation::with_play_count(texture, frames, frame_length, None)
     }
 
     /// Creates a new animation that does not repeat once all of the frames have been displayed.
     pub fn once(texture: Texture, frames: Vec<Rectangle>, frame_length: Duration) -> Animation {
+        Animation::with_play_count(texture, frames, frame_length, Some(1))
+    }
+
+    /// Creates a new looping animation, where each frame is displayed for its own
+    /// amount of time.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the number of frame lengths does not match the number of frames.
+    pub fn with_frame_lengths(
+        texture: Texture,
+        frames: Vec<Rectangle>,
+        frame_lengths: Vec<Duration>,
+    ) -> Animation {
+        assert_eq!(
+            frames.len(),
+            frame_lengths.len(),
+            "each frame should have a length"
+        );
+
+        let frame_length = frame_lengths
+            .first()
+            .copied()
+            .unwrap_or_else(|| Duration::from_secs(0));
+
+        let mut animation = Animation::new(texture, frames, frame_length);
+        animation.timeline.frame_lengths = Some(frame_lengths);
+        animation
+    }
+
+    fn with_play_count(
+        texture: Texture,
+        frames: Vec<Rectangle>,
+        frame_length: Duration,
+        play_count: Option<u32>,
+    ) -> Animation {
+        let timeline = Timeline::new(frames.len(), frame_length, play_count);
+
         Animation {
             texture,
             frames,
-            frame_length,
-
-            current_frame: 0,
-            timer: Duration::from_secs(0),
-            repeating: false,
+            timeline,
         }
     }
 
@@ -64,7 +154,7 @@ impl Animation {
     where
         P: Into<DrawParams>,
     {
-        let frame = self.frames[self.current_frame];
+        let frame = self.frames[self.timeline.current_frame];
 
         self.texture.draw_region(ctx, frame, params);
     }
@@ -80,27 +170,38 @@ impl Animation {
     /// Advances the animation's timer by a specified amount, switching the texture
     /// region if required.
     ///
-    /// If the specified duration is longer than the frame length, frames will be
-    /// skipped.
+    /// The duration will be multiplied by the animation's [`speed`](Self::speed) before
+    /// it is applied. If the resulting duration is longer than the current frame's
+    /// length, frames will be skipped - these will still be reported by
+    /// [`entered_frames`](Self::entered_frames).
     pub fn advance_by(&mut self, duration: Duration) {
-        self.timer += duration;
+        self.timeline.advance_by(duration);
+    }
 
-        let frames_remaining = self.current_frame < self.frames.len() - 1;
+    /// Gets how far through the current play of the animation the timer is, as a value
+    /// between `0.0` and `1.0`.
+    ///
+    /// This takes into account the length of each frame and the playback mode, so it can
+    /// be used to synchronize two animations that have the same timing but different
+    /// frames (e.g. walking and running).
+    pub fn progress(&self) -> f32 {
+        self.timeline.progress()
+    }
 
-        if frames_remaining || self.repeating {
-            while self.timer >= self.frame_length {
-                self.current_frame = (self.current_frame + 1) % self.frames.len();
-                self.timer -= self.frame_length;
-            }
-        } else if self.timer > self.frame_length {
-            self.timer = self.frame_length;
-        }
+    /// Moves the animation to the specified point in the current play, as a value
+    /// between `0.0` and `1.0`.
+    ///
+    /// Values outside of that range will be clamped.
+    pub fn set_progress(&mut self, progress: f32) {
+        self.timeline.set_progress(progress);
     }
 
     /// Restarts the animation from the first frame.
+    ///
+    /// If the animation is playing in [reverse](PlaybackMode::Reverse), it will
+    /// restart from the last frame instead.
     pub fn restart(&mut self) {
-        self.current_frame = 0;
-        self.timer = Duration::from_secs(0);
+        self.timeline.restart();
     }
 
     /// Returns a reference to the texture currently being used by the animation.
@@ -124,32 +225,167 @@ impl Animation {
 
     /// Sets the sections of the texture being displayed for each frame of the animation.
     ///
-    /// This method will reset the animation back to frame zero.
+    /// This method will reset the animation back to frame zero. If the number of frames
+    /// changes, any per-frame lengths will be discarded, and every frame will be displayed
+    /// for [`frame_length`](Self::frame_length).
     pub fn set_frames(&mut self, new_frames: Vec<Rectangle>) {
+        self.timeline.set_frame_count(new_frames.len());
         self.frames = new_frames;
 
         self.restart();
     }
 
     /// Gets the amount of time that each frame of the animation lasts for.
+    ///
+    /// If individual frames have been given their own lengths, this will return the length
+    /// that was most recently set for the whole animation - use [`frame_length_of`](Self::frame_length_of)
+    /// to get the length of a specific frame.
     pub fn frame_length(&self) -> Duration {
-        self.frame_length
+        self.timeline.frame_length
     }
 
     /// Sets the amount of time that each frame of the animation lasts for.
+    ///
+    /// This will replace any per-frame lengths that have been set.
     pub fn set_frame_length(&mut self, new_frame_length: Duration) {
-        self.frame_length = new_frame_length;
+        self.timeline.frame_length = new_frame_length;
+        self.timeline.frame_lengths = None;
+    }
+
+    /// Gets the amount of time that the specified frame lasts for.
+    ///
+    /// # Panics
+    ///
+    /// The index is zero-based, and must be within the bounds of the animation's
+    /// [`frames`](Self::frames), otherwise this method will panic.
+    pub fn frame_length_of(&self, index: usize) -> Duration {
+        self.timeline.frame_length_of(index)
     }
 
-    /// Gets whether or not the animation is currently set to repeat when it reaches the end
-    /// of the frames.
+    /// Sets the amount of time that the specified frame lasts for.
+    ///
+    /// # Panics
+    ///
+    /// The index is zero-based, and must be within the bounds of the animation's
+    /// [`frames`](Self::frames), otherwise this method will panic.
+    pub fn set_frame_length_of(&mut self, index: usize, new_frame_length: Duration) {
+        self.timeline.set_frame_length_of(index, new_frame_length);
+    }
+
+    /// Gets the amount of time that each frame lasts for, in order.
+    pub fn frame_lengths(&self) -> Vec<Duration> {
+        (0..self.frames.len())
+            .map(|i| self.frame_length_of(i))
+            .collect()
+    }
+
+    /// Sets the amount of time that each frame lasts for, in order.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the number of frame lengths does not match the number of frames.
+    pub fn set_frame_lengths(&mut self, new_frame_lengths: Vec<Duration>) {
+        assert_eq!(
+            self.frames.len(),
+            new_frame_lengths.len(),
+            "each frame should have a length"
+        );
+
+        self.timeline.frame_lengths = Some(new_frame_lengths);
+    }
+
+    /// Gets whether or not the animation is currently set to repeat indefinitely when it reaches
+    /// the end of the frames.
     pub fn repeating(&self) -> bool {
-        self.repeating
+        self.timeline.play_count.is_none()
     }
 
     /// Sets whether or not the animation should repeat when it reaches the end of the frames.
+    ///
+    /// Setting this to `false` is equivalent to setting the [`play_count`](Self::play_count) to `1`.
     pub fn set_repeating(&mut self, repeating: bool) {
-        self.repeating = repeating;
+        self.timeline.play_count = if repeating { None } else { Some(1) };
+    }
+
+    /// Gets the number of times that the animation will play before stopping.
+    ///
+    /// [`None`] means that the animation will repeat indefinitely.
+    pub fn play_count(&self) -> Option<u32> {
+        self.timeline.play_count
+    }
+
+    /// Sets the number of times that the animation will play before stopping.
+    ///
+    /// [`None`] means that the animation will repeat indefinitely. Once the animation
+    /// has finished, it will continue to display its final frame until it is
+    /// [restarted](Self::restart).
+    pub fn set_play_count(&mut self, play_count: Option<u32>) {
+        self.timeline.play_count = play_count;
+    }
+
+    /// Gets the number of times that the animation has played through all of its frames
+    /// since it was last restarted.
+    pub fn plays_completed(&self) -> u32 {
+        self.timeline.plays
+    }
+
+    /// Returns `true` if the animation has finished playing, and will not advance any further.
+    ///
+    /// This will never return `true` for a repeating animation.
+    pub fn is_finished(&self) -> bool {
+        self.timeline.is_finished()
+    }
+
+    /// Gets the order in which the frames are played.
+    pub fn playback_mode(&self) -> PlaybackMode {
+        self.timeline.mode
+    }
+
+    /// Sets the order in which the frames are played.
+    ///
+    /// This method will [restart](Self::restart) the animation, if the mode has changed.
+    pub fn set_playback_mode(&mut self, mode: PlaybackMode) {
+        self.timeline.set_playback_mode(mode);
+    }
+
+    /// Gets the multiplier that is applied to time passing in the animation.
+    ///
+    /// Defaults to `1.0`.
+    pub fn speed(&self) -> f32 {
+        self.timeline.speed
+    }
+
+    /// Sets the multiplier that is applied to time passing in the animation.
+    ///
+    /// For example, a speed of `2.0` will play the animation twice as fast, and a
+    /// speed of `0.5` will play it at half speed. Negative and non-finite values
+    /// will be treated as `0.0` (i.e. the animation will be paused).
+    pub fn set_speed(&mut self, speed: f32) {
+        self.timeline.speed = if speed.is_finite() {
+            speed.max(0.0)
+        } else {
+            0.0
+        };
+    }
+
+    /// Gets the indices of the frames that were switched to during the last call to
+    /// [`advance`](Self::advance) or [`advance_by`](Self::advance_by), in the order
+    /// that they were displayed.
+    ///
+    /// If the animation advanced by more than the length of a frame, the skipped frames
+    /// will also be included. This means that the same index can be present multiple
+    /// times, if the animation looped.
+    pub fn entered_frames(&self) -> &[usize] {
+        &self.timeline.entered_frames
+    }
+
+    /// Returns `true` if the specified frame was switched to during the last call to
+    /// [`advance`](Self::advance) or [`advance_by`](Self::advance_by).
+    ///
+    /// This is a shortcut for checking whether [`entered_frames`](Self::entered_frames)
+    /// contains the index.
+    pub fn entered_frame(&self, index: usize) -> bool {
+        self.timeline.entered_frames.contains(&index)
     }
 
     /// Gets the index of the frame that is currently being displayed.
@@ -157,7 +393,7 @@ impl Animation {
     /// This index is zero-based, and can be used in combination with the [`frames`](Self::frames)
     /// method in order to track the progress of the animation.
     pub fn current_frame_index(&self) -> usize {
-        self.current_frame
+        self.timeline.current_frame
     }
 
     /// Sets which frame of the animation should be displayed.
@@ -175,7 +411,7 @@ impl Animation {
         // is invalid, but the developer would have no clue where it was set.
         assert!(index < self.frames.len());
 
-        self.current_frame = index;
+        self.timeline.current_frame = index;
     }
 
     /// Gets the duration that the current frame has been visible.
@@ -183,7 +419,7 @@ impl Animation {
     /// This can be used in combination with the [`frame_length`](Self::frame_length) method
     /// in order to track the progress of the animation.
     pub fn current_frame_time(&self) -> Duration {
-        self.timer
+        self.timeline.timer
     }
 
     /// Sets the duration that the current frame has been visible.
@@ -197,6 +433,535 @@ impl Animation {
     /// that is larger than [`frame_length`](Self::frame_length), this animation may
     /// skip frames.
     pub fn set_current_frame_time(&mut self, duration: Duration) {
-        self.timer = duration;
+        self.timeline.timer = duration;
+    }
+}
+
+impl Timeline {
+    fn new(frame_count: usize, frame_length: Duration, play_count: Option<u32>) -> Timeline {
+        Timeline {
+            frame_count,
+            frame_length,
+            frame_lengths: None,
+
+            mode: PlaybackMode::Forward,
+            play_count,
+            speed: 1.0,
+
+            current_frame: 0,
+            timer: Duration::from_secs(0),
+            plays: 0,
+            reversing: false,
+            entered_frames: Vec::new(),
+        }
+    }
+
+    fn advance_by(&mut self, duration: Duration) {
+        self.entered_frames.clear();
+
+        // If every frame has zero length, we'd never be able to use up the timer.
+        if self.total_length() == Duration::from_secs(0) {
+            return;
+        }
+
+        // Very large speeds can scale the duration past what a `Duration` can hold,
+        // so the result is saturated rather than overflowing.
+        let scaled = duration.as_secs_f64() * f64::from(self.speed);
+
+        self.timer = if scaled < Duration::MAX.as_secs_f64() {
+            self.timer.saturating_add(Duration::from_secs_f64(scaled))
+        } else {
+            Duration::MAX
+        };
+
+        // An animation that repeats forever ends up in the same place no matter how
+        // many whole plays are skipped, so if a huge amount of time has passed, only
+        // the last play is stepped through.
+        if self.play_count.is_none() {
+            let play_length = self
+                .play_order()
+                .iter()
+                .map(|&(frame, _)| self.frame_length_of(frame))
+                .sum::<Duration>()
+                .as_nanos();
+
+            let timer = self.timer.as_nanos();
+
+            if play_length > 0 && timer > play_length * MAX_PLAYS_PER_ADVANCE {
+                let remaining = play_length + (timer - play_length) % play_length;
+                let skipped = (timer - remaining) / play_length;
+
+                self.plays = self
+                    .plays
+                    .saturating_add(u32::try_from(skipped).unwrap_or(u32::MAX));
+
+                self.timer = Duration::new(
+                    (remaining / 1_000_000_000) as u64,
+                    (remaining % 1_000_000_000) as u32,
+                );
+            }
+        }
+
+        loop {
+            let length = self.frame_length_of(self.current_frame);
+
+            if self.timer < length {
+                break;
+            }
+
+            match self.next_step() {
+                Some(step) => {
+                    self.timer -= length;
+                    self.current_frame = step.frame;
+                    self.reversing = step.reversing;
+
+                    if step.completed_play {
+                        self.plays = self.plays.saturating_add(1);
+                    }
+
+                    self.entered_frames.push(step.frame);
+                }
+
+                None => {
+                    // Forward and reverse plays are only counted when they loop, so the
+                    // final play has to be counted once the animation stops.
+                    if let Some(count) = self.play_count {
+                        self.plays = self.plays.max(count);
+                    }
+
+                    self.timer = length;
+                    break;
+                }
+            }
+        }
+    }
+
+    fn next_step(&self) -> Option<Step> {
+        let last = self.frame_count - 1;
+        let can_replay = |plays: u32| !matches!(self.play_count, Some(count) if plays >= count);
+
+        match self.mode {
+            PlaybackMode::Forward => {
+                if self.current_frame < last {
+                    Some(Step {
+                        frame: self.current_frame + 1,
+                        reversing: false,
+                        completed_play: false,
+                    })
+                } else if can_replay(self.plays + 1) {
+                    Some(Step {
+                        frame: 0,
+                        reversing: false,
+                        completed_play: true,
+                    })
+                } else {
+                    None
+                }
+            }
+
+            PlaybackMode::Reverse => {
+                if self.current_frame > 0 {
+                    Some(Step {
+                        frame: self.current_frame - 1,
+                        reversing: false,
+                        completed_play: false,
+                    })
+                } else if can_replay(self.plays + 1) {
+                    Some(Step {
+                        frame: last,
+                        reversing: false,
+                        completed_play: true,
+                    })
+                } else {
+                    None
+                }
+            }
+
+            PlaybackMode::PingPong => {
+                if last == 0 {
+                    // A single frame can't bounce, so this is the same as playing forwards.
+                    return if can_replay(self.plays + 1) {
+                        Some(Step {
+                            frame: 0,
+                            reversing: false,
+                            completed_play: true,
+                        })
+                    } else {
+                        None
+                    };
+                }
+
+                if !self.reversing {
+                    if self.current_frame < last {
+                        Some(Step {
+                            frame: self.current_frame + 1,
+                            reversing: false,
+                            completed_play: false,
+                        })
+                    } else {
+                        Some(Step {
+                            frame: self.current_frame - 1,
+                            reversing: true,
+                            completed_play: self.current_frame == 1,
+                        })
+                    }
+                } else if self.current_frame > 0 {
+                    Some(Step {
+                        frame: self.current_frame - 1,
+                        reversing: true,
+                        completed_play: self.current_frame == 1,
+                    })
+                } else if can_replay(self.plays) {
+                    Some(Step {
+                        frame: 1,
+                        reversing: false,
+                        completed_play: false,
+                    })
+                } else {
+                    None
+                }
+            }
+        }
+    }
+
+    fn is_finished(&self) -> bool {
+        self.timer >= self.frame_length_of(self.current_frame) && self.next_step().is_none()
+    }
+
+    fn first_frame(&self) -> usize {
+        match self.mode {
+            PlaybackMode::Forward | PlaybackMode::PingPong => 0,
+            PlaybackMode::Reverse => self.frame_count.saturating_sub(1),
+        }
+    }
+
+    fn total_length(&self) -> Duration {
+        match &self.frame_lengths {
+            Some(lengths) => lengths.iter().sum(),
+            None => self.frame_length * self.frame_count as u32,
+        }
+    }
+
+    // The order that frames are displayed in during a single play, along with the
+    // direction that a ping-pong animation is moving in at that point.
+    fn play_order(&self) -> Vec<(usize, bool)> {
+        let last = self.frame_count.saturating_sub(1);
+
+        match self.mode {
+            PlaybackMode::Forward => (0..=last).map(|i| (i, false)).collect(),
+            PlaybackMode::Reverse => (0..=last).rev().map(|i| (i, false)).collect(),
+            PlaybackMode::PingPong => (0..=last)
+                .map(|i| (i, false))
+                .chain((1..last).rev().map(|i| (i, true)))
+                .collect(),
+        }
+    }
+
+    fn progress(&self) -> f32 {
+        let order = self.play_order();
+        let total = order
+            .iter()
+            .map(|&(frame, _)| self.frame_length_of(frame))
+            .sum::<Duration>();
+
+        if total == Duration::from_secs(0) {
+            return 0.0;
+        }
+
+        let position = order
+            .iter()
+            .position(|&(frame, reversing)| {
+                frame == self.current_frame && (reversing == self.reversing || frame == 0)
+            })
+            .unwrap_or(0);
+
+        let elapsed = order[..position]
+            .iter()
+            .map(|&(frame, _)| self.frame_length_of(frame))
+            .sum::<Duration>()
+            + self.timer;
+
+        (elapsed.as_secs_f32() / total.as_secs_f32()).min(1.0)
+    }
+
+    fn set_progress(&mut self, progress: f32) {
+        let order = self.play_order();
+        let total = order
+            .iter()
+            .map(|&(frame, _)| self.frame_length_of(frame))
+            .sum::<Duration>();
+
+        let mut remaining = total.mul_f32(progress.clamp(0.0, 1.0));
+
+        for (i, &(frame, reversing)) in order.iter().enumerate() {
+            let length = self.frame_length_of(frame);
+
+            self.current_frame = frame;
+            self.reversing = reversing;
+
+            if remaining < length || i == order.len() - 1 {
+                break;
+            }
+
+            remaining -= length;
+        }
+
+        self.timer = remaining;
+    }
+
+    fn restart(&mut self) {
+        self.current_frame = self.first_frame();
+        self.timer = Duration::from_secs(0);
+        self.plays = 0;
+        self.reversing = false;
+        self.entered_frames.clear();
+    }
+
+    fn set_frame_count(&mut self, frame_count: usize) {
+        if frame_count != self.frame_count {
+            self.frame_lengths = None;
+        }
+
+        self.frame_count = frame_count;
+    }
+
+    fn frame_length_of(&self, index: usize) -> Duration {
+        assert!(index < self.frame_count);
+
+        match &self.frame_lengths {
+            Some(lengths) => lengths[index],
+            None => self.frame_length,
+        }
+    }
+
+    fn set_frame_length_of(&mut self, index: usize, new_frame_length: Duration) {
+        assert!(index < self.frame_count);
+
+        let frame_length = self.frame_length;
+        let count = self.frame_count;
+
+        self.frame_lengths
+            .get_or_insert_with(|| vec![frame_length; count])[index] = new_frame_length;
+    }
+
+    fn set_playback_mode(&mut self, mode: PlaybackMode) {
+        if self.mode != mode {
+            self.mode = mode;
+            self.restart();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn secs(secs: f32) -> Duration {
+        Duration::from_secs_f32(secs)
+    }
+
+    fn timeline(frame_count: usize, mode: PlaybackMode, play_count: Option<u32>) -> Timeline {
+        let mut timeline = Timeline::new(frame_count, secs(1.0), play_count);
+        timeline.set_playback_mode(mode);
+        timeline
+    }
+
+    #[test]
+    fn per_frame_lengths() {
+        let mut timeline = timeline(3, PlaybackMode::Forward, None);
+        timeline.frame_lengths = Some(vec![secs(1.0), secs(2.0), secs(3.0)]);
+
+        timeline.advance_by(secs(1.0));
+        assert_eq!(timeline.current_frame, 1);
+        assert_eq!(timeline.entered_frames, [1]);
+
+        timeline.advance_by(secs(2.5));
+        assert_eq!(timeline.current_frame, 2);
+        assert_eq!(timeline.timer, secs(0.5));
+        assert_eq!(timeline.entered_frames, [2]);
+
+        timeline.advance_by(secs(2.5));
+        assert_eq!(timeline.current_frame, 0);
+        assert_eq!(timeline.plays, 1);
+        assert_eq!(timeline.entered_frames, [0]);
+    }
+
+    #[test]
+    fn reverse() {
+        let mut timeline = timeline(3, PlaybackMode::Reverse, None);
+        assert_eq!(timeline.current_frame, 2);
+
+        timeline.advance_by(secs(3.0));
+        assert_eq!(timeline.entered_frames, [1, 0, 2]);
+        assert_eq!(timeline.plays, 1);
+    }
+
+    #[test]
+    fn ping_pong_turnarounds() {
+        let mut timeline = timeline(3, PlaybackMode::PingPong, None);
+
+        timeline.advance_by(secs(3.0));
+        assert_eq!(timeline.entered_frames, [1, 2, 1]);
+        assert!(timeline.reversing);
+        assert_eq!(timeline.plays, 0);
+
+        // The play is completed when moving from frame 1 back to the start.
+        timeline.advance_by(secs(1.0));
+        assert_eq!(timeline.entered_frames, [0]);
+        assert_eq!(timeline.plays, 1);
+
+        timeline.advance_by(secs(1.0));
+        assert_eq!(timeline.entered_frames, [1]);
+        assert!(!timeline.reversing);
+    }
+
+    #[test]
+    fn ping_pong_two_frames() {
+        let mut timeline = timeline(2, PlaybackMode::PingPong, None);
+
+        // Turning around at the last frame also completes the play, as it's frame 1.
+        timeline.advance_by(secs(2.0));
+        assert_eq!(timeline.entered_frames, [1, 0]);
+        assert_eq!(timeline.plays, 1);
+
+        timeline.advance_by(secs(1.0));
+        assert_eq!(timeline.entered_frames, [1]);
+        assert!(!timeline.reversing);
+    }
+
+    #[test]
+    fn play_count_cut_off() {
+        let mut timeline = timeline(3, PlaybackMode::Forward, Some(2));
+
+        timeline.advance_by(secs(100.0));
+        assert_eq!(timeline.entered_frames, [1, 2, 0, 1, 2]);
+        assert_eq!(timeline.current_frame, 2);
+        assert_eq!(timeline.timer, secs(1.0));
+        assert_eq!(timeline.plays, 2);
+        assert!(timeline.is_finished());
+
+        timeline.advance_by(secs(1.0));
+        assert!(timeline.entered_frames.is_empty());
+        assert_eq!(timeline.plays, 2);
+
+        let mut timeline = self::timeline(3, PlaybackMode::PingPong, Some(1));
+
+        timeline.advance_by(secs(100.0));
+        assert_eq!(timeline.entered_frames, [1, 2, 1, 0]);
+        assert_eq!(timeline.plays, 1);
+        assert!(timeline.is_finished());
+
+        timeline.restart();
+        assert!(!timeline.is_finished());
+        assert_eq!(timeline.plays, 0);
+    }
+
+    #[test]
+    fn speed() {
+        let mut timeline = timeline(2, PlaybackMode::Forward, None);
+
+        timeline.speed = 2.0;
+        timeline.advance_by(secs(0.5));
+        assert_eq!(timeline.current_frame, 1);
+
+        timeline.speed = 0.0;
+        timeline.advance_by(secs(100.0));
+        assert_eq!(timeline.current_frame, 1);
+        assert!(timeline.entered_frames.is_empty());
+    }
+
+    #[test]
+    fn huge_speed() {
+        let mut timeline = timeline(3, PlaybackMode::Forward, Some(2));
+
+        timeline.speed = f32::MAX;
+        timeline.advance_by(secs(100.0));
+        assert_eq!(timeline.current_frame, 2);
+        assert_eq!(timeline.plays, 2);
+        assert!(timeline.is_finished());
+
+        let mut timeline = self::timeline(3, PlaybackMode::PingPong, None);
+
+        timeline.speed = f32::MAX;
+        timeline.advance_by(secs(100.0));
+        assert!(timeline.entered_frames.len() <= 8);
+        assert!(timeline.timer < secs(1.0));
+        assert_eq!(timeline.plays, u32::MAX);
+
+        let mut timeline = self::timeline(3, PlaybackMode::Forward, None);
+
+        timeline.speed = 1000.0;
+        timeline.advance_by(secs(1.5));
+        assert_eq!(timeline.entered_frames, [1, 2, 0]);
+        assert_eq!(timeline.current_frame, 0);
+        assert_eq!(timeline.timer, secs(0.0));
+        assert_eq!(timeline.plays, 500);
+    }
+
+    #[test]
+    fn entered_frames_include_skipped_frames() {
+        let mut timeline = timeline(2, PlaybackMode::Forward, None);
+
+        timeline.advance_by(secs(4.5));
+        assert_eq!(timeline.entered_frames, [1, 0, 1, 0]);
+        assert_eq!(timeline.plays, 2);
+
+        timeline.advance_by(secs(0.25));
+        assert!(timeline.entered_frames.is_empty());
+    }
+
+    #[test]
+    fn zero_length_frames() {
+        let mut timeline = Timeline::new(3, Duration::from_secs(0), None);
+
+        timeline.advance_by(secs(1.0));
+        assert_eq!(timeline.current_frame, 0);
+        assert!(timeline.entered_frames.is_empty());
+        assert_eq!(timeline.progress(), 0.0);
+    }
+
+    #[test]
+    fn progress() {
+        // A ping-pong play is displayed as 0, 1, 2, 1.
+        let mut timeline = timeline(3, PlaybackMode::PingPong, None);
+
+        timeline.advance_by(secs(2.5));
+        assert_eq!(timeline.progress(), 0.625);
+
+        timeline.advance_by(secs(1.0));
+        assert!(timeline.reversing);
+        assert_eq!(timeline.progress(), 0.875);
+
+        let mut timeline = self::timeline(3, PlaybackMode::Forward, None);
+        timeline.frame_lengths = Some(vec![secs(1.0), secs(2.0), secs(1.0)]);
+
+        timeline.advance_by(secs(2.0));
+        assert_eq!(timeline.progress(), 0.5);
+    }
+
+    #[test]
+    fn set_progress() {
+        let mut timeline = timeline(3, PlaybackMode::PingPong, None);
+
+        timeline.set_progress(0.875);
+        assert_eq!(timeline.current_frame, 1);
+        assert!(timeline.reversing);
+        assert_eq!(timeline.timer, secs(0.5));
+        assert_eq!(timeline.progress(), 0.875);
+
+        timeline.set_progress(0.25);
+        assert_eq!(timeline.current_frame, 1);
+        assert!(!timeline.reversing);
+        assert_eq!(timeline.timer, secs(0.0));
+
+        // Values out of range are clamped, ending on the last frame of the play.
+        timeline.set_progress(2.0);
+        assert_eq!(timeline.current_frame, 1);
+        assert!(timeline.reversing);
+        assert_eq!(timeline.timer, secs(1.0));
+
+        timeline.set_progress(-1.0);
+        assert_eq!(timeline.current_frame, 0);
+        assert_eq!(timeline.timer, secs(0.0));
     }
 }
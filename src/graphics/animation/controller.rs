@@ -0,0 +1,728 @@
+use std::fmt::{self, Debug, Formatter};
+use std::time::Duration;
+
+use hashbrown::{HashMap, HashSet};
+
+use crate::context::Context;
+use crate::graphics::animation::Animation;
+use crate::graphics::DrawParams;
+use crate::time;
+
+/// A condition that must be met in order for a [`Transition`] to occur.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Condition {
+    /// The named boolean parameter must be `true`.
+    True(String),
+
+    /// The named boolean parameter must be `false`.
+    ///
+    /// Parameters that have never been set are treated as `false`.
+    False(String),
+
+    /// The named float parameter must be greater than the given value.
+    GreaterThan(String, f32),
+
+    /// The named float parameter must be less than the given value.
+    LessThan(String, f32),
+
+    /// The named trigger must have been set via [`AnimationController::set_trigger`].
+    ///
+    /// The trigger will be reset once the transition occurs.
+    Trigger(String),
+
+    /// The current state's animation must have [finished](Animation::is_finished).
+    ///
+    /// This will never be met by a repeating animation.
+    Finished,
+}
+
+/// A rule describing when an [`AnimationController`] should switch from one state
+/// to another.
+///
+/// Transitions are built up by chaining methods:
+///
+/// ```
+/// # use firecore_tetra::graphics::animation::{Condition, Transition};
+/// let transition = Transition::new("walk", "run")
+///     .when(Condition::GreaterThan("speed".into(), 4.0))
+///     .sync(true);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Transition {
+    from: Option<String>,
+    to: String,
+    conditions: Vec<Condition>,
+    sync: bool,
+}
+
+impl Transition {
+    /// Creates a new transition between two states.
+    ///
+    /// Without any [conditions](Self::when), the transition will occur as soon as the
+    /// `from` state becomes active.
+    pub fn new<F, T>(from: F, to: T) -> Transition
+    where
+        F: Into<String>,
+        T: Into<String>,
+    {
+        Transition {
+            from: Some(from.into()),
+            to: to.into(),
+            conditions: Vec::new(),
+            sync: false,
+        }
+    }
+
+    /// Creates a new transition that can occur from any state (other than the target state).
+    pub fn from_any<T>(to: T) -> Transition
+    where
+        T: Into<String>,
+    {
+        Transition {
+            from: None,
+            to: to.into(),
+            conditions: Vec::new(),
+            sync: false,
+        }
+    }
+
+    /// Adds a condition to the transition.
+    ///
+    /// If multiple conditions are added, they must all be met for the transition to occur.
+    pub fn when(mut self, condition: Condition) -> Transition {
+        self.conditions.push(condition);
+        self
+    }
+
+    /// Sets whether the target state should carry on from the same point in its
+    /// cycle as the previous state, rather than restarting.
+    ///
+    /// This is useful for switching between animations with the same timing (e.g.
+    /// walking and running) without the motion visibly resetting. See
+    /// [`Animation::progress`] for details of how the position is measured.
+    ///
+    /// Defaults to `false`.
+    pub fn sync(mut self, sync: bool) -> Transition {
+        self.sync = sync;
+        self
+    }
+}
+
+type FinishedCallback = Box<dyn FnMut(&str)>;
+
+/// The parts of an animation that the controller drives.
+///
+/// This allows the controller's logic to be used (and tested) without a texture.
+trait Playable {
+    fn advance_by(&mut self, duration: Duration);
+    fn is_finished(&self) -> bool;
+    fn progress(&self) -> f32;
+    fn set_progress(&mut self, progress: f32);
+    fn restart(&mut self);
+}
+
+impl Playable for Animation {
+    fn advance_by(&mut self, duration: Duration) {
+        Animation::advance_by(self, duration);
+    }
+
+    fn is_finished(&self) -> bool {
+        Animation::is_finished(self)
+    }
+
+    fn progress(&self) -> f32 {
+        Animation::progress(self)
+    }
+
+    fn set_progress(&mut self, progress: f32) {
+        Animation::set_progress(self, progress);
+    }
+
+    fn restart(&mut self) {
+        Animation::restart(self);
+    }
+}
+
+struct ControllerState<A> {
+    animation: A,
+    on_finished: Option<FinishedCallback>,
+}
+
+/// A state machine that switches between named [`Animation`]s.
+///
+/// Each state holds an animation, and [`Transition`]s describe when the controller
+/// should move between them. Transitions are driven by parameters (booleans, floats
+/// and triggers) which are set by your game code - for example, you might set a `running`
+/// parameter based on the player's velocity, or a `jump` trigger when a button is pressed.
+///
+/// Calling [`advance`](Self::advance) or [`advance_by`](Self::advance_by) will drive the
+/// active animation, and then check whether any transitions should occur. Transitions are
+/// checked in the order they were added, and only the first one that matches will be used.
+///
+/// # Examples
+///
+/// The [`animation_controller`](https://github.com/17cupsofcoffee/tetra/blob/main/examples/animation_controller.rs)
+/// example demonstrates how to switch between animations based on the player's input.
+pub struct AnimationController {
+    machine: StateMachine<Animation>,
+}
+
+impl AnimationController {
+    /// Creates a new controller, with an initial state.
+    pub fn new<S>(name: S, animation: Animation) -> AnimationController
+    where
+        S: Into<String>,
+    {
+        AnimationController {
+            machine: StateMachine::new(name.into(), animation),
+        }
+    }
+
+    /// Adds a state to the controller.
+    ///
+    /// If a state with the same name already exists, its animation will be replaced.
+    pub fn add_state<S>(&mut self, name: S, animation: Animation)
+    where
+        S: Into<String>,
+    {
+        self.machine.add_state(name.into(), animation);
+    }
+
+    /// Adds a transition to the controller.
+    ///
+    /// # Panics
+    ///
+    /// Panics if either of the states referenced by the transition have not been
+    /// added to the controller.
+    pub fn add_transition(&mut self, transition: Transition) {
+        self.machine.add_transition(transition);
+    }
+
+    /// Sets a function that will be called when the named state's animation finishes.
+    ///
+    /// The function is passed the name of the state, and will be called once each time
+    /// the animation reaches its end. Repeating animations will never finish, so this
+    /// is only useful for one-shot states (e.g. attacks).
+    ///
+    /// # Panics
+    ///
+    /// Panics if the state has not been added to the controller.
+    pub fn on_finished<S, F>(&mut self, name: S, callback: F)
+    where
+        S: AsRef<str>,
+        F: FnMut(&str) + 'static,
+    {
+        self.machine.on_finished(name.as_ref(), Box::new(callback));
+    }
+
+    /// Draws the current state's animation to the screen (or to a canvas, if one is enabled).
+    pub fn draw<P>(&self, ctx: &mut Context, params: P)
+    where
+        P: Into<DrawParams>,
+    {
+        self.current_animation().draw(ctx, params);
+    }
+
+    /// Advances the current state's animation, and then performs any transitions
+    /// whose conditions have been met.
+    ///
+    /// This method uses the current [delta time](crate::time::get_delta_time)
+    /// to calculate how much time has passed.
+    pub fn advance(&mut self, ctx: &Context) {
+        self.advance_by(time::get_delta_time(ctx));
+    }
+
+    /// Advances the current state's animation by a specified amount, and then performs
+    /// any transitions whose conditions have been met.
+    pub fn advance_by(&mut self, duration: Duration) {
+        self.machine.advance_by(duration);
+    }
+
+    /// Performs the first transition whose conditions have been met, if there is one.
+    ///
+    /// This is called automatically by [`advance`](Self::advance) and [`advance_by`](Self::advance_by),
+    /// but can be called manually if you want parameter changes to take effect immediately.
+    pub fn update_transitions(&mut self) {
+        self.machine.update_transitions();
+    }
+
+    /// Returns the name of the current state.
+    pub fn current_state(&self) -> &str {
+        &self.machine.current
+    }
+
+    /// Switches to the named state, restarting its animation.
+    ///
+    /// This ignores any transitions - it can be useful for e.g. resetting a character
+    /// when they respawn.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the state has not been added to the controller.
+    pub fn set_state<S>(&mut self, name: S)
+    where
+        S: AsRef<str>,
+    {
+        self.machine.set_state(name.as_ref());
+    }
+
+    /// Returns a reference to the current state's animation.
+    pub fn current_animation(&self) -> &Animation {
+        self.machine.current_animation()
+    }
+
+    /// Returns a mutable reference to the current state's animation.
+    pub fn current_animation_mut(&mut self) -> &mut Animation {
+        self.machine.current_animation_mut()
+    }
+
+    /// Returns a reference to the named state's animation.
+    ///
+    /// Returns [`None`] if the state has not been added to the controller.
+    pub fn animation(&self, name: &str) -> Option<&Animation> {
+        self.machine.states.get(name).map(|s| &s.animation)
+    }
+
+    /// Returns a mutable reference to the named state's animation.
+    ///
+    /// Returns [`None`] if the state has not been added to the controller.
+    pub fn animation_mut(&mut self, name: &str) -> Option<&mut Animation> {
+        self.machine.states.get_mut(name).map(|s| &mut s.animation)
+    }
+
+    /// Gets the value of a boolean parameter.
+    ///
+    /// Parameters that have never been set will return `false`.
+    pub fn get_bool(&self, name: &str) -> bool {
+        self.machine.get_bool(name)
+    }
+
+    /// Sets the value of a boolean parameter.
+    pub fn set_bool<S>(&mut self, name: S, value: bool)
+    where
+        S: Into<String>,
+    {
+        self.machine.bools.insert(name.into(), value);
+    }
+
+    /// Gets the value of a float parameter.
+    ///
+    /// Parameters that have never been set will return `0.0`.
+    pub fn get_float(&self, name: &str) -> f32 {
+        self.machine.get_float(name)
+    }
+
+    /// Sets the value of a float parameter.
+    pub fn set_float<S>(&mut self, name: S, value: f32)
+    where
+        S: Into<String>,
+    {
+        self.machine.floats.insert(name.into(), value);
+    }
+
+    /// Sets a trigger.
+    ///
+    /// Triggers stay set until a transition that depends on them occurs, or until
+    /// they are [reset](Self::reset_trigger).
+    pub fn set_trigger<S>(&mut self, name: S)
+    where
+        S: Into<String>,
+    {
+        self.machine.triggers.insert(name.into());
+    }
+
+    /// Resets a trigger without performing a transition.
+    pub fn reset_trigger(&mut self, name: &str) {
+        self.machine.triggers.remove(name);
+    }
+}
+
+/// The logic behind an [`AnimationController`], which is generic so that it can be
+/// tested without a texture.
+struct StateMachine<A> {
+    states: HashMap<String, ControllerState<A>>,
+    transitions: Vec<Transition>,
+
+    bools: HashMap<String, bool>,
+    floats: HashMap<String, f32>,
+    triggers: HashSet<String>,
+
+    current: String,
+    finish_handled: bool,
+}
+
+impl<A> StateMachine<A>
+where
+    A: Playable,
+{
+    fn new(name: String, animation: A) -> StateMachine<A> {
+        let mut states = HashMap::new();
+
+        states.insert(
+            name.clone(),
+            ControllerState {
+                animation,
+                on_finished: None,
+            },
+        );
+
+        StateMachine {
+            states,
+            transitions: Vec::new(),
+
+            bools: HashMap::new(),
+            floats: HashMap::new(),
+            triggers: HashSet::new(),
+
+            current: name,
+            finish_handled: false,
+        }
+    }
+
+    fn add_state(&mut self, name: String, animation: A) {
+        match self.states.get_mut(&name) {
+            Some(state) => state.animation = animation,
+            None => {
+                self.states.insert(
+                    name,
+                    ControllerState {
+                        animation,
+                        on_finished: None,
+                    },
+                );
+            }
+        }
+    }
+
+    fn add_transition(&mut self, transition: Transition) {
+        if let Some(from) = &transition.from {
+            assert!(
+                self.states.contains_key(from),
+                "transition from unknown state '{}'",
+                from
+            );
+        }
+
+        assert!(
+            self.states.contains_key(&transition.to),
+            "transition to unknown state '{}'",
+            transition.to
+        );
+
+        self.transitions.push(transition);
+    }
+
+    fn on_finished(&mut self, name: &str, callback: FinishedCallback) {
+        self.states
+            .get_mut(name)
+            .unwrap_or_else(|| panic!("unknown state '{}'", name))
+            .on_finished = Some(callback);
+    }
+
+    fn advance_by(&mut self, duration: Duration) {
+        let current = self.current.clone();
+        let state = self
+            .states
+            .get_mut(&current)
+            .expect("current state should exist");
+
+        state.animation.advance_by(duration);
+
+        if state.animation.is_finished() && !self.finish_handled {
+            self.finish_handled = true;
+
+            if let Some(callback) = &mut state.on_finished {
+                callback(&current);
+            }
+        }
+
+        self.update_transitions();
+    }
+
+    fn update_transitions(&mut self) {
+        let found = self.transitions.iter().position(|t| {
+            let from_matches = match &t.from {
+                Some(from) => *from == self.current,
+                None => t.to != self.current,
+            };
+
+            from_matches && t.conditions.iter().all(|c| self.is_met(c))
+        });
+
+        if let Some(index) = found {
+            let transition = self.transitions[index].clone();
+
+            for condition in &transition.conditions {
+                if let Condition::Trigger(name) = condition {
+                    self.triggers.remove(name);
+                }
+            }
+
+            if transition.sync {
+                let progress = self.current_animation().progress();
+                self.switch_to(&transition.to);
+                self.current_animation_mut().set_progress(progress);
+            } else {
+                self.switch_to(&transition.to);
+                self.current_animation_mut().restart();
+            }
+        }
+    }
+
+    fn is_met(&self, condition: &Condition) -> bool {
+        match condition {
+            Condition::True(name) => self.get_bool(name),
+            Condition::False(name) => !self.get_bool(name),
+            Condition::GreaterThan(name, value) => self.get_float(name) > *value,
+            Condition::LessThan(name, value) => self.get_float(name) < *value,
+            Condition::Trigger(name) => self.triggers.contains(name),
+            Condition::Finished => self.current_animation().is_finished(),
+        }
+    }
+
+    fn switch_to(&mut self, name: &str) {
+        self.current = name.to_owned();
+        self.finish_handled = false;
+    }
+
+    fn set_state(&mut self, name: &str) {
+        assert!(self.states.contains_key(name), "unknown state '{}'", name);
+
+        self.switch_to(name);
+        self.current_animation_mut().restart();
+    }
+
+    fn current_animation(&self) -> &A {
+        &self.states[&self.current].animation
+    }
+
+    fn current_animation_mut(&mut self) -> &mut A {
+        &mut self
+            .states
+            .get_mut(&self.current)
+            .expect("current state should exist")
+            .animation
+    }
+
+    fn get_bool(&self, name: &str) -> bool {
+        self.bools.get(name).copied().unwrap_or(false)
+    }
+
+    fn get_float(&self, name: &str) -> f32 {
+        self.floats.get(name).copied().unwrap_or(0.0)
+    }
+}
+
+impl Debug for AnimationController {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AnimationController")
+            .field("current", &self.machine.current)
+            .field("transitions", &self.machine.transitions)
+            .field("bools", &self.machine.bools)
+            .field("floats", &self.machine.floats)
+            .field("triggers", &self.machine.triggers)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+    use std::rc::Rc;
+
+    use super::*;
+    use crate::graphics::animation::{PlaybackMode, Timeline};
+
+    impl Playable for Timeline {
+        fn advance_by(&mut self, duration: Duration) {
+            Timeline::advance_by(self, duration);
+        }
+
+        fn is_finished(&self) -> bool {
+            Timeline::is_finished(self)
+        }
+
+        fn progress(&self) -> f32 {
+            Timeline::progress(self)
+        }
+
+        fn set_progress(&mut self, progress: f32) {
+            Timeline::set_progress(self, progress);
+        }
+
+        fn restart(&mut self) {
+            Timeline::restart(self);
+        }
+    }
+
+    fn secs(secs: f32) -> Duration {
+        Duration::from_secs_f32(secs)
+    }
+
+    fn machine() -> StateMachine<Timeline> {
+        let mut machine = StateMachine::new("idle".into(), Timeline::new(2, secs(1.0), None));
+
+        machine.add_state("walk".into(), Timeline::new(4, secs(1.0), None));
+        machine.add_state("run".into(), Timeline::new(4, secs(0.5), None));
+        machine.add_state("attack".into(), Timeline::new(2, secs(1.0), Some(1)));
+
+        machine
+    }
+
+    #[test]
+    fn parameter_transitions() {
+        let mut machine = machine();
+
+        machine
+            .add_transition(Transition::new("idle", "walk").when(Condition::True("moving".into())));
+        machine.add_transition(
+            Transition::new("walk", "run").when(Condition::GreaterThan("speed".into(), 4.0)),
+        );
+        machine.add_transition(
+            Transition::new("walk", "idle").when(Condition::False("moving".into())),
+        );
+
+        machine.advance_by(secs(0.5));
+        assert_eq!(machine.current, "idle");
+
+        machine.bools.insert("moving".into(), true);
+        machine.advance_by(secs(0.5));
+        assert_eq!(machine.current, "walk");
+
+        machine.floats.insert("speed".into(), 5.0);
+        machine.update_transitions();
+        assert_eq!(machine.current, "run");
+
+        machine.set_state("idle");
+        machine.bools.insert("moving".into(), false);
+        machine.update_transitions();
+        assert_eq!(machine.current, "idle");
+    }
+
+    #[test]
+    fn first_matching_transition_wins() {
+        let mut machine = machine();
+
+        machine.add_transition(Transition::new("idle", "walk"));
+        machine.add_transition(Transition::new("idle", "run"));
+
+        machine.update_transitions();
+        assert_eq!(machine.current, "walk");
+    }
+
+    #[test]
+    fn triggers_are_consumed() {
+        let mut machine = machine();
+
+        machine.add_transition(
+            Transition::from_any("attack").when(Condition::Trigger("attack".into())),
+        );
+        machine.add_transition(Transition::new("attack", "idle").when(Condition::Finished));
+
+        machine.triggers.insert("attack".into());
+        machine.update_transitions();
+        assert_eq!(machine.current, "attack");
+        assert!(machine.triggers.is_empty());
+
+        // `from_any` transitions don't apply to their own target state.
+        machine.triggers.insert("attack".into());
+        machine.update_transitions();
+        assert_eq!(machine.current, "attack");
+        assert!(!machine.triggers.is_empty());
+    }
+
+    #[test]
+    fn finished_transitions_and_callbacks() {
+        let mut machine = machine();
+        let calls = Rc::new(Cell::new(0));
+
+        let counter = Rc::clone(&calls);
+        machine.on_finished("attack", Box::new(move |_| counter.set(counter.get() + 1)));
+
+        machine.add_transition(
+            Transition::new("attack", "idle")
+                .when(Condition::Finished)
+                .when(Condition::True("done".into())),
+        );
+
+        machine.set_state("attack");
+        machine.advance_by(secs(1.5));
+        assert_eq!(calls.get(), 0);
+
+        machine.advance_by(secs(1.0));
+        machine.advance_by(secs(1.0));
+        assert_eq!(calls.get(), 1);
+        assert_eq!(machine.current, "attack");
+
+        machine.bools.insert("done".into(), true);
+        machine.advance_by(secs(0.0));
+        assert_eq!(machine.current, "idle");
+
+        // Switching back to the state should allow the callback to run again.
+        machine.set_state("attack");
+        machine.bools.insert("done".into(), false);
+        machine.advance_by(secs(2.0));
+        assert_eq!(calls.get(), 2);
+    }
+
+    #[test]
+    fn sync_keeps_progress() {
+        let mut machine = machine();
+
+        machine.add_transition(
+            Transition::new("walk", "run")
+                .when(Condition::True("running".into()))
+                .sync(true),
+        );
+        machine.add_transition(
+            Transition::new("run", "walk").when(Condition::False("running".into())),
+        );
+
+        machine.set_state("walk");
+        machine.advance_by(secs(2.5));
+
+        machine.bools.insert("running".into(), true);
+        machine.update_transitions();
+
+        let run = machine.current_animation();
+        assert_eq!(machine.current, "run");
+        assert_eq!(run.progress(), 0.625);
+        assert_eq!(run.current_frame, 2);
+        assert_eq!(run.timer, secs(0.25));
+
+        // Without sync, the target state restarts.
+        machine.bools.insert("running".into(), false);
+        machine.update_transitions();
+
+        let walk = machine.current_animation();
+        assert_eq!(machine.current, "walk");
+        assert_eq!(walk.current_frame, 0);
+        assert_eq!(walk.progress(), 0.0);
+    }
+
+    #[test]
+    fn sync_with_different_modes() {
+        let mut machine = machine();
+
+        let mut bounce = Timeline::new(3, secs(1.0), None);
+        bounce.set_playback_mode(PlaybackMode::PingPong);
+        machine.add_state("bounce".into(), bounce);
+
+        machine.add_transition(Transition::new("walk", "bounce").sync(true));
+
+        machine.set_state("walk");
+        machine.advance_by(secs(3.5));
+
+        let bounce = machine.current_animation();
+        assert_eq!(machine.current, "bounce");
+        assert_eq!(bounce.current_frame, 1);
+        assert!(bounce.reversing);
+        assert_eq!(bounce.timer, secs(0.5));
+    }
+
+    #[test]
+    #[should_panic(expected = "transition to unknown state 'jump'")]
+    fn unknown_states_panic() {
+        machine().add_transition(Transition::new("idle", "jump"));
+    }
+}
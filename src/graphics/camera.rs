@@ -1,4 +1,6 @@
+use super::rng::Rng;
 use super::Rectangle;
+use crate::graphics;
 use crate::input;
 use crate::math::{Mat4, Vec2, Vec3};
 use crate::window;
@@ -13,6 +15,17 @@ use crate::context::Context;
 /// The camera's matrix is cached internally as an optimization. After adjusting parameters
 /// on the camera, you can call the `update` method to recalculate the matrix.
 ///
+/// The camera also has some optional built-in behaviours, which are applied each time
+/// `update` is called:
+///
+/// * Following a target, via [`set_follow_target`](Self::set_follow_target) and [`CameraFollow`].
+/// * Keeping the view within the bounds of the world, via [`set_bounds`](Self::set_bounds).
+/// * Shaking the view, via [`add_trauma`](Self::add_trauma) and [`CameraShake`].
+///
+/// These behaviours do not depend on the frame rate - as long as `update` is called from
+/// [`State::update`](crate::State::update), they will run at the same speed (and give the
+/// same results) on every machine.
+///
 /// # Examples
 ///
 /// The [`camera`](https://github.com/17cupsofcoffee/tetra/blob/main/examples/camera.rs)
@@ -30,7 +43,8 @@ pub struct Camera {
     /// You may need to take this behaviour into account when positioning the camera,
     /// however. For example, if the viewport width or height is an odd number, setting
     /// the position to a whole number will mean that the view will not be aligned with
-    /// the pixel grid, which may cause issues for pixel-perfect rendering.
+    /// the pixel grid, which may cause issues for pixel-perfect rendering. Enabling
+    /// [`pixel_snapping`](Self::pixel_snapping) avoids this.
     pub position: Vec2<f32>,
 
     /// The rotation of the camera, in radians.
@@ -53,6 +67,35 @@ pub struct Camera {
     /// (e.g. the screen, or a [`Canvas`](crate::graphics::Canvas)).
     pub viewport_height: f32,
 
+    /// Whether the view should be snapped to the pixel grid. Defaults to `false`.
+    ///
+    /// When this is enabled, the translation of the camera's matrix will be rounded to
+    /// whole pixels, so that pixel art does not shimmer or distort as the camera moves.
+    /// This works best when the camera has an integer scale and no rotation.
+    ///
+    /// On its own, this will make scrolling less smooth, as the view can only move one
+    /// whole pixel at a time. If you are rendering to a low resolution canvas and then
+    /// upscaling it (e.g. via a [`ScreenScaler`](crate::graphics::scaling::ScreenScaler)),
+    /// you can pass the remaining [`subpixel_offset`](Self::subpixel_offset) to
+    /// [`ScreenScaler::set_subpixel_offset`](crate::graphics::scaling::ScreenScaler::set_subpixel_offset)
+    /// to apply it when the canvas is drawn to the screen, restoring smooth movement.
+    pub pixel_snapping: bool,
+
+    follow: CameraFollow,
+    follow_target: Option<Vec2<f32>>,
+    previous_target: Option<Vec2<f32>>,
+    lookahead_offset: Vec2<f32>,
+
+    bounds: Option<Rectangle>,
+
+    shake: CameraShake,
+    trauma: f32,
+    shake_time: f32,
+    shake_offset: Vec2<f32>,
+    shake_rotation: f32,
+
+    subpixel_offset: Vec2<f32>,
+
     matrix: Mat4<f32>,
 }
 
@@ -69,6 +112,23 @@ impl Camera {
             viewport_width,
             viewport_height,
 
+            pixel_snapping: false,
+
+            follow: CameraFollow::new(),
+            follow_target: None,
+            previous_target: None,
+            lookahead_offset: Vec2::zero(),
+
+            bounds: None,
+
+            shake: CameraShake::new(),
+            trauma: 0.0,
+            shake_time: 0.0,
+            shake_offset: Vec2::zero(),
+            shake_rotation: 0.0,
+
+            subpixel_offset: Vec2::zero(),
+
             matrix: Mat4::translation_2d(Vec2::new(viewport_width / 2.0, viewport_height / 2.0)),
         }
     }
@@ -98,17 +158,218 @@ impl Camera {
         self.viewport_height = height;
     }
 
-    /// Recalculates the transformation matrix, based on the data currently contained
-    /// within the camera.
+    /// Returns the settings that control how the camera follows its target.
+    pub fn follow_settings(&self) -> &CameraFollow {
+        &self.follow
+    }
+
+    /// Sets how the camera should follow its target.
+    pub fn set_follow_settings(&mut self, follow: CameraFollow) {
+        self.follow = follow;
+    }
+
+    /// Returns the position that the camera is following, if one has been set.
+    pub fn follow_target(&self) -> Option<Vec2<f32>> {
+        self.follow_target
+    }
+
+    /// Sets the position that the camera should follow.
+    ///
+    /// This will usually be called once per update with the position of the player,
+    /// before calling [`update`](Self::update). The camera will then move towards the
+    /// target, based on its [follow settings](Self::set_follow_settings).
+    pub fn set_follow_target(&mut self, target: Vec2<f32>) {
+        self.follow_target = Some(target);
+    }
+
+    /// Stops the camera from following its target.
+    pub fn reset_follow_target(&mut self) {
+        self.follow_target = None;
+        self.previous_target = None;
+        self.lookahead_offset = Vec2::zero();
+    }
+
+    /// Returns the area that the camera's view is restricted to, if one has been set.
+    pub fn bounds(&self) -> Option<Rectangle> {
+        self.bounds
+    }
+
+    /// Restricts the camera's view to the given area (e.g. the size of the level).
+    ///
+    /// Each time [`update`](Self::update) is called, the camera's position will be clamped
+    /// so that its [visible area](Self::visible_rect) does not extend past the bounds. If the
+    /// visible area is larger than the bounds, the camera will be centered on them instead.
+    pub fn set_bounds(&mut self, bounds: Rectangle) {
+        self.bounds = Some(bounds);
+    }
+
+    /// Allows the camera's view to move freely.
+    pub fn reset_bounds(&mut self) {
+        self.bounds = None;
+    }
+
+    /// Sets the scale of the camera, moving it so that the given point stays in the
+    /// same place on screen.
+    ///
+    /// The point should be in screen co-ordinates, relative to the camera's viewport - for
+    /// example, passing in the mouse position will zoom in or out around the cursor.
+    ///
+    /// Like the rest of the camera's fields, this change will not be applied until
+    /// [`update`](Self::update) is called.
+    pub fn zoom_around(&mut self, point: Vec2<f32>, scale: Vec2<f32>) {
+        let before = self.project(point);
+        self.scale = scale;
+        let after = self.project(point);
+
+        self.position += before - after;
+    }
+
+    /// Returns the settings that control how the camera shakes.
+    pub fn shake_settings(&self) -> &CameraShake {
+        &self.shake
+    }
+
+    /// Sets how the camera should shake when it has trauma.
+    pub fn set_shake_settings(&mut self, shake: CameraShake) {
+        self.shake = shake;
+    }
+
+    /// Returns the camera's current trauma, between `0.0` and `1.0`.
+    pub fn trauma(&self) -> f32 {
+        self.trauma
+    }
+
+    /// Adds trauma to the camera, causing it to shake.
+    ///
+    /// Trauma is clamped between `0.0` and `1.0`, and decreases each time
+    /// [`update`](Self::update) is called. The intensity of the shake is based on the
+    /// square of the trauma, so small amounts of trauma will only cause a slight shake,
+    /// while repeated hits will quickly build up to a violent one.
+    pub fn add_trauma(&mut self, amount: f32) {
+        self.trauma = (self.trauma + amount).clamp(0.0, 1.0);
+    }
+
+    /// Sets the camera's trauma, between `0.0` and `1.0`.
+    pub fn set_trauma(&mut self, trauma: f32) {
+        self.trauma = trauma.clamp(0.0, 1.0);
+    }
+
+    /// Updates the camera's behaviours, and then recalculates the transformation matrix
+    /// based on the data currently contained within the camera.
+    ///
+    /// This should usually be called once per [`State::update`](crate::State::update),
+    /// after moving the camera (or setting its follow target).
     pub fn update(&mut self) {
-        self.matrix = Mat4::translation_2d(-self.position);
-        self.matrix.rotate_z(self.rotation);
+        self.update_follow();
+        self.update_bounds();
+        self.update_shake();
+
+        self.matrix = Mat4::translation_2d(-(self.position + self.shake_offset));
+        self.matrix.rotate_z(self.rotation + self.shake_rotation);
         self.matrix
             .scale_3d(Vec3::new(self.scale.x, self.scale.y, 1.0));
         self.matrix.translate_2d(Vec2::new(
             self.viewport_width / 2.0,
             self.viewport_height / 2.0,
         ));
+
+        if self.pixel_snapping {
+            // The translation is where the world's origin ends up on screen - rounding
+            // it means that anything drawn at a whole pixel position in the world will
+            // also be drawn at a whole pixel position on screen.
+            let translation = Vec2::new(self.matrix.cols.w.x, self.matrix.cols.w.y);
+            let snapped = translation.round();
+
+            self.matrix.cols.w.x = snapped.x;
+            self.matrix.cols.w.y = snapped.y;
+            self.subpixel_offset = translation - snapped;
+        } else {
+            self.subpixel_offset = Vec2::zero();
+        }
+    }
+
+    /// Returns the part of the view's offset that was removed by
+    /// [pixel snapping](Self::pixel_snapping) during the last [`update`](Self::update),
+    /// in pixels.
+    ///
+    /// Each component will be between `-0.5` and `0.5`. If pixel snapping is disabled,
+    /// this will always be zero.
+    pub fn subpixel_offset(&self) -> Vec2<f32> {
+        self.subpixel_offset
+    }
+
+    fn update_follow(&mut self) {
+        let target = match self.follow_target {
+            Some(target) => target,
+            None => return,
+        };
+
+        let velocity = match self.previous_target {
+            Some(previous) => target - previous,
+            None => Vec2::zero(),
+        };
+
+        self.previous_target = Some(target);
+
+        let smoothing = self.follow.smoothing.clamp(0.0, 1.0);
+
+        let lookahead = velocity * self.follow.lookahead;
+        self.lookahead_offset += (lookahead - self.lookahead_offset) * smoothing;
+
+        // The camera only needs to move far enough to bring the goal back inside
+        // of the deadzone.
+        let offset = target + self.lookahead_offset - self.position;
+        let half_deadzone = self.follow.deadzone / 2.0;
+
+        let excess = Vec2::new(
+            offset.x - offset.x.clamp(-half_deadzone.x, half_deadzone.x),
+            offset.y - offset.y.clamp(-half_deadzone.y, half_deadzone.y),
+        );
+
+        self.position += excess * smoothing;
+    }
+
+    fn update_bounds(&mut self) {
+        let bounds = match self.bounds {
+            Some(bounds) => bounds,
+            None => return,
+        };
+
+        let visible = self.visible_rect();
+
+        let clamp_axis = |position: f32, visible_size: f32, min: f32, size: f32| {
+            if visible_size >= size {
+                min + size / 2.0
+            } else {
+                let half = visible_size / 2.0;
+                position.clamp(min + half, min + size - half)
+            }
+        };
+
+        self.position.x = clamp_axis(self.position.x, visible.width, bounds.x, bounds.width);
+        self.position.y = clamp_axis(self.position.y, visible.height, bounds.y, bounds.height);
+    }
+
+    fn update_shake(&mut self) {
+        if self.trauma <= 0.0 {
+            self.shake_offset = Vec2::zero();
+            self.shake_rotation = 0.0;
+            return;
+        }
+
+        let intensity = self.trauma * self.trauma;
+        let seed = self.shake.seed;
+        let time = self.shake_time;
+
+        self.shake_offset = Vec2::new(
+            self.shake.max_offset.x * intensity * noise(seed, 0, time),
+            self.shake.max_offset.y * intensity * noise(seed, 1, time),
+        );
+
+        self.shake_rotation = self.shake.max_rotation * intensity * noise(seed, 2, time);
+
+        self.shake_time += self.shake.frequency;
+        self.trauma = (self.trauma - self.shake.decay).max(0.0);
     }
 
     /// Returns the current transformation matrix.
@@ -228,6 +489,271 @@ impl Camera {
     }
 }
 
+/// A [`Camera`] paired with a viewport, which can be used to render one of several views
+/// of a scene to the same target (e.g. for local multiplayer split-screen).
+///
+/// The camera's viewport size will be kept in sync with the size of the viewport.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use firecore_tetra::Context;
+/// # use firecore_tetra::graphics::{CameraView, Rectangle};
+/// # fn draw_scene(_: &mut Context) {}
+/// # fn draw(ctx: &mut Context) {
+/// let mut left = CameraView::new(Rectangle::new(0, 0, 320, 480));
+/// let mut right = CameraView::new(Rectangle::new(320, 0, 320, 480));
+///
+/// for view in [&mut left, &mut right].iter_mut() {
+///     view.camera.update();
+///
+///     view.begin(ctx);
+///     draw_scene(ctx);
+///     view.end(ctx);
+/// }
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct CameraView {
+    /// The camera used to render the view.
+    pub camera: Camera,
+
+    viewport: Rectangle<i32>,
+}
+
+impl CameraView {
+    /// Creates a new view, with a camera that matches the size of the given viewport.
+    pub fn new(viewport: Rectangle<i32>) -> CameraView {
+        CameraView {
+            camera: Camera::new(viewport.width as f32, viewport.height as f32),
+            viewport,
+        }
+    }
+
+    /// Creates a new view from an existing camera.
+    ///
+    /// The camera's viewport size will be set to match the size of the given viewport.
+    pub fn with_camera(mut camera: Camera, viewport: Rectangle<i32>) -> CameraView {
+        camera.set_viewport_size(viewport.width as f32, viewport.height as f32);
+
+        CameraView { camera, viewport }
+    }
+
+    /// Returns the area of the screen (or canvas) that the view will be rendered to.
+    pub fn viewport(&self) -> Rectangle<i32> {
+        self.viewport
+    }
+
+    /// Sets the area of the screen (or canvas) that the view will be rendered to.
+    ///
+    /// The camera's viewport size will be updated to match, but its matrix will not be
+    /// recalculated until [`Camera::update`] is called.
+    pub fn set_viewport(&mut self, viewport: Rectangle<i32>) {
+        self.viewport = viewport;
+        self.camera
+            .set_viewport_size(viewport.width as f32, viewport.height as f32);
+    }
+
+    /// Starts rendering the view.
+    ///
+    /// This sets the [viewport](graphics::set_viewport) and pushes the camera's matrix onto
+    /// the [transform stack](graphics::push_transform). Call [`end`](Self::end) once you have
+    /// finished drawing the view.
+    pub fn begin(&self, ctx: &mut Context) {
+        graphics::set_viewport(ctx, self.viewport);
+        graphics::push_transform(ctx, self.camera.as_matrix());
+    }
+
+    /// Finishes rendering the view, popping the camera's matrix from the transform stack
+    /// and resetting the viewport.
+    pub fn end(&self, ctx: &mut Context) {
+        graphics::pop_transform(ctx);
+        graphics::reset_viewport(ctx);
+    }
+
+    /// Projects a point from screen co-ordinates to the co-ordinates of the view's camera.
+    ///
+    /// This takes into account the position of the viewport, but not any other
+    /// transformations being made to the view (e.g. screen scaling).
+    pub fn project(&self, point: Vec2<f32>) -> Vec2<f32> {
+        let origin = Vec2::new(self.viewport.x as f32, self.viewport.y as f32);
+        self.camera.project(point - origin)
+    }
+
+    /// Projects a point from the co-ordinates of the view's camera to screen co-ordinates.
+    pub fn unproject(&self, point: Vec2<f32>) -> Vec2<f32> {
+        let origin = Vec2::new(self.viewport.x as f32, self.viewport.y as f32);
+        self.camera.unproject(point) + origin
+    }
+
+    /// Returns the mouse's position in the co-ordinates of the view's camera.
+    ///
+    /// This is a shortcut for calling [`project(input::get_mouse_position(ctx))`](Self::project).
+    pub fn mouse_position(&self, ctx: &Context) -> Vec2<f32> {
+        self.project(input::get_mouse_position(ctx))
+    }
+}
+
+/// Settings that control how a [`Camera`] follows its target.
+///
+/// All of the settings are applied once per call to [`Camera::update`], so they do not
+/// depend on the frame rate.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CameraFollow {
+    /// How quickly the camera catches up to its target, between `0.0` and `1.0`.
+    /// Defaults to `1.0`.
+    ///
+    /// This is the fraction of the remaining distance that the camera will move each
+    /// update. A value of `1.0` will snap to the target immediately, while lower values
+    /// will cause the camera to smoothly ease towards it.
+    pub smoothing: f32,
+
+    /// The size of the area around the center of the view in which the target can move
+    /// without the camera following it. Defaults to `(0.0, 0.0)`.
+    pub deadzone: Vec2<f32>,
+
+    /// How far ahead of the target the camera should look, as a multiple of how far the
+    /// target moved since the last update. Defaults to `0.0`.
+    ///
+    /// For example, a value of `10.0` will center the camera on where the target would be
+    /// in ten updates' time, if it kept moving at the same speed.
+    pub lookahead: f32,
+}
+
+impl CameraFollow {
+    /// Creates a new set of follow settings, which will snap the camera to its target.
+    pub fn new() -> CameraFollow {
+        CameraFollow::default()
+    }
+
+    /// Sets how quickly the camera catches up to its target.
+    pub fn smoothing(mut self, smoothing: f32) -> CameraFollow {
+        self.smoothing = smoothing;
+        self
+    }
+
+    /// Sets the size of the deadzone.
+    pub fn deadzone(mut self, deadzone: Vec2<f32>) -> CameraFollow {
+        self.deadzone = deadzone;
+        self
+    }
+
+    /// Sets how far ahead of the target the camera should look.
+    pub fn lookahead(mut self, lookahead: f32) -> CameraFollow {
+        self.lookahead = lookahead;
+        self
+    }
+}
+
+impl Default for CameraFollow {
+    fn default() -> CameraFollow {
+        CameraFollow {
+            smoothing: 1.0,
+            deadzone: Vec2::zero(),
+            lookahead: 0.0,
+        }
+    }
+}
+
+/// Settings that control how a [`Camera`] shakes when it has trauma.
+///
+/// The shake is generated from smooth noise, rather than picking a new random offset
+/// every update, so it looks less jittery at low frequencies. Given the same seed and
+/// the same sequence of updates, the shake will always be identical.
+///
+/// The shake does not affect the camera's `position` or `rotation` fields, or methods
+/// such as [`Camera::visible_rect`] - it is only applied to the camera's matrix.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CameraShake {
+    /// The maximum distance that the view can be offset by. Defaults to `(8.0, 8.0)`.
+    pub max_offset: Vec2<f32>,
+
+    /// The maximum angle that the view can be rotated by, in radians. Defaults to `0.05`.
+    pub max_rotation: f32,
+
+    /// How much trauma is removed each update. Defaults to `0.02`.
+    pub decay: f32,
+
+    /// How quickly the shake changes direction, in noise samples per update.
+    /// Defaults to `0.5`.
+    pub frequency: f32,
+
+    /// The seed used to generate the noise. Defaults to `0`.
+    pub seed: u64,
+}
+
+impl CameraShake {
+    /// Creates a new set of shake settings, with the default values.
+    pub fn new() -> CameraShake {
+        CameraShake::default()
+    }
+
+    /// Sets the maximum distance that the view can be offset by.
+    pub fn max_offset(mut self, max_offset: Vec2<f32>) -> CameraShake {
+        self.max_offset = max_offset;
+        self
+    }
+
+    /// Sets the maximum angle that the view can be rotated by, in radians.
+    pub fn max_rotation(mut self, max_rotation: f32) -> CameraShake {
+        self.max_rotation = max_rotation;
+        self
+    }
+
+    /// Sets how much trauma is removed each update.
+    pub fn decay(mut self, decay: f32) -> CameraShake {
+        self.decay = decay;
+        self
+    }
+
+    /// Sets how quickly the shake changes direction.
+    pub fn frequency(mut self, frequency: f32) -> CameraShake {
+        self.frequency = frequency;
+        self
+    }
+
+    /// Sets the seed used to generate the noise.
+    pub fn seed(mut self, seed: u64) -> CameraShake {
+        self.seed = seed;
+        self
+    }
+}
+
+impl Default for CameraShake {
+    fn default() -> CameraShake {
+        CameraShake {
+            max_offset: Vec2::new(8.0, 8.0),
+            max_rotation: 0.05,
+            decay: 0.02,
+            frequency: 0.5,
+            seed: 0,
+        }
+    }
+}
+
+/// Samples one-dimensional value noise, returning a value between `-1.0` and `1.0`.
+///
+/// Each channel gives an independent stream of noise for the same seed.
+fn noise(seed: u64, channel: u64, time: f32) -> f32 {
+    let value = |index: u64| {
+        let mut rng = Rng::new(
+            seed ^ channel.wrapping_mul(0x9E37_79B9_7F4A_7C15)
+                ^ index.wrapping_mul(0xD6E8_FEB8_6659_FD93),
+        );
+
+        rng.next_f32() * 2.0 - 1.0
+    };
+
+    let index = time.floor();
+    let fraction = time - index;
+    let smooth = fraction * fraction * (3.0 - 2.0 * fraction);
+
+    let a = value(index as u64);
+    let b = value(index as u64 + 1);
+
+    a + (b - a) * smooth
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -323,4 +849,150 @@ mod tests {
         assert!(rect.width - 150.0 < 0.001);
         assert!(rect.height - 400.0 < 0.001);
     }
+
+    #[test]
+    fn follow_with_deadzone() {
+        let mut camera = Camera::new(800.0, 600.0);
+        camera.set_follow_settings(
+            CameraFollow::new()
+                .smoothing(0.5)
+                .deadzone(Vec2::new(100.0, 100.0)),
+        );
+
+        // Targets inside of the deadzone shouldn't move the camera.
+        camera.set_follow_target(Vec2::new(40.0, -40.0));
+        camera.update();
+        assert_eq!(camera.position, Vec2::zero());
+
+        // Targets outside of the deadzone should move the camera halfway towards
+        // the edge of the deadzone.
+        camera.set_follow_target(Vec2::new(150.0, 0.0));
+        camera.update();
+        assert_eq!(camera.position, Vec2::new(50.0, 0.0));
+
+        camera.update();
+        assert_eq!(camera.position, Vec2::new(75.0, 0.0));
+    }
+
+    #[test]
+    fn follow_with_lookahead() {
+        let mut camera = Camera::new(800.0, 600.0);
+        camera.set_follow_settings(CameraFollow::new().lookahead(10.0));
+
+        camera.set_follow_target(Vec2::new(0.0, 0.0));
+        camera.update();
+        assert_eq!(camera.position, Vec2::zero());
+
+        camera.set_follow_target(Vec2::new(2.0, 0.0));
+        camera.update();
+        assert_eq!(camera.position, Vec2::new(22.0, 0.0));
+
+        camera.reset_follow_target();
+        camera.update();
+        assert_eq!(camera.position, Vec2::new(22.0, 0.0));
+    }
+
+    #[test]
+    fn clamp_to_bounds() {
+        let mut camera = Camera::new(200.0, 100.0);
+        camera.set_bounds(Rectangle::new(0.0, 0.0, 1000.0, 50.0));
+
+        camera.position = Vec2::new(-500.0, 500.0);
+        camera.update();
+
+        // The view is taller than the bounds, so it should be centered vertically.
+        assert_eq!(camera.position, Vec2::new(100.0, 25.0));
+
+        camera.position = Vec2::new(2000.0, 0.0);
+        camera.scale = Vec2::new(4.0, 4.0);
+        camera.update();
+
+        assert_eq!(camera.position, Vec2::new(975.0, 12.5));
+    }
+
+    #[test]
+    fn zoom_around_point() {
+        let mut camera = Camera::new(800.0, 600.0);
+        camera.position = Vec2::new(100.0, 50.0);
+
+        let point = Vec2::new(600.0, 100.0);
+        let world = camera.project(point);
+
+        camera.zoom_around(point, Vec2::new(2.0, 2.0));
+
+        assert_eq!(camera.scale, Vec2::new(2.0, 2.0));
+        assert_eq!(camera.project(point), world);
+    }
+
+    #[test]
+    fn shake_is_deterministic() {
+        let shake = |seed| {
+            let mut camera = Camera::new(800.0, 600.0);
+            camera.set_shake_settings(CameraShake::new().decay(0.25).seed(seed));
+            camera.add_trauma(0.8);
+            camera.add_trauma(0.8);
+
+            assert_eq!(camera.trauma(), 1.0);
+
+            let mut offsets = Vec::new();
+
+            for _ in 0..5 {
+                camera.update();
+                offsets.push(camera.shake_offset);
+            }
+
+            assert_eq!(camera.trauma(), 0.0);
+            assert_eq!(camera.shake_offset, Vec2::zero());
+            assert!(offsets.iter().all(|o| o.x.abs() <= 8.0 && o.y.abs() <= 8.0));
+
+            offsets
+        };
+
+        assert_eq!(shake(1), shake(1));
+        assert_ne!(shake(1), shake(2));
+    }
+
+    #[test]
+    fn pixel_snapping() {
+        let mut camera = Camera::new(320.0, 180.0);
+        camera.position = Vec2::new(10.25, -3.75);
+        camera.scale = Vec2::new(2.0, 2.0);
+        camera.update();
+
+        assert_eq!(camera.subpixel_offset(), Vec2::zero());
+        assert_eq!(camera.as_matrix().cols.w.x, 139.5);
+
+        camera.pixel_snapping = true;
+        camera.update();
+
+        let matrix = camera.as_matrix();
+        assert_eq!(matrix.cols.w.x, 140.0);
+        assert_eq!(matrix.cols.w.y, 98.0);
+        assert_eq!(camera.subpixel_offset(), Vec2::new(-0.5, -0.5));
+    }
+
+    #[test]
+    fn camera_view_projections() {
+        let mut view = CameraView::new(Rectangle::new(320, 0, 320, 480));
+        view.camera.position = Vec2::new(100.0, 100.0);
+
+        assert_eq!(view.camera.viewport_width, 320.0);
+        assert_eq!(
+            view.project(Vec2::new(480.0, 240.0)),
+            Vec2::new(100.0, 100.0)
+        );
+        assert_eq!(
+            view.unproject(Vec2::new(100.0, 100.0)),
+            Vec2::new(480.0, 240.0)
+        );
+
+        view.set_viewport(Rectangle::new(0, 240, 640, 240));
+
+        assert_eq!(view.camera.viewport_width, 640.0);
+        assert_eq!(view.camera.viewport_height, 240.0);
+        assert_eq!(
+            view.project(Vec2::new(320.0, 360.0)),
+            Vec2::new(100.0, 100.0)
+        );
+    }
 }
@@ -1,7 +1,7 @@
 use std::rc::Rc;
 
 use crate::error::Result;
-use crate::graphics::{DrawParams, FilterMode, Texture};
+use crate::graphics::{Color, DrawParams, FilterMode, Texture, TextureFormat, WrapMode};
 use crate::platform::{RawCanvas, RawRenderbuffer};
 use crate::context::Context;
 
@@ -16,8 +16,11 @@ use super::ImageData;
 pub struct CanvasBuilder {
     width: i32,
     height: i32,
+    format: TextureFormat,
+    attachments: Vec<TextureFormat>,
     samples: u8,
     stencil_buffer: bool,
+    depth_buffer: bool,
 }
 
 impl CanvasBuilder {
@@ -30,11 +33,52 @@ impl CanvasBuilder {
         CanvasBuilder {
             width,
             height,
+            format: TextureFormat::Rgba8,
+            attachments: Vec::new(),
             samples: 0,
             stencil_buffer: false,
+            depth_buffer: false,
         }
     }
 
+    /// Sets the format of the canvas' pixel data.
+    ///
+    /// For example, [`TextureFormat::Rgba16F`] can be used to render values outside of the
+    /// `0.0` to `1.0` range, which is useful for HDR effects. Defaults to
+    /// [`TextureFormat::Rgba8`].
+    pub fn format(&mut self, format: TextureFormat) -> &mut CanvasBuilder {
+        self.format = format;
+        self
+    }
+
+    /// Adds an additional color attachment to the canvas, with the given format.
+    ///
+    /// This allows a single draw to output to multiple textures at once (sometimes
+    /// referred to as 'multiple render targets'), which can be useful for techniques
+    /// such as deferred lighting. The first color attachment is always the canvas'
+    /// main [`texture`](Canvas::texture), and each additional attachment can be accessed
+    /// via [`Canvas::attachment`], starting from index 1.
+    ///
+    /// To write to the additional attachments, your fragment shader must declare
+    /// an output for each of them, using the index of the attachment as the output's
+    /// location. As Tetra uses OpenGL 3.2, this requires the `GL_ARB_explicit_attrib_location`
+    /// extension, which is supported by almost all drivers:
+    ///
+    /// ```glsl
+    /// #version 150
+    /// #extension GL_ARB_explicit_attrib_location : require
+    ///
+    /// layout(location = 0) out vec4 o_color;
+    /// layout(location = 1) out vec4 o_normal;
+    /// ```
+    ///
+    /// The number of attachments that can be used varies between graphics cards, but
+    /// at least 8 (including the main texture) are guaranteed to be available.
+    pub fn add_attachment(&mut self, format: TextureFormat) -> &mut CanvasBuilder {
+        self.attachments.push(format);
+        self
+    }
+
     /// Sets the level of multisample anti-aliasing to use.
     ///
     /// The number of samples that can be used varies between graphics cards - `2`, `4` and `8` are reasonably
@@ -60,26 +104,53 @@ impl CanvasBuilder {
         self
     }
 
+    /// Sets whether the canvas should have a depth buffer.
+    ///
+    /// Setting this to `true` allows you to use [depth testing](super::set_depth_state) while
+    /// rendering to the canvas, at the cost of some extra video RAM usage. The depth buffer
+    /// will initially be cleared to `-1.0`.
+    pub fn depth_buffer(&mut self, enabled: bool) -> &mut CanvasBuilder {
+        self.depth_buffer = enabled;
+        self
+    }
+
     /// Builds the canvas.
     ///
     /// # Errors
     ///
     /// * [`TetraError::PlatformError`](crate::TetraError::PlatformError) will be returned if the underlying
-    /// graphics API encounters an error.
+    /// graphics API encounters an error, or if more color attachments were requested than are
+    /// supported by the graphics card.
     pub fn build(&self, ctx: &mut Context) -> Result<Canvas> {
+        let mut formats = Vec::with_capacity(self.attachments.len() + 1);
+        formats.push(self.format);
+        formats.extend_from_slice(&self.attachments);
+
         let attachments = ctx.device.new_canvas(
             self.width,
             self.height,
+            &formats,
             ctx.graphics.default_filter_mode,
             self.samples,
             self.stencil_buffer,
+            self.depth_buffer,
         )?;
 
+        let mut textures = attachments
+            .color
+            .into_iter()
+            .map(|handle| Texture::from_raw(handle, ctx.graphics.default_filter_mode));
+
         Ok(Canvas {
             handle: Rc::new(attachments.canvas),
-            texture: Texture::from_raw(attachments.color, ctx.graphics.default_filter_mode),
-            stencil_buffer: attachments.depth_stencil.map(Rc::new),
-            multisample: attachments.multisample_color.map(Rc::new),
+            texture: textures.next().expect("canvas should have a color attachment"),
+            attachments: textures.collect(),
+            depth_stencil: attachments.depth_stencil.map(Rc::new),
+            multisample: attachments
+                .multisample_color
+                .into_iter()
+                .map(Rc::new)
+                .collect(),
         })
     }
 }
@@ -112,8 +183,9 @@ impl CanvasBuilder {
 pub struct Canvas {
     pub(crate) handle: Rc<RawCanvas>,
     pub(crate) texture: Texture,
-    pub(crate) stencil_buffer: Option<Rc<RawRenderbuffer>>,
-    pub(crate) multisample: Option<Rc<RawRenderbuffer>>,
+    pub(crate) attachments: Vec<Texture>,
+    pub(crate) depth_stencil: Option<Rc<RawRenderbuffer>>,
+    pub(crate) multisample: Vec<Rc<RawRenderbuffer>>,
 }
 
 impl Canvas {
@@ -179,16 +251,63 @@ impl Canvas {
         self.texture.size()
     }
 
+    /// Returns the format of the canvas' pixel data.
+    pub fn format(&self) -> TextureFormat {
+        self.texture.format()
+    }
+
     /// Returns the filter mode being used by the canvas.
     pub fn filter_mode(&self) -> FilterMode {
         self.texture.filter_mode()
     }
 
     /// Sets the filter mode that should be used by the canvas.
+    ///
+    /// The mipmapped filter modes will only have an effect once mipmaps have been
+    /// [generated](Self::generate_mipmaps) for the canvas.
     pub fn set_filter_mode(&mut self, ctx: &mut Context, filter_mode: FilterMode) {
         self.texture.set_filter_mode(ctx, filter_mode);
     }
 
+    /// Returns the wrap modes being used by the canvas, on the horizontal and
+    /// vertical axes respectively.
+    pub fn wrap_mode(&self) -> (WrapMode, WrapMode) {
+        self.texture.wrap_mode()
+    }
+
+    /// Sets the wrap modes that should be used by the canvas, on the horizontal and
+    /// vertical axes respectively.
+    pub fn set_wrap_mode(&mut self, ctx: &mut Context, horizontal: WrapMode, vertical: WrapMode) {
+        self.texture.set_wrap_mode(ctx, horizontal, vertical);
+    }
+
+    /// Returns the border color being used by the canvas.
+    pub fn border_color(&self) -> Color {
+        self.texture.border_color()
+    }
+
+    /// Sets the color that will be returned when sampling outside of the canvas' bounds
+    /// on an axis that is using [`WrapMode::ClampToBorder`].
+    pub fn set_border_color(&mut self, ctx: &mut Context, color: Color) {
+        self.texture.set_border_color(ctx, color);
+    }
+
+    /// Returns whether mipmaps have been generated for the canvas.
+    pub fn has_mipmaps(&self) -> bool {
+        self.texture.has_mipmaps()
+    }
+
+    /// Generates mipmaps for the canvas.
+    ///
+    /// Once this has been called, the mipmaps will be regenerated automatically
+    /// whenever you switch away from the canvas (after it has been [resolved](#resolving),
+    /// if it is multisampled).
+    ///
+    /// See [`Texture::generate_mipmaps`] for more information.
+    pub fn generate_mipmaps(&mut self, ctx: &mut Context) {
+        self.texture.generate_mipmaps(ctx);
+    }
+
     /// Gets the canvas' data from the GPU.
     ///
     /// This can be useful if you need to do some image processing on the CPU,
@@ -204,7 +323,17 @@ impl Canvas {
         self.texture.get_data(ctx)
     }
 
-    /// Writes RGBA pixel data to a specified region of the canvas.
+    /// Gets the canvas' data from the GPU, in the canvas' [format](Self::format).
+    ///
+    /// The same caveats apply as for [`get_data`](Self::get_data).
+    pub fn get_raw_data(&self, ctx: &mut Context) -> Vec<u8> {
+        self.texture.get_raw_data(ctx)
+    }
+
+    /// Writes pixel data to a specified region of the canvas.
+    ///
+    /// The data should be in the canvas' [format](Self::format), laid out as described
+    /// in the docs for [`TextureFormat`].
     ///
     /// This method requires you to provide enough data to fill the target rectangle.
     /// If you provide too little data, an error will be returned.
@@ -234,7 +363,10 @@ impl Canvas {
         self.texture.set_data(ctx, x, y, width, height, data)
     }
 
-    /// Overwrites the entire canvas with new RGBA pixel data.
+    /// Overwrites the entire canvas with new pixel data.
+    ///
+    /// The data should be in the canvas' [format](Self::format), laid out as described
+    /// in the docs for [`TextureFormat`].
     ///
     /// This method requires you to provide enough data to fill the canvas.
     /// If you provide too little data, an error will be returned.
@@ -262,4 +394,26 @@ impl Canvas {
     pub fn texture(&self) -> &Texture {
         &self.texture
     }
+
+    /// Returns a reference to the texture for one of the canvas' color attachments.
+    ///
+    /// Index 0 is the canvas' main [`texture`](Self::texture), and any additional
+    /// attachments that were [added via the builder](CanvasBuilder::add_attachment)
+    /// follow on from there. The same caveats apply as for [`texture`](Self::texture).
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds.
+    pub fn attachment(&self, index: usize) -> &Texture {
+        match index {
+            0 => &self.texture,
+            _ => &self.attachments[index - 1],
+        }
+    }
+
+    /// Returns the number of color attachments that the canvas has, including the
+    /// main texture.
+    pub fn attachment_count(&self) -> usize {
+        self.attachments.len() + 1
+    }
 }
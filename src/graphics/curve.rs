@@ -0,0 +1,128 @@
+use crate::graphics::Color;
+use crate::math::Vec2;
+
+/// A value that can be interpolated by a [`Curve`].
+pub trait CurveValue: Copy {
+    /// Linearly interpolates between `a` and `b`, by the factor `t` (between 0.0 and 1.0).
+    fn lerp(a: Self, b: Self, t: f32) -> Self;
+}
+
+impl CurveValue for f32 {
+    fn lerp(a: f32, b: f32, t: f32) -> f32 {
+        a + (b - a) * t
+    }
+}
+
+impl CurveValue for Vec2<f32> {
+    fn lerp(a: Vec2<f32>, b: Vec2<f32>, t: f32) -> Vec2<f32> {
+        a + (b - a) * t
+    }
+}
+
+impl CurveValue for Color {
+    fn lerp(a: Color, b: Color, t: f32) -> Color {
+        Color::rgba(
+            CurveValue::lerp(a.r, b.r, t),
+            CurveValue::lerp(a.g, b.g, t),
+            CurveValue::lerp(a.b, b.b, t),
+            CurveValue::lerp(a.a, b.a, t),
+        )
+    }
+}
+
+/// A value that changes over a normalized range, such as the lifetime of a particle
+/// or the length of a gradient.
+///
+/// A curve is made up of keys, each of which has a position (between 0.0 at the
+/// start of the range, and 1.0 at the end) and a value. Values between keys
+/// are linearly interpolated.
+///
+/// # Examples
+///
+/// ```
+/// # use firecore_tetra::graphics::Curve;
+/// // Grows to full size, then shrinks away:
+/// let size = Curve::new(0.0).with_key(0.5, 1.0).with_key(1.0, 0.0);
+///
+/// assert_eq!(size.sample(0.25), 0.5);
+/// assert_eq!(size.sample(0.75), 0.5);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Curve<T> {
+    keys: Vec<(f32, T)>,
+}
+
+impl<T> Curve<T>
+where
+    T: CurveValue,
+{
+    /// Creates a new curve, with a single key at the start.
+    ///
+    /// This is equivalent to a constant value until more keys are added.
+    pub fn new(start: T) -> Curve<T> {
+        Curve {
+            keys: vec![(0.0, start)],
+        }
+    }
+
+    /// Creates a new curve that moves linearly between two values.
+    pub fn linear(start: T, end: T) -> Curve<T> {
+        Curve {
+            keys: vec![(0.0, start), (1.0, end)],
+        }
+    }
+
+    /// Adds a key to the curve.
+    ///
+    /// The position will be clamped between 0.0 and 1.0. Keys can be added in any order.
+    pub fn with_key(mut self, position: f32, value: T) -> Curve<T> {
+        let position = position.clamp(0.0, 1.0);
+        let index = self.keys.partition_point(|(p, _)| *p <= position);
+
+        self.keys.insert(index, (position, value));
+        self
+    }
+
+    /// Returns the keys of the curve, in order of their position.
+    pub fn keys(&self) -> &[(f32, T)] {
+        &self.keys
+    }
+
+    /// Samples the curve at the given position (between 0.0 and 1.0).
+    pub fn sample(&self, position: f32) -> T {
+        let next = self.keys.partition_point(|(p, _)| *p <= position);
+
+        if next == 0 {
+            return self.keys[0].1;
+        }
+
+        if next == self.keys.len() {
+            return self.keys[next - 1].1;
+        }
+
+        let (start_pos, start) = self.keys[next - 1];
+        let (end_pos, end) = self.keys[next];
+
+        T::lerp(start, end, (position - start_pos) / (end_pos - start_pos))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn curve_sampling() {
+        let curve = Curve::new(0.0).with_key(1.0, 10.0).with_key(0.5, 2.0);
+
+        assert_eq!(curve.keys().len(), 3);
+        assert_eq!(curve.sample(-1.0), 0.0);
+        assert_eq!(curve.sample(0.25), 1.0);
+        assert_eq!(curve.sample(0.75), 6.0);
+        assert_eq!(curve.sample(2.0), 10.0);
+
+        let constant = Curve::new(Color::RED);
+
+        assert_eq!(constant.sample(0.5), Color::RED);
+    }
+}
@@ -1,11 +1,22 @@
-use crate::graphics::Color;
+use crate::graphics::{Color, Rectangle};
 use crate::math::{Mat4, Vec2, Vec3};
 
 /// Parameters that can be manipulated when drawing an object.
 ///
 /// You can either use this as a builder by calling [`DrawParams::new`] and then chaining methods, or
 /// construct it manually - whichever you find more pleasant to write.
+///
+/// # Serde
+///
+/// Serialization and deserialization of this type (via [Serde](https://serde.rs/))
+/// can be enabled via the `serde_support` feature. Any fields that are missing from
+/// the serialized data will be set to their default values.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+    feature = "serde_support",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(default)
+)]
 pub struct DrawParams {
     /// The position that the graphic should be drawn at. Defaults to `(0.0, 0.0)`.
     pub position: Vec2<f32>,
@@ -25,8 +36,55 @@ pub struct DrawParams {
     /// The rotation of the graphic, in radians. Defaults to `0.0`.
     pub rotation: f32,
 
+    /// Whether the graphic should be flipped horizontally. Defaults to `false`.
+    ///
+    /// The graphic will be flipped around the origin, so a graphic with its origin at the
+    /// center will be flipped in place. This has the same effect as negating `scale.x`, but
+    /// can be changed without affecting the scale.
+    pub flip_x: bool,
+
+    /// Whether the graphic should be flipped vertically. Defaults to `false`.
+    ///
+    /// The graphic will be flipped around the origin, so a graphic with its origin at the
+    /// center will be flipped in place. This has the same effect as negating `scale.y`, but
+    /// can be changed without affecting the scale.
+    pub flip_y: bool,
+
+    /// The amount that the graphic should be skewed (or sheared) along each axis.
+    /// Defaults to `(0.0, 0.0)`.
+    ///
+    /// This is applied after scaling and before rotation. A skew of `(1.0, 0.0)` will move
+    /// each point of the graphic horizontally by its vertical distance from the origin,
+    /// turning a square into a parallelogram.
+    pub skew: Vec2<f32>,
+
+    /// The region of the texture that should be drawn, in pixels. Defaults to `None`,
+    /// which will draw the entire texture.
+    ///
+    /// This is used by [`Texture::draw`](super::Texture::draw) and
+    /// [`Canvas::draw`](super::Canvas::draw) - other types of graphic will ignore it.
+    pub source: Option<Rectangle>,
+
     /// A color to multiply the graphic by. Defaults to [`Color::WHITE`].
     pub color: Color,
+
+    /// Colors to multiply each corner of the graphic by, in the order top left, top right,
+    /// bottom right, bottom left. Defaults to `None`.
+    ///
+    /// The colors will be blended across the graphic, which can be used to draw simple
+    /// gradients. They are combined with `color`, and follow the corners of the texture
+    /// when the graphic is flipped. For graphics that are made up of multiple quads (e.g.
+    /// [`Text`](super::text::Text)), the colors will be applied to each quad. Meshes
+    /// ignore this setting.
+    pub corner_colors: Option<[Color; 4]>,
+
+    /// The depth that the graphic should be drawn at, between `-1.0` and `1.0`. Defaults to `0.0`.
+    ///
+    /// This is only used when [depth testing](super::set_depth_state) is enabled - graphics
+    /// with a higher depth will be drawn in front of graphics with a lower depth, regardless
+    /// of the order in which they were drawn. For meshes, this is added to the depth of each
+    /// [`Vertex`](super::mesh::Vertex).
+    pub depth: f32,
 }
 
 impl DrawParams {
@@ -59,23 +117,90 @@ impl DrawParams {
         self
     }
 
+    /// Sets whether the graphic should be flipped horizontally.
+    pub fn flip_x(mut self, flip_x: bool) -> DrawParams {
+        self.flip_x = flip_x;
+        self
+    }
+
+    /// Sets whether the graphic should be flipped vertically.
+    pub fn flip_y(mut self, flip_y: bool) -> DrawParams {
+        self.flip_y = flip_y;
+        self
+    }
+
+    /// Sets the amount that the graphic should be skewed along each axis.
+    pub fn skew(mut self, skew: Vec2<f32>) -> DrawParams {
+        self.skew = skew;
+        self
+    }
+
+    /// Sets the region of the texture that should be drawn.
+    pub fn source(mut self, source: Rectangle) -> DrawParams {
+        self.source = Some(source);
+        self
+    }
+
     /// Sets the color to multiply the graphic by.
     pub fn color(mut self, color: Color) -> DrawParams {
         self.color = color;
         self
     }
 
+    /// Sets the colors to multiply each corner of the graphic by, in the order
+    /// top left, top right, bottom right, bottom left.
+    pub fn corner_colors(mut self, corner_colors: [Color; 4]) -> DrawParams {
+        self.corner_colors = Some(corner_colors);
+        self
+    }
+
+    /// Sets the depth that the graphic should be drawn at.
+    pub fn depth(mut self, depth: f32) -> DrawParams {
+        self.depth = depth;
+        self
+    }
+
     /// Creates a new transformation matrix equivalent to this set of params.
     ///
-    /// This method does not take into account `color`, as it cannot
-    /// be represented via a matrix.
+    /// This method does not take into account `color`, `corner_colors` or `source`, as
+    /// they cannot be represented via a matrix.
     pub fn to_matrix(&self) -> Mat4<f32> {
+        let scale = self.flipped_scale();
+
         let mut matrix = Mat4::translation_2d(-self.origin);
-        matrix.scale_3d(Vec3::from(self.scale));
+        matrix.scale_3d(Vec3::new(scale.x, scale.y, 1.0));
+
+        if self.skew != Vec2::zero() {
+            #[rustfmt::skip]
+            let skew = Mat4::new(
+                1.0, self.skew.x, 0.0, 0.0,
+                self.skew.y, 1.0, 0.0, 0.0,
+                0.0, 0.0, 1.0, 0.0,
+                0.0, 0.0, 0.0, 1.0,
+            );
+
+            matrix = skew * matrix;
+        }
+
         matrix.rotate_z(self.rotation);
-        matrix.translate_2d(self.position);
+        matrix.translate_3d(Vec3::new(self.position.x, self.position.y, self.depth));
         matrix
     }
+
+    /// Returns the scale of the graphic, with `flip_x` and `flip_y` applied.
+    pub(crate) fn flipped_scale(&self) -> Vec2<f32> {
+        let mut scale = self.scale;
+
+        if self.flip_x {
+            scale.x = -scale.x;
+        }
+
+        if self.flip_y {
+            scale.y = -scale.y;
+        }
+
+        scale
+    }
 }
 
 impl Default for DrawParams {
@@ -85,7 +210,13 @@ impl Default for DrawParams {
             scale: Vec2::new(1.0, 1.0),
             origin: Vec2::new(0.0, 0.0),
             rotation: 0.0,
+            flip_x: false,
+            flip_y: false,
+            skew: Vec2::new(0.0, 0.0),
+            source: None,
             color: Color::WHITE,
+            corner_colors: None,
+            depth: 0.0,
         }
     }
 }
@@ -104,3 +235,50 @@ impl From<DrawParams> for Mat4<f32> {
         params.to_matrix()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn transform(params: &DrawParams, point: Vec2<f32>) -> Vec2<f32> {
+        let point = params
+            .to_matrix()
+            .mul_point(Vec3::new(point.x, point.y, 0.0));
+        Vec2::new(point.x, point.y)
+    }
+
+    #[test]
+    fn flip_around_origin() {
+        let params = DrawParams::new()
+            .position(Vec2::new(100.0, 100.0))
+            .origin(Vec2::new(8.0, 8.0))
+            .scale(Vec2::new(2.0, 1.0))
+            .flip_x(true);
+
+        assert_eq!(params.flipped_scale(), Vec2::new(-2.0, 1.0));
+        assert_eq!(
+            transform(&params, Vec2::new(8.0, 8.0)),
+            Vec2::new(100.0, 100.0)
+        );
+        assert_eq!(
+            transform(&params, Vec2::new(0.0, 0.0)),
+            Vec2::new(116.0, 92.0)
+        );
+    }
+
+    #[test]
+    fn skew() {
+        let params = DrawParams::new()
+            .position(Vec2::new(10.0, 0.0))
+            .skew(Vec2::new(0.5, 0.0));
+
+        assert_eq!(
+            transform(&params, Vec2::new(0.0, 0.0)),
+            Vec2::new(10.0, 0.0)
+        );
+        assert_eq!(
+            transform(&params, Vec2::new(0.0, 10.0)),
+            Vec2::new(15.0, 10.0)
+        );
+    }
+}
@@ -1,21 +1,32 @@
 //! Functions and types relating to meshes and shape drawing.
 
 pub use lyon_tessellation::path::builder::BorderRadii;
+pub use lyon_tessellation::{FillRule, LineCap, LineJoin};
+
+mod gradient;
+mod path;
+#[cfg(feature = "svg")]
+mod svg;
+
+pub use gradient::*;
+pub use path::*;
 
 use std::rc::Rc;
 
 use bytemuck::{Pod, Zeroable};
 use lyon_tessellation::geom::euclid::{Point2D, Size2D};
 use lyon_tessellation::math::{Angle, Point, Rect, Vector};
-use lyon_tessellation::path::builder::{Build, PathBuilder};
-use lyon_tessellation::path::{Polygon, Winding};
+use lyon_tessellation::path::builder::PathBuilder as _;
+use lyon_tessellation::path::iterator::PathIterator;
+use lyon_tessellation::path::{Path as LyonPath, PathEvent, Polygon, Winding};
 use lyon_tessellation::{
-    BuffersBuilder, FillOptions, FillTessellator, FillVertex, FillVertexConstructor, StrokeOptions,
-    StrokeTessellator, StrokeVertex, StrokeVertexConstructor, VertexBuffers,
+    BuffersBuilder, FillOptions as LyonFillOptions, FillTessellator, FillVertex,
+    FillVertexConstructor, Side, StrokeOptions as LyonStrokeOptions, StrokeTessellator,
+    StrokeVertex, StrokeVertexConstructor, VertexBuffers,
 };
 
 use crate::graphics::{self, ActiveCanvas, ActiveShader, Color, DrawParams, Rectangle, Texture};
-use crate::math::Vec2;
+use crate::math::{Vec2, Vec4};
 use crate::platform::{RawIndexBuffer, RawVertexBuffer};
 use crate::context::Context;
 use crate::{Result, TetraError};
@@ -40,17 +51,63 @@ pub struct Vertex {
     /// This will be multiplied by the `color` of the `DrawParams` when drawing a
     /// mesh.
     pub color: Color,
+
+    /// The depth of the vertex, between `-1.0` and `1.0`.
+    ///
+    /// This is only used when [depth testing](super::set_depth_state) is enabled - vertices
+    /// with a higher depth are closer to the viewer. Vertices outside of the valid range
+    /// will be clipped.
+    pub depth: f32,
 }
 
 impl Vertex {
-    /// Creates a new vertex.
+    /// Creates a new vertex, with a depth of `0.0`.
     pub fn new(position: Vec2<f32>, uv: Vec2<f32>, color: Color) -> Vertex {
         Vertex {
             position,
             uv,
             color,
+            depth: 0.0,
         }
     }
+
+    /// Sets the depth of the vertex.
+    pub fn with_depth(mut self, depth: f32) -> Vertex {
+        self.depth = depth;
+        self
+    }
+
+    /// Returns the layout of the standard vertex data.
+    ///
+    /// This binds the fields to the `a_position`, `a_uv`, `a_color` and `a_depth`
+    /// attributes that are described in the [`Shader`](super::Shader) docs.
+    pub fn layout() -> VertexLayout {
+        VertexLayout::new(std::mem::size_of::<Vertex>())
+            .with_attribute(VertexAttribute::with_location(
+                0,
+                2,
+                VertexAttributeType::F32,
+                0,
+            ))
+            .with_attribute(VertexAttribute::with_location(
+                1,
+                2,
+                VertexAttributeType::F32,
+                8,
+            ))
+            .with_attribute(VertexAttribute::with_location(
+                2,
+                4,
+                VertexAttributeType::F32,
+                16,
+            ))
+            .with_attribute(VertexAttribute::with_location(
+                3,
+                1,
+                VertexAttributeType::F32,
+                32,
+            ))
+    }
 }
 
 // SAFETY: While the contract for `Pod` states that all fields should also be `Pod`,
@@ -59,6 +116,193 @@ impl Vertex {
 unsafe impl Pod for Vertex {}
 unsafe impl Zeroable for Vertex {}
 
+/// The type of the components in a [`VertexAttribute`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum VertexAttributeType {
+    /// 32-bit floating point numbers.
+    F32,
+
+    /// 8-bit signed integers.
+    I8,
+
+    /// 8-bit unsigned integers.
+    U8,
+
+    /// 16-bit signed integers.
+    I16,
+
+    /// 16-bit unsigned integers.
+    U16,
+
+    /// 32-bit signed integers.
+    I32,
+
+    /// 32-bit unsigned integers.
+    U32,
+}
+
+impl VertexAttributeType {
+    /// Returns the size of a single component of this type, in bytes.
+    pub fn size(self) -> usize {
+        match self {
+            VertexAttributeType::F32 => 4,
+            VertexAttributeType::I8 => 1,
+            VertexAttributeType::U8 => 1,
+            VertexAttributeType::I16 => 2,
+            VertexAttributeType::U16 => 2,
+            VertexAttributeType::I32 => 4,
+            VertexAttributeType::U32 => 4,
+        }
+    }
+}
+
+/// How a [`VertexAttribute`] is bound to an input of a shader.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum AttributeBinding {
+    /// The attribute will be bound to the shader input with the given name. If the
+    /// active shader does not have an input with that name, the attribute will be ignored.
+    Name(String),
+
+    /// The attribute will be bound to the given location.
+    ///
+    /// Locations `0` to `3` are reserved for the standard attributes (`a_position`,
+    /// `a_uv`, `a_color` and `a_depth`). To use any other location in GLSL, your
+    /// shader will need to enable the `GL_ARB_explicit_attrib_location` extension
+    /// and declare the input with `layout(location = N)`.
+    ///
+    /// The location must be lower than the number of vertex attributes supported by
+    /// the graphics driver (at least 16, and at most 32) - otherwise, creating a
+    /// buffer with this attribute will fail.
+    Location(u32),
+}
+
+/// Describes a single attribute within a vertex.
+///
+/// Integer attributes are always converted to floating point when they are passed
+/// to the shader - if `normalized` is `true`, they will be mapped into the range
+/// `0.0` to `1.0` (for unsigned types) or `-1.0` to `1.0` (for signed types).
+#[derive(Debug, Clone, PartialEq)]
+pub struct VertexAttribute {
+    /// How the attribute is bound to the shader.
+    pub binding: AttributeBinding,
+
+    /// The number of components in the attribute (between 1 and 4).
+    pub components: u8,
+
+    /// The type of the attribute's components.
+    pub ty: VertexAttributeType,
+
+    /// Whether integer data should be normalized when it is converted to floating point.
+    pub normalized: bool,
+
+    /// The offset of the attribute from the start of the vertex, in bytes.
+    pub offset: usize,
+}
+
+impl VertexAttribute {
+    /// Creates a new vertex attribute, which will be bound to the shader input with
+    /// the given name.
+    pub fn new(
+        name: &str,
+        components: u8,
+        ty: VertexAttributeType,
+        offset: usize,
+    ) -> VertexAttribute {
+        VertexAttribute {
+            binding: AttributeBinding::Name(name.to_owned()),
+            components,
+            ty,
+            normalized: false,
+            offset,
+        }
+    }
+
+    /// Creates a new vertex attribute, which will be bound to the given location.
+    pub fn with_location(
+        location: u32,
+        components: u8,
+        ty: VertexAttributeType,
+        offset: usize,
+    ) -> VertexAttribute {
+        VertexAttribute {
+            binding: AttributeBinding::Location(location),
+            components,
+            ty,
+            normalized: false,
+            offset,
+        }
+    }
+
+    /// Sets whether integer data should be normalized when it is converted to floating point.
+    pub fn normalized(mut self, normalized: bool) -> VertexAttribute {
+        self.normalized = normalized;
+        self
+    }
+
+    /// Returns the size of the attribute, in bytes.
+    pub fn size(&self) -> usize {
+        self.components as usize * self.ty.size()
+    }
+}
+
+/// Describes the layout of the data in a [`VertexBuffer`].
+///
+/// This allows you to use your own vertex types, in order to pass extra per-vertex
+/// data (e.g. normals) to a custom [`Shader`](super::Shader). The vertex type should
+/// be `#[repr(C)]` and implement [`bytemuck::Pod`], and its size must match the
+/// layout's stride.
+///
+/// # Examples
+///
+/// The [`vertex_layout`](https://github.com/17cupsofcoffee/tetra/blob/main/examples/vertex_layout.rs)
+/// example demonstrates how to use a custom vertex type.
+#[derive(Debug, Clone, PartialEq)]
+pub struct VertexLayout {
+    stride: usize,
+    attributes: Vec<VertexAttribute>,
+}
+
+impl VertexLayout {
+    /// Creates a new, empty layout, with the given stride (the size of each vertex, in bytes).
+    pub fn new(stride: usize) -> VertexLayout {
+        VertexLayout {
+            stride,
+            attributes: Vec::new(),
+        }
+    }
+
+    /// Adds an attribute to the layout.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the attribute does not have between 1 and 4 components, or if it does not
+    /// fit within the stride.
+    pub fn with_attribute(mut self, attribute: VertexAttribute) -> VertexLayout {
+        assert!(
+            (1..=4).contains(&attribute.components),
+            "vertex attributes must have between 1 and 4 components"
+        );
+
+        assert!(
+            attribute.offset + attribute.size() <= self.stride,
+            "vertex attribute does not fit within the layout's stride"
+        );
+
+        self.attributes.push(attribute);
+        self
+    }
+
+    /// Returns the size of each vertex, in bytes.
+    pub fn stride(&self) -> usize {
+        self.stride
+    }
+
+    /// Returns the attributes in the layout.
+    pub fn attributes(&self) -> &[VertexAttribute] {
+        &self.attributes
+    }
+}
+
 /// The expected usage of a GPU buffer.
 ///
 /// The GPU may optionally use this to optimize data storage and access.
@@ -97,6 +341,9 @@ impl VertexWinding {
 ///
 /// This data can be drawn to the screen via a [`Mesh`].
 ///
+/// By default, vertex buffers store [`Vertex`] data. Other vertex types can be used by
+/// creating the buffer via [`with_layout`](Self::with_layout).
+///
 /// # Performance
 ///
 /// Creating a `VertexBuffer` is a relatively expensive operation. If you can, store them in your
@@ -139,7 +386,32 @@ impl VertexBuffer {
         vertices: &[Vertex],
         usage: BufferUsage,
     ) -> Result<VertexBuffer> {
-        let buffer = ctx.device.new_vertex_buffer(vertices.len(), usage)?;
+        VertexBuffer::with_layout(ctx, vertices, Vertex::layout(), usage)
+    }
+
+    /// Creates a new vertex buffer with a custom vertex layout, and the specified usage hint.
+    ///
+    /// # Errors
+    ///
+    /// * [`TetraError::PlatformError`](crate::TetraError::PlatformError) will be returned if the underlying
+    /// graphics API encounters an error, or if the layout uses an attribute location that is
+    /// not supported by the graphics driver.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the size of `T` does not match the layout's stride.
+    pub fn with_layout<T>(
+        ctx: &mut Context,
+        vertices: &[T],
+        layout: VertexLayout,
+        usage: BufferUsage,
+    ) -> Result<VertexBuffer>
+    where
+        T: Pod,
+    {
+        let buffer = ctx
+            .device
+            .new_vertex_buffer(vertices.len(), layout, usage)?;
 
         ctx.device.set_vertex_buffer_data(&buffer, vertices, 0);
 
@@ -152,12 +424,21 @@ impl VertexBuffer {
     ///
     /// # Panics
     ///
-    /// Panics if the offset is out of bounds.
-    pub fn set_data(&self, ctx: &mut Context, vertices: &[Vertex], offset: usize) {
+    /// Panics if the offset is out of bounds, or if the size of `T` does not match
+    /// the buffer's layout.
+    pub fn set_data<T>(&self, ctx: &mut Context, vertices: &[T], offset: usize)
+    where
+        T: Pod,
+    {
         ctx.device
             .set_vertex_buffer_data(&self.handle, vertices, offset);
     }
 
+    /// Returns the layout of the buffer's vertex data.
+    pub fn layout(&self) -> &VertexLayout {
+        self.handle.layout()
+    }
+
     /// Creates a mesh using this buffer.
     ///
     /// This is a shortcut for calling [`Mesh::new`].
@@ -166,6 +447,239 @@ impl VertexBuffer {
     }
 }
 
+/// The data for a single instance of a mesh, for use with an [`InstanceBuffer`].
+///
+/// When no custom shader is active, meshes with an instance buffer will be drawn using
+/// the [default instanced shader](super::DEFAULT_INSTANCED_VERTEX_SHADER), which applies
+/// these properties to each instance. They can also be accessed from your own shaders, via
+/// the `a_instance_position`, `a_instance_scale`, `a_instance_rotation`, `a_instance_color`
+/// and `a_instance_region` attributes.
+#[repr(C)]
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Instance {
+    /// The position of the instance, relative to the mesh's origin.
+    pub position: Vec2<f32>,
+
+    /// The scale of the instance.
+    pub scale: Vec2<f32>,
+
+    /// The rotation of the instance, in radians.
+    pub rotation: f32,
+
+    /// The color of the instance. This will be multiplied by the color of each vertex.
+    pub color: Color,
+
+    /// The region of the texture that the instance should sample from, in texture
+    /// co-ordinates (`x`, `y`, `width`, `height`).
+    ///
+    /// The UVs of each vertex will be mapped into this region, which makes it possible
+    /// to draw different sprites from a texture atlas with a single mesh.
+    pub region: Vec4<f32>,
+}
+
+impl Instance {
+    /// Creates a new instance at the given position, with the default scale, rotation,
+    /// color and region.
+    pub fn new(position: Vec2<f32>) -> Instance {
+        Instance {
+            position,
+            ..Instance::default()
+        }
+    }
+
+    /// Sets the scale of the instance.
+    pub fn scale(mut self, scale: Vec2<f32>) -> Instance {
+        self.scale = scale;
+        self
+    }
+
+    /// Sets the rotation of the instance, in radians.
+    pub fn rotation(mut self, rotation: f32) -> Instance {
+        self.rotation = rotation;
+        self
+    }
+
+    /// Sets the color of the instance.
+    pub fn color(mut self, color: Color) -> Instance {
+        self.color = color;
+        self
+    }
+
+    /// Sets the region of the texture that the instance should sample from, in texture
+    /// co-ordinates.
+    pub fn region(mut self, region: Rectangle) -> Instance {
+        self.region = Vec4::new(region.x, region.y, region.width, region.height);
+        self
+    }
+
+    /// Returns the layout of the instance data.
+    pub fn layout() -> VertexLayout {
+        VertexLayout::new(std::mem::size_of::<Instance>())
+            .with_attribute(VertexAttribute::new(
+                "a_instance_position",
+                2,
+                VertexAttributeType::F32,
+                0,
+            ))
+            .with_attribute(VertexAttribute::new(
+                "a_instance_scale",
+                2,
+                VertexAttributeType::F32,
+                8,
+            ))
+            .with_attribute(VertexAttribute::new(
+                "a_instance_rotation",
+                1,
+                VertexAttributeType::F32,
+                16,
+            ))
+            .with_attribute(VertexAttribute::new(
+                "a_instance_color",
+                4,
+                VertexAttributeType::F32,
+                20,
+            ))
+            .with_attribute(VertexAttribute::new(
+                "a_instance_region",
+                4,
+                VertexAttributeType::F32,
+                36,
+            ))
+    }
+}
+
+impl Default for Instance {
+    fn default() -> Instance {
+        Instance {
+            position: Vec2::zero(),
+            scale: Vec2::one(),
+            rotation: 0.0,
+            color: Color::WHITE,
+            region: Vec4::new(0.0, 0.0, 1.0, 1.0),
+        }
+    }
+}
+
+// SAFETY: See the equivalent impl for `Vertex`.
+unsafe impl Pod for Instance {}
+unsafe impl Zeroable for Instance {}
+
+/// Per-instance data, stored in GPU memory.
+///
+/// When an instance buffer is attached to a [`Mesh`], each of its elements will be
+/// passed to the shader once per instance (rather than once per vertex) when the mesh
+/// is drawn via [`Mesh::draw_instanced`]. This makes it possible to draw a large number
+/// of objects with a single draw call.
+///
+/// By default, instance buffers store [`Instance`] data. Other types can be used by
+/// creating the buffer via [`with_layout`](Self::with_layout), in which case you will
+/// need to use a custom shader to access the data.
+///
+/// # Performance
+///
+/// Creating an `InstanceBuffer` is a relatively expensive operation. If you can, store them in your
+/// [`State`](crate::State) struct rather than recreating them each frame.
+///
+/// Cloning an `InstanceBuffer` is a very cheap operation, as the underlying data is shared between the
+/// original instance and the clone via [reference-counting](https://doc.rust-lang.org/std/rc/struct.Rc.html).
+/// This does mean, however, that updating an `InstanceBuffer` will also update any other clones of
+/// that `InstanceBuffer`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct InstanceBuffer {
+    handle: Rc<RawVertexBuffer>,
+}
+
+impl InstanceBuffer {
+    /// Creates a new instance buffer.
+    ///
+    /// The buffer will be created with the [`BufferUsage::Dynamic`] usage hint - this can
+    /// be overridden via the [`with_usage`](Self::with_usage) constructor.
+    ///
+    /// # Errors
+    ///
+    /// * [`TetraError::PlatformError`](crate::TetraError::PlatformError) will be returned if the underlying
+    /// graphics API encounters an error.
+    pub fn new(ctx: &mut Context, instances: &[Instance]) -> Result<InstanceBuffer> {
+        InstanceBuffer::with_usage(ctx, instances, BufferUsage::Dynamic)
+    }
+
+    /// Creates a new instance buffer, with the specified usage hint.
+    ///
+    /// The GPU may optionally use the usage hint to optimize data storage and access.
+    ///
+    /// # Errors
+    ///
+    /// * [`TetraError::PlatformError`](crate::TetraError::PlatformError) will be returned if the underlying
+    /// graphics API encounters an error.
+    pub fn with_usage(
+        ctx: &mut Context,
+        instances: &[Instance],
+        usage: BufferUsage,
+    ) -> Result<InstanceBuffer> {
+        InstanceBuffer::with_layout(ctx, instances, Instance::layout(), usage)
+    }
+
+    /// Creates a new instance buffer with a custom layout, and the specified usage hint.
+    ///
+    /// # Errors
+    ///
+    /// * [`TetraError::PlatformError`](crate::TetraError::PlatformError) will be returned if the underlying
+    /// graphics API encounters an error, or if the layout uses an attribute location that is
+    /// not supported by the graphics driver.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the size of `T` does not match the layout's stride.
+    pub fn with_layout<T>(
+        ctx: &mut Context,
+        instances: &[T],
+        layout: VertexLayout,
+        usage: BufferUsage,
+    ) -> Result<InstanceBuffer>
+    where
+        T: Pod,
+    {
+        let buffer = ctx
+            .device
+            .new_vertex_buffer(instances.len(), layout, usage)?;
+
+        ctx.device.set_vertex_buffer_data(&buffer, instances, 0);
+
+        Ok(InstanceBuffer {
+            handle: Rc::new(buffer),
+        })
+    }
+
+    /// Uploads new instance data to the GPU.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the offset is out of bounds, or if the size of `T` does not match
+    /// the buffer's layout.
+    pub fn set_data<T>(&self, ctx: &mut Context, instances: &[T], offset: usize)
+    where
+        T: Pod,
+    {
+        ctx.device
+            .set_vertex_buffer_data(&self.handle, instances, offset);
+    }
+
+    /// Returns the number of instances that the buffer can hold.
+    pub fn len(&self) -> usize {
+        self.handle.count()
+    }
+
+    /// Returns `true` if the buffer cannot hold any instances.
+    pub fn is_empty(&self) -> bool {
+        self.handle.count() == 0
+    }
+
+    /// Returns the layout of the buffer's instance data.
+    pub fn layout(&self) -> &VertexLayout {
+        self.handle.layout()
+    }
+}
+
 /// Index data, stored in GPU memory.
 ///
 /// An index buffer can be used as part of a [`Mesh`], in order to describe which vertex data should be drawn,
@@ -256,6 +770,206 @@ pub enum ShapeStyle {
     Stroke(f32),
 }
 
+/// Options that control how strokes are generated by a [`GeometryBuilder`].
+///
+/// The width of the stroke is specified separately, via [`ShapeStyle::Stroke`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct StrokeOptions {
+    /// How the segments of the stroke should be joined together.
+    pub line_join: LineJoin,
+
+    /// The cap that should be drawn at the start of each open sub-path.
+    pub start_cap: LineCap,
+
+    /// The cap that should be drawn at the end of each open sub-path.
+    pub end_cap: LineCap,
+
+    /// The limit at which miter joins will be converted into bevel joins,
+    /// relative to the stroke width.
+    ///
+    /// Values lower than 1.0 will be treated as 1.0.
+    pub miter_limit: f32,
+
+    /// The maximum distance between a curve and the line segments used to
+    /// approximate it.
+    pub tolerance: f32,
+
+    /// The lengths of alternating dashes and gaps along the stroke.
+    ///
+    /// If this is empty, the stroke will be solid. If the pattern contains an odd
+    /// number of entries, it will be repeated to make it even.
+    pub dash_pattern: Vec<f32>,
+
+    /// How far into the dash pattern the stroke should start.
+    pub dash_offset: f32,
+}
+
+impl StrokeOptions {
+    /// Creates a new set of stroke options, with the default values.
+    pub fn new() -> StrokeOptions {
+        StrokeOptions::default()
+    }
+
+    /// Sets how the segments of the stroke should be joined together.
+    pub fn line_join(mut self, line_join: LineJoin) -> StrokeOptions {
+        self.line_join = line_join;
+        self
+    }
+
+    /// Sets the cap that should be drawn at both ends of each open sub-path.
+    pub fn line_cap(mut self, line_cap: LineCap) -> StrokeOptions {
+        self.start_cap = line_cap;
+        self.end_cap = line_cap;
+        self
+    }
+
+    /// Sets the cap that should be drawn at the start of each open sub-path.
+    pub fn start_cap(mut self, start_cap: LineCap) -> StrokeOptions {
+        self.start_cap = start_cap;
+        self
+    }
+
+    /// Sets the cap that should be drawn at the end of each open sub-path.
+    pub fn end_cap(mut self, end_cap: LineCap) -> StrokeOptions {
+        self.end_cap = end_cap;
+        self
+    }
+
+    /// Sets the limit at which miter joins will be converted into bevel joins.
+    ///
+    /// Values lower than 1.0 will be clamped.
+    pub fn miter_limit(mut self, miter_limit: f32) -> StrokeOptions {
+        self.miter_limit = miter_limit.max(1.0);
+        self
+    }
+
+    /// Sets the maximum distance between a curve and the line segments used to
+    /// approximate it.
+    pub fn tolerance(mut self, tolerance: f32) -> StrokeOptions {
+        self.tolerance = tolerance;
+        self
+    }
+
+    /// Sets the dash pattern and the offset into that pattern.
+    pub fn dashes(mut self, pattern: &[f32], offset: f32) -> StrokeOptions {
+        self.dash_pattern = pattern.to_vec();
+        self.dash_offset = offset;
+        self
+    }
+
+    fn to_lyon(&self, width: f32) -> LyonStrokeOptions {
+        LyonStrokeOptions::default()
+            .with_line_width(width)
+            .with_line_join(self.line_join)
+            .with_start_cap(self.start_cap)
+            .with_end_cap(self.end_cap)
+            .with_miter_limit(self.miter_limit.max(1.0))
+            .with_tolerance(self.tolerance)
+    }
+
+    fn dash_pattern(&self) -> Option<Vec<f32>> {
+        let pattern = &self.dash_pattern;
+
+        if pattern.iter().any(|len| *len < 0.0) || pattern.iter().sum::<f32>() <= 0.0 {
+            return None;
+        }
+
+        // Doubling the pattern ensures that odd-length patterns alternate between
+        // dashes and gaps correctly, and has no visible effect on even-length ones.
+        Some(pattern.repeat(2))
+    }
+}
+
+impl Default for StrokeOptions {
+    fn default() -> StrokeOptions {
+        StrokeOptions {
+            line_join: LineJoin::Miter,
+            start_cap: LineCap::Butt,
+            end_cap: LineCap::Butt,
+            miter_limit: LyonStrokeOptions::DEFAULT_MITER_LIMIT,
+            tolerance: LyonStrokeOptions::DEFAULT_TOLERANCE,
+            dash_pattern: Vec::new(),
+            dash_offset: 0.0,
+        }
+    }
+}
+
+/// Options that control how fills are generated by a [`GeometryBuilder`].
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct FillOptions {
+    /// The rule that determines which parts of a self-intersecting shape are
+    /// considered to be inside it.
+    pub fill_rule: FillRule,
+
+    /// The maximum distance between a curve and the line segments used to
+    /// approximate it.
+    pub tolerance: f32,
+}
+
+impl FillOptions {
+    /// Creates a new set of fill options, with the default values.
+    pub fn new() -> FillOptions {
+        FillOptions::default()
+    }
+
+    /// Sets the rule that determines which parts of a self-intersecting shape are
+    /// considered to be inside it.
+    pub fn fill_rule(mut self, fill_rule: FillRule) -> FillOptions {
+        self.fill_rule = fill_rule;
+        self
+    }
+
+    /// Sets the maximum distance between a curve and the line segments used to
+    /// approximate it.
+    pub fn tolerance(mut self, tolerance: f32) -> FillOptions {
+        self.tolerance = tolerance;
+        self
+    }
+
+    fn to_lyon(self) -> LyonFillOptions {
+        LyonFillOptions::default()
+            .with_fill_rule(self.fill_rule)
+            .with_tolerance(self.tolerance)
+    }
+}
+
+impl Default for FillOptions {
+    fn default() -> FillOptions {
+        FillOptions {
+            fill_rule: LyonFillOptions::DEFAULT_FILL_RULE,
+            tolerance: LyonFillOptions::DEFAULT_TOLERANCE,
+        }
+    }
+}
+
+/// Ways of generating texture co-ordinates for shapes.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum UvMode {
+    /// All vertices will have a texture co-ordinate of zero.
+    ///
+    /// This is the default.
+    Zero,
+
+    /// Texture co-ordinates will be stretched over the bounding box of each shape,
+    /// with `(0.0, 0.0)` at the top left and `(1.0, 1.0)` at the bottom right.
+    BoundingBox,
+
+    /// For strokes, the X co-ordinate will be the distance along the path (in pixels),
+    /// and the Y co-ordinate will be 0.0 on the left side of the stroke and 1.0 on
+    /// the right side.
+    ///
+    /// This can be used to texture a stroke, or to animate dashes in a shader.
+    /// Fills do not have a path to measure, so [`UvMode::BoundingBox`] will be used
+    /// for them instead.
+    PathLength,
+}
+
+impl Default for UvMode {
+    fn default() -> UvMode {
+        UvMode::Zero
+    }
+}
+
 /// A 2D mesh that can be drawn to the screen.
 ///
 /// A `Mesh` is a wrapper for a [`VertexBuffer`], which allows it to be drawn in combination with several
@@ -263,6 +977,7 @@ pub enum ShapeStyle {
 ///
 /// * A [`Texture`] that individual vertices can sample from.
 /// * An [`IndexBuffer`] that can be used to modify the order/subset of vertices that are drawn.
+/// * An [`InstanceBuffer`] that provides per-instance data when drawing multiple instances.
 /// * A winding order, which determines which side of the geometry is front-facing.
 /// * A backface culling flag, which determines whether back-facing geometry should be drawn.
 /// * A draw range, which can be used to draw subsections of the mesh.
@@ -294,6 +1009,7 @@ pub enum ShapeStyle {
 pub struct Mesh {
     vertex_buffer: VertexBuffer,
     index_buffer: Option<IndexBuffer>,
+    instance_buffer: Option<InstanceBuffer>,
     texture: Option<Texture>,
     draw_range: Option<DrawRange>,
     winding: VertexWinding,
@@ -306,6 +1022,7 @@ impl Mesh {
         Mesh {
             vertex_buffer,
             index_buffer: None,
+            instance_buffer: None,
             texture: None,
             draw_range: None,
             winding: VertexWinding::CounterClockwise,
@@ -318,6 +1035,7 @@ impl Mesh {
         Mesh {
             vertex_buffer,
             index_buffer: Some(index_buffer),
+            instance_buffer: None,
             texture: None,
             winding: VertexWinding::CounterClockwise,
             draw_range: None,
@@ -336,11 +1054,18 @@ impl Mesh {
     /// Draws multiple instances of the mesh to the screen (or to a canvas,
     /// if one is enabled).
     ///
-    /// You will need to use a custom [`Shader`](crate::graphics::Shader) in order to pass unique
-    /// properties to each instance. Currently, the easiest way of doing this is via uniform
-    /// arrays - however, there is a hardware-determined limit on how many uniform locations
-    /// an individual shader can use, so this may not work if you're rendering a large
-    /// number of objects.
+    /// Unique properties can be passed to each instance by attaching an [`InstanceBuffer`] to
+    /// the mesh. If the mesh has an instance buffer and no custom shader is active, the
+    /// [default instanced shader](crate::graphics::DEFAULT_INSTANCED_VERTEX_SHADER) will be used
+    /// to apply the [`Instance`] data. If `instances` is greater than the length of the
+    /// instance buffer, it will be clamped, so that data past the end of the buffer is
+    /// never read.
+    ///
+    /// Alternatively, you can use a custom [`Shader`](crate::graphics::Shader), and
+    /// either read from a custom instance buffer or pass data via uniform arrays. Note
+    /// that there is a hardware-determined limit on how many uniform locations an
+    /// individual shader can use, so uniform arrays may not work if you're rendering a
+    /// large number of objects.
     ///
     /// This should usually only be used for complex meshes - instancing can be inefficient
     /// for simple geometry (e.g. quads). That said, as with all things performance-related,
@@ -356,13 +1081,15 @@ impl Mesh {
             None => &ctx.graphics.default_texture,
         };
 
-        let shader = match &ctx.graphics.shader {
-            ActiveShader::Default => &ctx.graphics.default_shader,
-            ActiveShader::User(s) => s,
+        let shader = match (&ctx.graphics.shader, &self.instance_buffer) {
+            (ActiveShader::Default, None) => &ctx.graphics.default_shader,
+            (ActiveShader::Default, Some(_)) => &ctx.graphics.default_instanced_shader,
+            (ActiveShader::User(s), _) => s,
         };
 
         let params = params.into();
         let model_matrix = params.to_matrix();
+        let frame = graphics::frame_uniforms(ctx);
 
         // TODO: Failing to apply the defaults should be handled more gracefully than this,
         // but we can't do that without breaking changes.
@@ -370,6 +1097,7 @@ impl Mesh {
             &mut ctx.device,
             ctx.graphics.projection_matrix * ctx.graphics.transform_matrix * model_matrix,
             params.color,
+            frame,
         );
 
         ctx.device.cull_face(self.backface_culling);
@@ -387,9 +1115,15 @@ impl Mesh {
             (_, None) => (0, self.vertex_buffer.handle.count()),
         };
 
+        let instances = match &self.instance_buffer {
+            Some(i) => instances.min(i.len()),
+            None => instances,
+        };
+
         ctx.device.draw_instanced(
             &self.vertex_buffer.handle,
             self.index_buffer.as_ref().map(|i| &*i.handle),
+            self.instance_buffer.as_ref().map(|i| &*i.handle),
             &texture.data.handle,
             &shader.data.handle,
             start,
@@ -425,6 +1159,23 @@ impl Mesh {
         self.index_buffer = None;
     }
 
+    /// Gets a reference to the instance buffer contained within this mesh.
+    ///
+    /// Returns [`None`] if this mesh does not currently have an instance buffer attatched.
+    pub fn instance_buffer(&self) -> Option<&InstanceBuffer> {
+        self.instance_buffer.as_ref()
+    }
+
+    /// Sets the instance buffer that will be used when drawing the mesh.
+    pub fn set_instance_buffer(&mut self, instance_buffer: InstanceBuffer) {
+        self.instance_buffer = Some(instance_buffer);
+    }
+
+    /// Resets the mesh to no longer use an instance buffer.
+    pub fn reset_instance_buffer(&mut self) {
+        self.instance_buffer = None;
+    }
+
     /// Gets a reference to the texture contained within this mesh.
     ///
     /// Returns [`None`] if this mesh does not currently have an texture attatched.
@@ -614,6 +1365,21 @@ impl Mesh {
             .polyline(stroke_width, points)?
             .build_mesh(ctx)
     }
+
+    /// Creates a new mesh from a path.
+    ///
+    /// If you need to draw multiple shapes, consider using [`GeometryBuilder`] to generate a combined mesh
+    /// instead.
+    ///
+    /// # Errors
+    ///
+    /// * [`TetraError::TessellationError`](crate::TetraError::TessellationError) will be returned if the shape
+    /// could not be turned into vertex data.
+    /// * [`TetraError::PlatformError`](crate::TetraError::PlatformError) will be returned if the underlying
+    /// graphics API encounters an error.
+    pub fn path(ctx: &mut Context, style: ShapeStyle, path: &Path) -> Result<Mesh> {
+        GeometryBuilder::new().path(style, path)?.build_mesh(ctx)
+    }
 }
 
 impl From<VertexBuffer> for Mesh {
@@ -629,13 +1395,17 @@ fn to_lyon_rect(rectangle: Rectangle) -> Rect {
     )
 }
 
-struct TetraVertexConstructor(Color);
+struct TetraVertexConstructor {
+    color: Color,
+    uv_mode: UvMode,
+    advancement: f32,
+}
 
 impl FillVertexConstructor<Vertex> for TetraVertexConstructor {
     fn new_vertex(&mut self, vertex: FillVertex) -> Vertex {
         let position = vertex.position();
 
-        Vertex::new(Vec2::new(position.x, position.y), Vec2::zero(), self.0)
+        Vertex::new(Vec2::new(position.x, position.y), Vec2::zero(), self.color)
     }
 }
 
@@ -643,12 +1413,103 @@ impl StrokeVertexConstructor<Vertex> for TetraVertexConstructor {
     fn new_vertex(&mut self, vertex: StrokeVertex) -> Vertex {
         let position = vertex.position();
 
-        Vertex::new(Vec2::new(position.x, position.y), Vec2::zero(), self.0)
+        let uv = match self.uv_mode {
+            UvMode::PathLength => {
+                let side = match vertex.side() {
+                    Side::Left => 0.0,
+                    Side::Right => 1.0,
+                };
+
+                Vec2::new(self.advancement + vertex.advancement(), side)
+            }
+            _ => Vec2::zero(),
+        };
+
+        Vertex::new(Vec2::new(position.x, position.y), uv, self.color)
     }
 }
 
+/// Splits a polyline into dashes, returning the points of each dash along with
+/// the distance along the line at which it starts.
+///
+/// The pattern must contain an even number of non-negative entries, and
+/// must not sum to zero.
+fn dash_polyline(points: &[Point], pattern: &[f32], offset: f32) -> Vec<(Vec<Point>, f32)> {
+    let mut dashes = Vec::new();
+
+    if points.is_empty() {
+        return dashes;
+    }
+
+    let total: f32 = pattern.iter().sum();
+    let mut phase = offset.rem_euclid(total);
+    let mut index = 0;
+    let mut remaining = pattern[0];
+
+    while phase > 0.0 {
+        if phase >= remaining {
+            phase -= remaining;
+            index = (index + 1) % pattern.len();
+            remaining = pattern[index];
+        } else {
+            remaining -= phase;
+            phase = 0.0;
+        }
+    }
+
+    let mut distance = 0.0;
+    let mut current = if index % 2 == 0 {
+        Some((vec![points[0]], 0.0))
+    } else {
+        None
+    };
+
+    for segment in points.windows(2) {
+        let (mut from, to) = (segment[0], segment[1]);
+        let mut length = (to - from).length();
+
+        while remaining < length {
+            let point = from.lerp(to, remaining / length);
+
+            length -= remaining;
+            distance += remaining;
+            from = point;
+
+            if let Some((mut dash, start)) = current.take() {
+                dash.push(point);
+                dashes.push((dash, start));
+            }
+
+            index = (index + 1) % pattern.len();
+            remaining = pattern[index];
+
+            if index % 2 == 0 {
+                current = Some((vec![point], distance));
+            }
+        }
+
+        remaining -= length;
+        distance += length;
+
+        if let Some((dash, _)) = &mut current {
+            dash.push(to);
+        }
+    }
+
+    if let Some((dash, start)) = current {
+        if dash.len() > 1 {
+            dashes.push((dash, start));
+        }
+    }
+
+    dashes
+}
+
 /// A builder for creating primitive shape geometry, and associated buffers/meshes.
 ///
+/// The way that shapes are generated can be customized via [`StrokeOptions`], [`FillOptions`]
+/// and [`UvMode`] - like the color, these apply to all subsequently added shapes.
+///
 /// # Performance
 ///
 /// `GeometryBuilder` stores the generated vertex and index data in a pair of `Vec`s. This means that creating
@@ -668,6 +1529,10 @@ impl StrokeVertexConstructor<Vertex> for TetraVertexConstructor {
 pub struct GeometryBuilder {
     data: VertexBuffers<Vertex, u32>,
     color: Color,
+    stroke_options: StrokeOptions,
+    fill_options: FillOptions,
+    uv_mode: UvMode,
+    gradient: Option<Gradient>,
 }
 
 impl GeometryBuilder {
@@ -676,6 +1541,10 @@ impl GeometryBuilder {
         GeometryBuilder {
             data: VertexBuffers::new(),
             color: Color::WHITE,
+            stroke_options: StrokeOptions::default(),
+            fill_options: FillOptions::default(),
+            uv_mode: UvMode::Zero,
+            gradient: None,
         }
     }
 
@@ -690,27 +1559,10 @@ impl GeometryBuilder {
         style: ShapeStyle,
         rectangle: Rectangle,
     ) -> Result<&mut GeometryBuilder> {
-        let mut builder = BuffersBuilder::new(&mut self.data, TetraVertexConstructor(self.color));
+        let mut builder = LyonPath::builder();
+        builder.add_rectangle(&to_lyon_rect(rectangle), Winding::Positive);
 
-        match style {
-            ShapeStyle::Fill => {
-                let options = FillOptions::default();
-                let mut tessellator = FillTessellator::new();
-                tessellator
-                    .tessellate_rectangle(&to_lyon_rect(rectangle), &options, &mut builder)
-                    .map_err(TetraError::TessellationError)?;
-            }
-
-            ShapeStyle::Stroke(width) => {
-                let options = StrokeOptions::default().with_line_width(width);
-                let mut tessellator = StrokeTessellator::new();
-                tessellator
-                    .tessellate_rectangle(&to_lyon_rect(rectangle), &options, &mut builder)
-                    .map_err(TetraError::TessellationError)?;
-            }
-        }
-
-        Ok(self)
+        self.add_path(style, &builder.build())
     }
 
     /// Adds a rounded rectangle.
@@ -725,27 +1577,10 @@ impl GeometryBuilder {
         rectangle: Rectangle,
         radii: BorderRadii,
     ) -> Result<&mut GeometryBuilder> {
-        let mut builder = BuffersBuilder::new(&mut self.data, TetraVertexConstructor(self.color));
+        let mut builder = LyonPath::builder();
+        builder.add_rounded_rectangle(&to_lyon_rect(rectangle), &radii, Winding::Positive);
 
-        match style {
-            ShapeStyle::Fill => {
-                let options = FillOptions::default();
-                let mut tessellator = FillTessellator::new();
-                let mut builder = tessellator.builder(&options, &mut builder);
-                builder.add_rounded_rectangle(&to_lyon_rect(rectangle), &radii, Winding::Positive);
-                builder.build().map_err(TetraError::TessellationError)?;
-            }
-
-            ShapeStyle::Stroke(width) => {
-                let options = StrokeOptions::default().with_line_width(width);
-                let mut tessellator = StrokeTessellator::new();
-                let mut builder = tessellator.builder(&options, &mut builder);
-                builder.add_rounded_rectangle(&to_lyon_rect(rectangle), &radii, Winding::Positive);
-                builder.build().map_err(TetraError::TessellationError)?;
-            }
-        }
-
-        Ok(self)
+        self.add_path(style, &builder.build())
     }
 
     /// Adds a circle.
@@ -760,39 +1595,10 @@ impl GeometryBuilder {
         center: Vec2<f32>,
         radius: f32,
     ) -> Result<&mut GeometryBuilder> {
-        let mut builder = BuffersBuilder::new(&mut self.data, TetraVertexConstructor(self.color));
-
-        match style {
-            ShapeStyle::Fill => {
-                let options = FillOptions::default();
-                let mut tessellator = FillTessellator::new();
-
-                tessellator
-                    .tessellate_circle(
-                        Point::new(center.x, center.y),
-                        radius,
-                        &options,
-                        &mut builder,
-                    )
-                    .map_err(TetraError::TessellationError)?;
-            }
-
-            ShapeStyle::Stroke(width) => {
-                let options = StrokeOptions::default().with_line_width(width);
-                let mut tessellator = StrokeTessellator::new();
-
-                tessellator
-                    .tessellate_circle(
-                        Point::new(center.x, center.y),
-                        radius,
-                        &options,
-                        &mut builder,
-                    )
-                    .map_err(TetraError::TessellationError)?;
-            }
-        }
+        let mut builder = LyonPath::builder();
+        builder.add_circle(Point::new(center.x, center.y), radius, Winding::Positive);
 
-        Ok(self)
+        self.add_path(style, &builder.build())
     }
 
     /// Adds an ellipse.
@@ -807,43 +1613,15 @@ impl GeometryBuilder {
         center: Vec2<f32>,
         radii: Vec2<f32>,
     ) -> Result<&mut GeometryBuilder> {
-        let mut builder = BuffersBuilder::new(&mut self.data, TetraVertexConstructor(self.color));
-
-        match style {
-            ShapeStyle::Fill => {
-                let options = FillOptions::default();
-                let mut tessellator = FillTessellator::new();
-
-                tessellator
-                    .tessellate_ellipse(
-                        Point::new(center.x, center.y),
-                        Vector::new(radii.x, radii.y),
-                        Angle::radians(0.0),
-                        Winding::Positive,
-                        &options,
-                        &mut builder,
-                    )
-                    .map_err(TetraError::TessellationError)?;
-            }
-
-            ShapeStyle::Stroke(width) => {
-                let options = StrokeOptions::default().with_line_width(width);
-                let mut tessellator = StrokeTessellator::new();
-
-                tessellator
-                    .tessellate_ellipse(
-                        Point::new(center.x, center.y),
-                        Vector::new(radii.x, radii.y),
-                        Angle::radians(0.0),
-                        Winding::Positive,
-                        &options,
-                        &mut builder,
-                    )
-                    .map_err(TetraError::TessellationError)?;
-            }
-        }
+        let mut builder = LyonPath::builder();
+        builder.add_ellipse(
+            Point::new(center.x, center.y),
+            Vector::new(radii.x, radii.y),
+            Angle::radians(0.0),
+            Winding::Positive,
+        );
 
-        Ok(self)
+        self.add_path(style, &builder.build())
     }
 
     /// Adds a polygon.
@@ -857,39 +1635,18 @@ impl GeometryBuilder {
         style: ShapeStyle,
         points: &[Vec2<f32>],
     ) -> Result<&mut GeometryBuilder> {
-        let mut builder = BuffersBuilder::new(&mut self.data, TetraVertexConstructor(self.color));
-
         let points: Vec<Point> = points
             .iter()
             .map(|point| Point::new(point.x, point.y))
             .collect();
 
-        let polygon = Polygon {
+        let mut builder = LyonPath::builder();
+        builder.add_polygon(Polygon {
             points: &points,
             closed: true,
-        };
-
-        match style {
-            ShapeStyle::Fill => {
-                let options = FillOptions::default();
-                let mut tessellator = FillTessellator::new();
-
-                tessellator
-                    .tessellate_polygon(polygon, &options, &mut builder)
-                    .map_err(TetraError::TessellationError)?;
-            }
-
-            ShapeStyle::Stroke(width) => {
-                let options = StrokeOptions::default().with_line_width(width);
-                let mut tessellator = StrokeTessellator::new();
-
-                tessellator
-                    .tessellate_polygon(polygon, &options, &mut builder)
-                    .map_err(TetraError::TessellationError)?;
-            }
-        }
+        });
 
-        Ok(self)
+        self.add_path(style, &builder.build())
     }
 
     /// Adds a polyline.
@@ -903,26 +1660,212 @@ impl GeometryBuilder {
         stroke_width: f32,
         points: &[Vec2<f32>],
     ) -> Result<&mut GeometryBuilder> {
-        let mut builder = BuffersBuilder::new(&mut self.data, TetraVertexConstructor(self.color));
-
         let points: Vec<Point> = points
             .iter()
             .map(|point| Point::new(point.x, point.y))
             .collect();
 
-        let polygon = Polygon {
+        let mut builder = LyonPath::builder();
+        builder.add_polygon(Polygon {
             points: &points,
             closed: false,
-        };
+        });
 
-        let options = StrokeOptions::default().with_line_width(stroke_width);
-        let mut tessellator = StrokeTessellator::new();
+        self.add_path(ShapeStyle::Stroke(stroke_width), &builder.build())
+    }
+
+    /// Adds a path.
+    ///
+    /// # Errors
+    ///
+    /// * [`TetraError::TessellationError`](crate::TetraError::TessellationError) will be returned if the shape
+    /// could not be turned into vertex data.
+    pub fn path(&mut self, style: ShapeStyle, path: &Path) -> Result<&mut GeometryBuilder> {
+        self.add_path(style, &path.inner)
+    }
+
+    fn add_path(&mut self, style: ShapeStyle, path: &LyonPath) -> Result<&mut GeometryBuilder> {
+        let start = self.data.vertices.len();
+        let index_start = self.data.indices.len();
+
+        match style {
+            ShapeStyle::Fill => {
+                self.fill_path(path)?;
+
+                if self.uv_mode != UvMode::Zero {
+                    self.apply_bounding_box_uvs(start);
+                }
+            }
+
+            ShapeStyle::Stroke(width) => {
+                self.stroke_path(path, width)?;
+
+                if self.uv_mode == UvMode::BoundingBox {
+                    self.apply_bounding_box_uvs(start);
+                }
+            }
+        }
+
+        if let Some(gradient) = &self.gradient {
+            gradient.apply(
+                &mut self.data.vertices,
+                &mut self.data.indices,
+                start,
+                index_start,
+            );
+        }
+
+        Ok(self)
+    }
+
+    fn fill_path(&mut self, path: &LyonPath) -> Result {
+        let options = self.fill_options.to_lyon();
+        let mut tessellator = FillTessellator::new();
+
+        let mut builder = BuffersBuilder::new(
+            &mut self.data,
+            TetraVertexConstructor {
+                color: self.color,
+                uv_mode: self.uv_mode,
+                advancement: 0.0,
+            },
+        );
 
         tessellator
-            .tessellate_polygon(polygon, &options, &mut builder)
+            .tessellate_path(path, &options, &mut builder)
             .map_err(TetraError::TessellationError)?;
 
-        Ok(self)
+        Ok(())
+    }
+
+    fn stroke_path(&mut self, path: &LyonPath, width: f32) -> Result {
+        let options = self.stroke_options.to_lyon(width);
+        let mut tessellator = StrokeTessellator::new();
+
+        let pattern = match self.stroke_options.dash_pattern() {
+            Some(pattern) => pattern,
+            None => {
+                let mut builder = BuffersBuilder::new(
+                    &mut self.data,
+                    TetraVertexConstructor {
+                        color: self.color,
+                        uv_mode: self.uv_mode,
+                        advancement: 0.0,
+                    },
+                );
+
+                tessellator
+                    .tessellate_path(path, &options, &mut builder)
+                    .map_err(TetraError::TessellationError)?;
+
+                return Ok(());
+            }
+        };
+
+        // lyon doesn't support dashing, so the path gets flattened and split up
+        // manually, with each dash being stroked as a seperate open polyline.
+        let mut points = Vec::new();
+
+        for event in path.iter().flattened(options.tolerance) {
+            match event {
+                PathEvent::Begin { at } => {
+                    points.clear();
+                    points.push(at);
+                }
+
+                PathEvent::Line { to, .. } => points.push(to),
+
+                PathEvent::End { first, close, .. } => {
+                    if close {
+                        points.push(first);
+                    }
+
+                    let dashes = dash_polyline(&points, &pattern, self.stroke_options.dash_offset);
+
+                    for (dash, start) in dashes {
+                        let mut builder = BuffersBuilder::new(
+                            &mut self.data,
+                            TetraVertexConstructor {
+                                color: self.color,
+                                uv_mode: self.uv_mode,
+                                advancement: start,
+                            },
+                        );
+
+                        let polygon = Polygon {
+                            points: &dash,
+                            closed: false,
+                        };
+
+                        tessellator
+                            .tessellate_polygon(polygon, &options, &mut builder)
+                            .map_err(TetraError::TessellationError)?;
+                    }
+                }
+
+                _ => {}
+            }
+        }
+
+        Ok(())
+    }
+
+    fn apply_bounding_box_uvs(&mut self, start: usize) {
+        let vertices = &mut self.data.vertices[start..];
+
+        let mut min = Vec2::broadcast(f32::INFINITY);
+        let mut max = Vec2::broadcast(f32::NEG_INFINITY);
+
+        for vertex in vertices.iter() {
+            min = Vec2::partial_min(min, vertex.position);
+            max = Vec2::partial_max(max, vertex.position);
+        }
+
+        let size = max - min;
+
+        for vertex in vertices.iter_mut() {
+            let offset = vertex.position - min;
+
+            vertex.uv = Vec2::new(
+                if size.x > 0.0 { offset.x / size.x } else { 0.0 },
+                if size.y > 0.0 { offset.y / size.y } else { 0.0 },
+            );
+        }
+    }
+
+    /// Sets the options that will be used for subsequent strokes.
+    pub fn set_stroke_options(&mut self, options: StrokeOptions) -> &mut GeometryBuilder {
+        self.stroke_options = options;
+        self
+    }
+
+    /// Sets the options that will be used for subsequent fills.
+    pub fn set_fill_options(&mut self, options: FillOptions) -> &mut GeometryBuilder {
+        self.fill_options = options;
+        self
+    }
+
+    /// Sets how texture co-ordinates will be generated for subsequent shapes.
+    ///
+    /// By default, all vertices will have a texture co-ordinate of zero.
+    pub fn set_uv_mode(&mut self, uv_mode: UvMode) -> &mut GeometryBuilder {
+        self.uv_mode = uv_mode;
+        self
+    }
+
+    /// Sets the gradient that will be used to color subsequent shapes.
+    ///
+    /// The gradient's colors will be multiplied by the color set via
+    /// [`set_color`](Self::set_color), so you will usually want to set that to white.
+    pub fn set_gradient(&mut self, gradient: Gradient) -> &mut GeometryBuilder {
+        self.gradient = Some(gradient);
+        self
+    }
+
+    /// Stops applying a gradient to subsequent shapes.
+    pub fn reset_gradient(&mut self) -> &mut GeometryBuilder {
+        self.gradient = None;
+        self
     }
 
     /// Sets the color that will be used for subsequent shapes.
@@ -993,3 +1936,85 @@ impl Default for GeometryBuilder {
         GeometryBuilder::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn line(points: &[(f32, f32)]) -> Vec<Point> {
+        points.iter().map(|&(x, y)| Point::new(x, y)).collect()
+    }
+
+    #[test]
+    fn dash_straight_line() {
+        let dashes = dash_polyline(&line(&[(0.0, 0.0), (10.0, 0.0)]), &[3.0, 2.0], 0.0);
+
+        assert_eq!(
+            dashes,
+            vec![
+                (line(&[(0.0, 0.0), (3.0, 0.0)]), 0.0),
+                (line(&[(5.0, 0.0), (8.0, 0.0)]), 5.0),
+            ]
+        );
+    }
+
+    #[test]
+    fn dash_across_corner() {
+        let dashes = dash_polyline(
+            &line(&[(0.0, 0.0), (4.0, 0.0), (4.0, 4.0)]),
+            &[6.0, 1.0],
+            0.0,
+        );
+
+        assert_eq!(
+            dashes,
+            vec![
+                (line(&[(0.0, 0.0), (4.0, 0.0), (4.0, 2.0)]), 0.0),
+                (line(&[(4.0, 3.0), (4.0, 4.0)]), 7.0),
+            ]
+        );
+    }
+
+    #[test]
+    fn dash_with_offset() {
+        let dashes = dash_polyline(&line(&[(0.0, 0.0), (10.0, 0.0)]), &[3.0, 2.0], 4.0);
+
+        assert_eq!(
+            dashes,
+            vec![
+                (line(&[(1.0, 0.0), (4.0, 0.0)]), 1.0),
+                (line(&[(6.0, 0.0), (9.0, 0.0)]), 6.0),
+            ]
+        );
+    }
+
+    #[test]
+    fn invalid_dash_patterns_are_solid() {
+        let pattern = |dashes: &[f32]| StrokeOptions::new().dashes(dashes, 0.0).dash_pattern();
+
+        assert_eq!(pattern(&[]), None);
+        assert_eq!(pattern(&[0.0, 0.0]), None);
+        assert_eq!(pattern(&[1.0, -1.0]), None);
+        assert_eq!(
+            pattern(&[1.0, 2.0, 3.0]),
+            Some(vec![1.0, 2.0, 3.0, 1.0, 2.0, 3.0])
+        );
+    }
+
+    #[test]
+    fn miter_limit_is_clamped() {
+        let options = StrokeOptions {
+            miter_limit: 0.5,
+            ..StrokeOptions::default()
+        };
+
+        assert_eq!(options.to_lyon(1.0).miter_limit, 1.0);
+
+        let options = StrokeOptions {
+            miter_limit: f32::NAN,
+            ..StrokeOptions::default()
+        };
+
+        assert_eq!(options.to_lyon(1.0).miter_limit, 1.0);
+    }
+}
@@ -0,0 +1,369 @@
+//! Gradient fills for shapes.
+
+use hashbrown::HashMap;
+
+use crate::graphics::mesh::Vertex;
+use crate::graphics::{Color, Curve, CurveValue};
+use crate::math::Vec2;
+
+/// The maximum number of times that a shape's triangles will be split when
+/// subdividing for a gradient.
+const MAX_SUBDIVISIONS: usize = 8;
+
+/// The shape of a [`Gradient`].
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum GradientKind {
+    /// Colors change along the line between two points.
+    ///
+    /// Points before the start or after the end of the line will use the first and last
+    /// color of the gradient, respectively.
+    Linear {
+        /// The point at which the gradient starts.
+        start: Vec2<f32>,
+
+        /// The point at which the gradient ends.
+        end: Vec2<f32>,
+    },
+
+    /// Colors change based on the distance from a center point.
+    ///
+    /// Points outside of the radius will use the last color of the gradient.
+    Radial {
+        /// The center of the gradient.
+        center: Vec2<f32>,
+
+        /// The distance from the center at which the gradient ends.
+        radius: f32,
+    },
+}
+
+/// A gradient that can be used to color shapes.
+///
+/// Gradients are applied to each vertex of a shape, and then interpolated across
+/// the triangles between them. This means that simple shapes (e.g. rectangles) may not
+/// have enough vertices to display every color of a multi-stop gradient - in this case,
+/// [`with_subdivision`](Gradient::with_subdivision) can be used to split the shape up
+/// into smaller triangles.
+///
+/// The gradient's points are specified in the same co-ordinate space as the shapes
+/// that it is applied to.
+///
+/// # Examples
+///
+/// ```
+/// # use firecore_tetra::graphics::Color;
+/// # use firecore_tetra::graphics::mesh::Gradient;
+/// # use firecore_tetra::graphics::Curve;
+/// # use firecore_tetra::math::Vec2;
+/// let gradient = Gradient::linear(
+///     Vec2::new(0.0, 0.0),
+///     Vec2::new(100.0, 0.0),
+///     Curve::linear(Color::RED, Color::BLUE),
+/// );
+///
+/// assert_eq!(gradient.color_at(Vec2::new(50.0, 25.0)), Color::rgb(0.5, 0.0, 0.5));
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Gradient {
+    kind: GradientKind,
+    colors: Curve<Color>,
+    subdivision: Option<f32>,
+}
+
+impl Gradient {
+    /// Creates a new gradient with the given shape and colors.
+    ///
+    /// The positions of the curve's keys are relative to the shape of the gradient,
+    /// with 0.0 being the start and 1.0 being the end.
+    pub fn new(kind: GradientKind, colors: Curve<Color>) -> Gradient {
+        Gradient {
+            kind,
+            colors,
+            subdivision: None,
+        }
+    }
+
+    /// Creates a new linear gradient.
+    pub fn linear(start: Vec2<f32>, end: Vec2<f32>, colors: Curve<Color>) -> Gradient {
+        Gradient::new(GradientKind::Linear { start, end }, colors)
+    }
+
+    /// Creates a new radial gradient.
+    pub fn radial(center: Vec2<f32>, radius: f32, colors: Curve<Color>) -> Gradient {
+        Gradient::new(GradientKind::Radial { center, radius }, colors)
+    }
+
+    /// Sets the maximum length of a triangle's edge in shapes that the gradient is
+    /// applied to.
+    ///
+    /// Any triangles with longer edges will be split up, making the gradient smoother
+    /// at the cost of generating more vertices.
+    pub fn with_subdivision(mut self, max_edge_length: f32) -> Gradient {
+        self.subdivision = Some(max_edge_length);
+        self
+    }
+
+    /// Returns the shape of the gradient.
+    pub fn kind(&self) -> GradientKind {
+        self.kind
+    }
+
+    /// Returns the colors of the gradient.
+    pub fn colors(&self) -> &Curve<Color> {
+        &self.colors
+    }
+
+    /// Returns the maximum edge length that shapes will be subdivided to, if set.
+    pub fn subdivision(&self) -> Option<f32> {
+        self.subdivision
+    }
+
+    /// Returns the color of the gradient at the given point.
+    pub fn color_at(&self, point: Vec2<f32>) -> Color {
+        let position = match self.kind {
+            GradientKind::Linear { start, end } => {
+                let direction = end - start;
+                let length = direction.magnitude_squared();
+
+                if length > 0.0 {
+                    (point - start).dot(direction) / length
+                } else {
+                    0.0
+                }
+            }
+
+            GradientKind::Radial { center, radius } => {
+                if radius > 0.0 {
+                    point.distance(center) / radius
+                } else {
+                    1.0
+                }
+            }
+        };
+
+        self.colors.sample(position.clamp(0.0, 1.0))
+    }
+
+    /// Applies the gradient to the vertices generated after `vertex_start` and
+    /// `index_start`, multiplying it with the existing vertex colors.
+    pub(crate) fn apply(
+        &self,
+        vertices: &mut Vec<Vertex>,
+        indices: &mut Vec<u32>,
+        vertex_start: usize,
+        index_start: usize,
+    ) {
+        if let Some(max_edge_length) = self.subdivision {
+            if max_edge_length > 0.0 {
+                subdivide(vertices, indices, index_start, max_edge_length);
+            }
+        }
+
+        for vertex in &mut vertices[vertex_start..] {
+            vertex.color = self.color_at(vertex.position) * vertex.color;
+        }
+    }
+}
+
+/// Splits up the triangles after `index_start` until none of their edges are longer
+/// than `max_edge_length`.
+///
+/// In each pass, every long edge is split at its midpoint, and the midpoint is shared
+/// between the triangles on either side. This avoids creating T-junctions, which can
+/// cause visible seams.
+fn subdivide(
+    vertices: &mut Vec<Vertex>,
+    indices: &mut Vec<u32>,
+    index_start: usize,
+    max_edge_length: f32,
+) {
+    let max_length_squared = max_edge_length * max_edge_length;
+
+    for _ in 0..MAX_SUBDIVISIONS {
+        let triangles: Vec<([u32; 3], [bool; 3])> = indices[index_start..]
+            .chunks_exact(3)
+            .map(|t| {
+                let is_long = |a: u32, b: u32| {
+                    let a = vertices[a as usize].position;
+                    let b = vertices[b as usize].position;
+
+                    a.distance_squared(b) > max_length_squared
+                };
+
+                (
+                    [t[0], t[1], t[2]],
+                    [
+                        is_long(t[0], t[1]),
+                        is_long(t[1], t[2]),
+                        is_long(t[2], t[0]),
+                    ],
+                )
+            })
+            .collect();
+
+        if !triangles.iter().any(|(_, long)| long.contains(&true)) {
+            return;
+        }
+
+        let mut midpoints = HashMap::new();
+
+        indices.truncate(index_start);
+
+        for (triangle, long) in triangles {
+            let count = long.iter().filter(|l| **l).count();
+
+            // Rotate the triangle so that the split edges are in a consistent position,
+            // without changing its winding order.
+            let rotation = match count {
+                1 => long.iter().position(|l| *l).unwrap(),
+                2 => (long.iter().position(|l| !*l).unwrap() + 1) % 3,
+                _ => 0,
+            };
+
+            let a = triangle[rotation];
+            let b = triangle[(rotation + 1) % 3];
+            let c = triangle[(rotation + 2) % 3];
+
+            let mut midpoint = |from: u32, to: u32| midpoint(vertices, &mut midpoints, from, to);
+
+            match count {
+                0 => indices.extend_from_slice(&[a, b, c]),
+
+                1 => {
+                    let ab = midpoint(a, b);
+                    indices.extend_from_slice(&[a, ab, c, ab, b, c]);
+                }
+
+                2 => {
+                    let ab = midpoint(a, b);
+                    let bc = midpoint(b, c);
+                    indices.extend_from_slice(&[ab, b, bc, a, ab, bc, a, bc, c]);
+                }
+
+                _ => {
+                    let ab = midpoint(a, b);
+                    let bc = midpoint(b, c);
+                    let ca = midpoint(c, a);
+                    indices.extend_from_slice(&[a, ab, ca, ab, b, bc, ca, bc, c, ab, bc, ca]);
+                }
+            }
+        }
+    }
+}
+
+/// Returns the index of the vertex halfway between `a` and `b`, creating it
+/// if it doesn't exist yet.
+fn midpoint(
+    vertices: &mut Vec<Vertex>,
+    midpoints: &mut HashMap<(u32, u32), u32>,
+    a: u32,
+    b: u32,
+) -> u32 {
+    *midpoints.entry((a.min(b), a.max(b))).or_insert_with(|| {
+        let a = vertices[a as usize];
+        let b = vertices[b as usize];
+
+        vertices.push(Vertex::new(
+            (a.position + b.position) / 2.0,
+            (a.uv + b.uv) / 2.0,
+            CurveValue::lerp(a.color, b.color, 0.5),
+        ));
+
+        (vertices.len() - 1) as u32
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn quad() -> (Vec<Vertex>, Vec<u32>) {
+        let vertices = [(0.0, 0.0), (0.0, 10.0), (10.0, 10.0), (10.0, 0.0)]
+            .iter()
+            .map(|&(x, y)| Vertex::new(Vec2::new(x, y), Vec2::zero(), Color::WHITE))
+            .collect();
+
+        (vertices, vec![0, 1, 2, 2, 3, 0])
+    }
+
+    fn signed_area(vertices: &[Vertex], triangle: &[u32]) -> f32 {
+        let a = vertices[triangle[0] as usize].position;
+        let b = vertices[triangle[1] as usize].position;
+        let c = vertices[triangle[2] as usize].position;
+
+        (b.x - a.x) * (c.y - a.y) - (c.x - a.x) * (b.y - a.y)
+    }
+
+    #[test]
+    fn linear_gradient() {
+        let gradient = Gradient::linear(
+            Vec2::new(10.0, 0.0),
+            Vec2::new(10.0, 20.0),
+            Curve::new(Color::BLACK)
+                .with_key(0.5, Color::WHITE)
+                .with_key(1.0, Color::BLACK),
+        );
+
+        assert_eq!(gradient.color_at(Vec2::new(0.0, -5.0)), Color::BLACK);
+        assert_eq!(gradient.color_at(Vec2::new(50.0, 10.0)), Color::WHITE);
+        assert_eq!(
+            gradient.color_at(Vec2::new(10.0, 15.0)),
+            Color::rgb(0.5, 0.5, 0.5)
+        );
+        assert_eq!(gradient.color_at(Vec2::new(10.0, 25.0)), Color::BLACK);
+    }
+
+    #[test]
+    fn radial_gradient() {
+        let gradient = Gradient::radial(
+            Vec2::new(10.0, 10.0),
+            10.0,
+            Curve::linear(Color::WHITE, Color::BLACK),
+        );
+
+        assert_eq!(gradient.color_at(Vec2::new(10.0, 10.0)), Color::WHITE);
+        assert_eq!(
+            gradient.color_at(Vec2::new(10.0, 15.0)),
+            Color::rgb(0.5, 0.5, 0.5)
+        );
+        assert_eq!(gradient.color_at(Vec2::new(30.0, 30.0)), Color::BLACK);
+    }
+
+    #[test]
+    fn subdivision_splits_long_edges() {
+        let (mut vertices, mut indices) = quad();
+        let winding = signed_area(&vertices, &indices[..3]).signum();
+
+        subdivide(&mut vertices, &mut indices, 0, 6.0);
+
+        for triangle in indices.chunks_exact(3) {
+            for i in 0..3 {
+                let a = vertices[triangle[i] as usize].position;
+                let b = vertices[triangle[(i + 1) % 3] as usize].position;
+
+                assert!(a.distance(b) <= 6.0);
+            }
+
+            // The winding order of the original triangles should be preserved.
+            assert_eq!(signed_area(&vertices, triangle).signum(), winding);
+        }
+
+        // Midpoints are shared between neighbouring triangles, so no vertices
+        // should be duplicated.
+        for (i, a) in vertices.iter().enumerate() {
+            assert!(vertices[i + 1..].iter().all(|b| b.position != a.position));
+        }
+    }
+
+    #[test]
+    fn subdivision_only_affects_new_triangles() {
+        let (mut vertices, mut indices) = quad();
+
+        subdivide(&mut vertices, &mut indices, 3, 100.0);
+        assert_eq!(indices, vec![0, 1, 2, 2, 3, 0]);
+
+        subdivide(&mut vertices, &mut indices, 3, 12.0);
+        assert_eq!(&indices[..3], &[0, 1, 2]);
+        assert_eq!(vertices.len(), 5);
+    }
+}
@@ -0,0 +1,488 @@
+//! Path building and SVG path data parsing.
+
+use std::mem;
+
+use lyon_tessellation::geom::ArcFlags;
+use lyon_tessellation::math::{Angle, Point, Vector};
+use lyon_tessellation::path as lyon_path;
+use lyon_tessellation::path::builder::{SvgPathBuilder, WithSvg};
+use lyon_tessellation::path::path::Builder;
+
+use crate::math::Vec2;
+use crate::{Result, TetraError};
+
+/// A shape made up of lines, curves and arcs, which can be added to a
+/// [`GeometryBuilder`](super::GeometryBuilder).
+///
+/// Paths can be created either via a [`PathBuilder`], or by parsing
+/// [SVG path data](https://www.w3.org/TR/SVG11/paths.html#PathData).
+#[derive(Debug, Clone)]
+pub struct Path {
+    pub(crate) inner: lyon_path::Path,
+}
+
+impl Path {
+    /// Creates a new path builder.
+    pub fn builder() -> PathBuilder {
+        PathBuilder::new()
+    }
+
+    /// Creates a path by parsing the contents of an SVG path's `d` attribute.
+    ///
+    /// All of the SVG path commands are supported, in both their absolute and relative forms.
+    ///
+    /// # Errors
+    ///
+    /// * [`TetraError::InvalidSvg`](crate::TetraError::InvalidSvg) will be returned if the path
+    /// data could not be parsed.
+    pub fn from_svg(data: &str) -> Result<Path> {
+        Ok(Path {
+            inner: parse_path_data(data)?,
+        })
+    }
+}
+
+/// A builder for creating a [`Path`].
+///
+/// The commands map to those used in SVG path data - each sub-path starts with
+/// a [`move_to`](PathBuilder::move_to), and can optionally be ended with a
+/// [`close`](PathBuilder::close).
+pub struct PathBuilder {
+    builder: WithSvg<Builder>,
+}
+
+impl PathBuilder {
+    /// Creates a new empty path builder.
+    pub fn new() -> PathBuilder {
+        PathBuilder {
+            builder: lyon_path::Path::builder().with_svg(),
+        }
+    }
+
+    /// Starts a new sub-path at the given position.
+    ///
+    /// If a sub-path is in progress, it will be ended without being closed.
+    pub fn move_to(&mut self, to: Vec2<f32>) -> &mut PathBuilder {
+        self.builder.move_to(to_point(to));
+        self
+    }
+
+    /// Adds a straight line from the current position.
+    pub fn line_to(&mut self, to: Vec2<f32>) -> &mut PathBuilder {
+        self.builder.line_to(to_point(to));
+        self
+    }
+
+    /// Adds a quadratic Bézier curve from the current position.
+    pub fn quadratic_to(&mut self, ctrl: Vec2<f32>, to: Vec2<f32>) -> &mut PathBuilder {
+        self.builder
+            .quadratic_bezier_to(to_point(ctrl), to_point(to));
+        self
+    }
+
+    /// Adds a cubic Bézier curve from the current position.
+    pub fn cubic_to(
+        &mut self,
+        ctrl1: Vec2<f32>,
+        ctrl2: Vec2<f32>,
+        to: Vec2<f32>,
+    ) -> &mut PathBuilder {
+        self.builder
+            .cubic_bezier_to(to_point(ctrl1), to_point(ctrl2), to_point(to));
+        self
+    }
+
+    /// Adds an elliptical arc around the given center point, starting at the current position.
+    ///
+    /// The sweep angle and X axis rotation are specified in radians.
+    pub fn arc(
+        &mut self,
+        center: Vec2<f32>,
+        radii: Vec2<f32>,
+        sweep_angle: f32,
+        x_rotation: f32,
+    ) -> &mut PathBuilder {
+        self.builder.arc(
+            to_point(center),
+            Vector::new(radii.x, radii.y),
+            Angle::radians(sweep_angle),
+            Angle::radians(x_rotation),
+        );
+        self
+    }
+
+    /// Adds an elliptical arc from the current position to the given point.
+    ///
+    /// This is equivalent to SVG's `A` command - of the four possible arcs between the
+    /// two points, `large_arc` and `sweep` determine which will be drawn. The X axis
+    /// rotation is specified in radians.
+    pub fn arc_to(
+        &mut self,
+        radii: Vec2<f32>,
+        x_rotation: f32,
+        large_arc: bool,
+        sweep: bool,
+        to: Vec2<f32>,
+    ) -> &mut PathBuilder {
+        self.builder.arc_to(
+            Vector::new(radii.x, radii.y),
+            Angle::radians(x_rotation),
+            ArcFlags { large_arc, sweep },
+            to_point(to),
+        );
+        self
+    }
+
+    /// Closes the current sub-path, connecting it back to its starting point.
+    pub fn close(&mut self) -> &mut PathBuilder {
+        self.builder.close();
+        self
+    }
+
+    /// Builds the path.
+    ///
+    /// The builder will be reset afterwards, so that it can be reused.
+    pub fn build(&mut self) -> Path {
+        let builder = mem::replace(&mut self.builder, lyon_path::Path::builder().with_svg());
+
+        Path {
+            inner: builder.build(),
+        }
+    }
+}
+
+impl Default for PathBuilder {
+    fn default() -> Self {
+        PathBuilder::new()
+    }
+}
+
+fn to_point(point: Vec2<f32>) -> Point {
+    Point::new(point.x, point.y)
+}
+
+pub(crate) fn invalid<S>(message: S) -> TetraError
+where
+    S: Into<String>,
+{
+    TetraError::InvalidSvg(message.into())
+}
+
+pub(crate) fn parse_path_data(data: &str) -> Result<lyon_path::Path> {
+    let mut parser = PathParser {
+        data: data.as_bytes(),
+        position: 0,
+    };
+
+    let mut builder = lyon_path::Path::builder().with_svg();
+    let mut command = None;
+
+    loop {
+        parser.skip_separators();
+
+        let next = match parser.peek() {
+            Some(next) => next,
+            None => break,
+        };
+
+        if next.is_ascii_alphabetic() {
+            parser.position += 1;
+            command = Some(next);
+        } else if command.is_none() {
+            return Err(invalid(format!(
+                "expected a path command at position {}",
+                parser.position
+            )));
+        }
+
+        match command {
+            Some(b'M') => {
+                builder.move_to(parser.point()?);
+                command = Some(b'L');
+            }
+            Some(b'm') => {
+                builder.relative_move_to(parser.vector()?);
+                command = Some(b'l');
+            }
+            Some(b'L') => {
+                builder.line_to(parser.point()?);
+            }
+            Some(b'l') => {
+                builder.relative_line_to(parser.vector()?);
+            }
+            Some(b'H') => {
+                builder.horizontal_line_to(parser.number()?);
+            }
+            Some(b'h') => {
+                builder.relative_horizontal_line_to(parser.number()?);
+            }
+            Some(b'V') => {
+                builder.vertical_line_to(parser.number()?);
+            }
+            Some(b'v') => {
+                builder.relative_vertical_line_to(parser.number()?);
+            }
+            Some(b'Q') => {
+                builder.quadratic_bezier_to(parser.point()?, parser.point()?);
+            }
+            Some(b'q') => {
+                builder.relative_quadratic_bezier_to(parser.vector()?, parser.vector()?);
+            }
+            Some(b'T') => {
+                builder.smooth_quadratic_bezier_to(parser.point()?);
+            }
+            Some(b't') => {
+                builder.smooth_relative_quadratic_bezier_to(parser.vector()?);
+            }
+            Some(b'C') => {
+                builder.cubic_bezier_to(parser.point()?, parser.point()?, parser.point()?);
+            }
+            Some(b'c') => {
+                builder.relative_cubic_bezier_to(
+                    parser.vector()?,
+                    parser.vector()?,
+                    parser.vector()?,
+                );
+            }
+            Some(b'S') => {
+                builder.smooth_cubic_bezier_to(parser.point()?, parser.point()?);
+            }
+            Some(b's') => {
+                builder.smooth_relative_cubic_bezier_to(parser.vector()?, parser.vector()?);
+            }
+            Some(b'A') => {
+                let (radii, x_rotation, flags) = parser.arc()?;
+                builder.arc_to(radii, x_rotation, flags, parser.point()?);
+            }
+            Some(b'a') => {
+                let (radii, x_rotation, flags) = parser.arc()?;
+                builder.relative_arc_to(radii, x_rotation, flags, parser.vector()?);
+            }
+            Some(b'Z') | Some(b'z') => {
+                builder.close();
+
+                // Closing a sub-path can't be implicitly repeated, so the next
+                // token has to be a new command.
+                command = None;
+            }
+            Some(other) => {
+                return Err(invalid(format!(
+                    "unknown path command '{}'",
+                    char::from(other)
+                )));
+            }
+            None => unreachable!(),
+        }
+    }
+
+    Ok(builder.build())
+}
+
+/// Parses a list of numbers, seperated by whitespace and/or commas, using the same
+/// syntax as path data.
+#[cfg(feature = "svg")]
+pub(crate) fn parse_number_list(data: &str) -> Result<Vec<f32>> {
+    let mut parser = PathParser {
+        data: data.as_bytes(),
+        position: 0,
+    };
+
+    let mut numbers = Vec::new();
+
+    loop {
+        parser.skip_separators();
+
+        if parser.peek().is_none() {
+            return Ok(numbers);
+        }
+
+        numbers.push(parser.number()?);
+    }
+}
+
+struct PathParser<'a> {
+    data: &'a [u8],
+    position: usize,
+}
+
+impl PathParser<'_> {
+    fn peek(&self) -> Option<u8> {
+        self.data.get(self.position).copied()
+    }
+
+    fn skip_separators(&mut self) {
+        while matches!(
+            self.peek(),
+            Some(b' ') | Some(b'\t') | Some(b'\r') | Some(b'\n') | Some(b',')
+        ) {
+            self.position += 1;
+        }
+    }
+
+    fn skip_digits(&mut self) -> usize {
+        let start = self.position;
+
+        while matches!(self.peek(), Some(b'0'..=b'9')) {
+            self.position += 1;
+        }
+
+        self.position - start
+    }
+
+    fn number(&mut self) -> Result<f32> {
+        self.skip_separators();
+
+        let start = self.position;
+
+        if matches!(self.peek(), Some(b'+') | Some(b'-')) {
+            self.position += 1;
+        }
+
+        let mut digits = self.skip_digits();
+
+        if self.peek() == Some(b'.') {
+            self.position += 1;
+            digits += self.skip_digits();
+        }
+
+        if digits == 0 {
+            self.position = start;
+            return Err(invalid(format!("expected a number at position {}", start)));
+        }
+
+        if matches!(self.peek(), Some(b'e') | Some(b'E')) {
+            let mantissa_end = self.position;
+            self.position += 1;
+
+            if matches!(self.peek(), Some(b'+') | Some(b'-')) {
+                self.position += 1;
+            }
+
+            // An 'e' that isn't followed by digits isn't part of the number.
+            if self.skip_digits() == 0 {
+                self.position = mantissa_end;
+            }
+        }
+
+        // The scanned range only contains ASCII, so it's always valid UTF-8.
+        std::str::from_utf8(&self.data[start..self.position])
+            .ok()
+            .and_then(|number| number.parse::<f32>().ok())
+            .filter(|number| number.is_finite())
+            .ok_or_else(|| invalid(format!("invalid number at position {}", start)))
+    }
+
+    fn flag(&mut self) -> Result<bool> {
+        self.skip_separators();
+
+        let flag = match self.peek() {
+            Some(b'0') => false,
+            Some(b'1') => true,
+            _ => {
+                return Err(invalid(format!(
+                    "expected an arc flag at position {}",
+                    self.position
+                )))
+            }
+        };
+
+        self.position += 1;
+
+        Ok(flag)
+    }
+
+    fn point(&mut self) -> Result<Point> {
+        Ok(Point::new(self.number()?, self.number()?))
+    }
+
+    fn vector(&mut self) -> Result<Vector> {
+        Ok(Vector::new(self.number()?, self.number()?))
+    }
+
+    fn arc(&mut self) -> Result<(Vector, Angle, ArcFlags)> {
+        let radii = self.vector()?;
+        let x_rotation = Angle::degrees(self.number()?);
+        let large_arc = self.flag()?;
+        let sweep = self.flag()?;
+
+        Ok((radii, x_rotation, ArcFlags { large_arc, sweep }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use lyon_tessellation::path::PathEvent;
+
+    fn events(data: &str) -> Vec<PathEvent> {
+        parse_path_data(data).unwrap().iter().collect()
+    }
+
+    fn point(x: f32, y: f32) -> Point {
+        Point::new(x, y)
+    }
+
+    #[test]
+    fn parse_lines() {
+        assert_eq!(
+            events("M 10,10 L 20 10 v10 h-10 z"),
+            vec![
+                PathEvent::Begin {
+                    at: point(10.0, 10.0)
+                },
+                PathEvent::Line {
+                    from: point(10.0, 10.0),
+                    to: point(20.0, 10.0)
+                },
+                PathEvent::Line {
+                    from: point(20.0, 10.0),
+                    to: point(20.0, 20.0)
+                },
+                PathEvent::Line {
+                    from: point(20.0, 20.0),
+                    to: point(10.0, 20.0)
+                },
+                PathEvent::End {
+                    last: point(10.0, 20.0),
+                    first: point(10.0, 10.0),
+                    close: true
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_implicit_commands() {
+        // Extra coordinates after a move are treated as lines, and numbers
+        // don't need to be separated if the sign or decimal point is unambiguous.
+        assert_eq!(events("m1-1 2.5.5"), events("M 1 -1 l 2.5 0.5"));
+        assert_eq!(events("M0 0L1 1 2 2"), events("M0 0L1 1L2 2"));
+        assert_eq!(events("M0 0L1e1-1E-1"), events("M0 0L10 -0.1"));
+    }
+
+    #[test]
+    fn parse_compact_arc_flags() {
+        assert_eq!(events("M0 0a5 5 0 0110 0"), events("M0 0 a 5 5 0 0 1 10 0"));
+    }
+
+    #[test]
+    #[cfg(feature = "svg")]
+    fn parse_numbers() {
+        assert_eq!(
+            parse_number_list(" 1,2 -3.5.5e1 ").unwrap(),
+            vec![1.0, 2.0, -3.5, 5.0]
+        );
+        assert!(parse_number_list("1 px").is_err());
+    }
+
+    #[test]
+    fn parse_errors() {
+        assert!(parse_path_data("10 10").is_err());
+        assert!(parse_path_data("M 10").is_err());
+        assert!(parse_path_data("M 0 0 X 1 1").is_err());
+        assert!(parse_path_data("M 0 0 Z 1 1").is_err());
+        assert!(parse_path_data("M 0 0 A 5 5 0 2 0 1 1").is_err());
+        assert!(parse_path_data("M 1e39 0").is_err());
+    }
+}
@@ -0,0 +1,717 @@
+//! Importing of shapes from SVG documents.
+
+use std::path::Path as FilePath;
+
+use lyon_tessellation::geom::{ArcFlags, LineSegment};
+use lyon_tessellation::math::{Angle, Point, Transform, Vector};
+use lyon_tessellation::path::builder::{PathBuilder as _, SvgPathBuilder as _};
+use lyon_tessellation::path::{Path as LyonPath, Polygon, Winding};
+use roxmltree::{Document, Node};
+
+use crate::fs;
+use crate::graphics::mesh::path::{invalid, parse_number_list, parse_path_data};
+use crate::graphics::mesh::{
+    FillOptions, FillRule, GeometryBuilder, LineCap, LineJoin, Mesh, ShapeStyle, StrokeOptions,
+};
+use crate::graphics::Color;
+use crate::{Context, Result};
+
+impl GeometryBuilder {
+    /// Adds the shapes from an SVG document.
+    ///
+    /// Only a basic subset of SVG is supported:
+    ///
+    /// * `<path>`, `<rect>`, `<circle>`, `<ellipse>`, `<line>`, `<polyline>` and `<polygon>` elements
+    /// are imported, and can be nested inside of `<g>` elements.
+    /// * Solid fills and strokes are supported, including opacity, fill rules, line caps, line joins
+    /// and dashes. These can be specified either as attributes or via the `style` attribute.
+    /// * Colors can be specified as hex codes (with or without alpha), via `rgb()`/`rgba()`, or
+    /// via the basic named colors. Gradients, patterns and other colors are not supported, and
+    /// will be skipped.
+    /// * `transform` attributes are supported.
+    /// * Shapes are imported in the document's user units - the `viewBox`, `width` and `height` of
+    /// the document are ignored, and lengths with units other than `px` are rejected.
+    ///
+    /// The current color, stroke options and fill options of the builder will be left unchanged.
+    ///
+    /// This method requires the `svg` feature to be enabled.
+    ///
+    /// # Errors
+    ///
+    /// * [`TetraError::InvalidSvg`](crate::TetraError::InvalidSvg) will be returned if the document
+    /// is invalid or uses unsupported features.
+    /// * [`TetraError::TessellationError`](crate::TetraError::TessellationError) will be returned if a shape
+    /// could not be turned into vertex data.
+    pub fn svg(&mut self, data: &str) -> Result<&mut GeometryBuilder> {
+        let document = Document::parse(data).map_err(|e| invalid(format!("invalid XML: {}", e)))?;
+
+        let root = document.root_element();
+
+        if root.tag_name().name() != "svg" {
+            return Err(invalid("expected an <svg> element"));
+        }
+
+        let color = self.color;
+        let stroke_options = self.stroke_options.clone();
+        let fill_options = self.fill_options;
+
+        let result = add_node(self, root, &Style::default());
+
+        self.color = color;
+        self.stroke_options = stroke_options;
+        self.fill_options = fill_options;
+
+        result.map(|_| self)
+    }
+}
+
+impl Mesh {
+    /// Creates a new mesh from the shapes in an SVG file.
+    ///
+    /// See [`GeometryBuilder::svg`] for details of which SVG features are supported.
+    ///
+    /// This method requires the `svg` feature to be enabled.
+    ///
+    /// # Errors
+    ///
+    /// * [`TetraError::FailedToLoadAsset`](crate::TetraError::FailedToLoadAsset) will be returned if the file
+    /// could not be loaded.
+    /// * [`TetraError::InvalidSvg`](crate::TetraError::InvalidSvg) will be returned if the document
+    /// is invalid or uses unsupported features.
+    /// * [`TetraError::TessellationError`](crate::TetraError::TessellationError) will be returned if a shape
+    /// could not be turned into vertex data.
+    /// * [`TetraError::PlatformError`](crate::TetraError::PlatformError) will be returned if the underlying
+    /// graphics API encounters an error.
+    pub fn from_svg_file<P>(ctx: &mut Context, path: P) -> Result<Mesh>
+    where
+        P: AsRef<FilePath>,
+    {
+        let data = fs::read_to_string(path)?;
+
+        Mesh::from_svg_string(ctx, &data)
+    }
+
+    /// Creates a new mesh from the shapes in an SVG document.
+    ///
+    /// See [`GeometryBuilder::svg`] for details of which SVG features are supported.
+    ///
+    /// This method requires the `svg` feature to be enabled.
+    ///
+    /// # Errors
+    ///
+    /// * [`TetraError::InvalidSvg`](crate::TetraError::InvalidSvg) will be returned if the document
+    /// is invalid or uses unsupported features.
+    /// * [`TetraError::TessellationError`](crate::TetraError::TessellationError) will be returned if a shape
+    /// could not be turned into vertex data.
+    /// * [`TetraError::PlatformError`](crate::TetraError::PlatformError) will be returned if the underlying
+    /// graphics API encounters an error.
+    pub fn from_svg_string(ctx: &mut Context, data: &str) -> Result<Mesh> {
+        GeometryBuilder::new().svg(data)?.build_mesh(ctx)
+    }
+}
+
+/// The presentation attributes that apply to an element, including any that
+/// were inherited from its parents.
+#[derive(Debug, Clone)]
+struct Style {
+    color: Color,
+    fill: Option<Paint>,
+    stroke: Option<Paint>,
+    stroke_width: f32,
+    opacity: f32,
+    fill_opacity: f32,
+    stroke_opacity: f32,
+    fill_rule: FillRule,
+    stroke_options: StrokeOptions,
+    transform: Transform,
+    display: bool,
+}
+
+impl Default for Style {
+    fn default() -> Style {
+        Style {
+            color: Color::BLACK,
+            fill: Some(Paint::Color(Color::BLACK)),
+            stroke: None,
+            stroke_width: 1.0,
+            opacity: 1.0,
+            fill_opacity: 1.0,
+            stroke_opacity: 1.0,
+            fill_rule: FillRule::NonZero,
+            stroke_options: StrokeOptions::default(),
+            transform: Transform::identity(),
+            display: true,
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone)]
+enum Paint {
+    Color(Color),
+    CurrentColor,
+}
+
+impl Style {
+    fn apply(&mut self, node: Node<'_, '_>) -> Result {
+        for attribute in node.attributes() {
+            self.set_property(attribute.name(), attribute.value())?;
+        }
+
+        // Properties set via CSS take precedence over attributes.
+        if let Some(css) = node.attribute("style") {
+            for declaration in css.split(';') {
+                if let Some((name, value)) = declaration.split_once(':') {
+                    self.set_property(name.trim(), value.trim())?;
+                }
+            }
+        }
+
+        if let Some(transform) = node.attribute("transform") {
+            self.transform = parse_transform(transform)?.then(&self.transform);
+        }
+
+        Ok(())
+    }
+
+    fn set_property(&mut self, name: &str, value: &str) -> Result {
+        if value == "inherit" {
+            return Ok(());
+        }
+
+        match name {
+            "color" => {
+                // Unsupported colors are skipped, so the inherited color is used instead.
+                if let Ok(color) = parse_color(value) {
+                    self.color = color;
+                }
+            }
+
+            "fill" => self.fill = parse_paint(value)?,
+            "stroke" => self.stroke = parse_paint(value)?,
+            "stroke-width" => self.stroke_width = parse_length(value)?,
+            "opacity" => self.opacity *= parse_opacity(value)?,
+            "fill-opacity" => self.fill_opacity = parse_opacity(value)?,
+            "stroke-opacity" => self.stroke_opacity = parse_opacity(value)?,
+            "display" => self.display = value != "none",
+
+            "fill-rule" => {
+                self.fill_rule = match value {
+                    "nonzero" => FillRule::NonZero,
+                    "evenodd" => FillRule::EvenOdd,
+                    _ => return Err(invalid(format!("unknown fill rule '{}'", value))),
+                }
+            }
+
+            "stroke-linecap" => {
+                let cap = match value {
+                    "butt" => LineCap::Butt,
+                    "round" => LineCap::Round,
+                    "square" => LineCap::Square,
+                    _ => return Err(invalid(format!("unknown line cap '{}'", value))),
+                };
+
+                self.stroke_options.start_cap = cap;
+                self.stroke_options.end_cap = cap;
+            }
+
+            "stroke-linejoin" => {
+                self.stroke_options.line_join = match value {
+                    "miter" => LineJoin::Miter,
+                    "miter-clip" => LineJoin::MiterClip,
+                    "round" => LineJoin::Round,
+                    "bevel" => LineJoin::Bevel,
+                    _ => return Err(invalid(format!("unknown line join '{}'", value))),
+                }
+            }
+
+            "stroke-miterlimit" => {
+                self.stroke_options.miter_limit = parse_number(value)?.max(1.0);
+            }
+
+            "stroke-dasharray" => {
+                self.stroke_options.dash_pattern = if value == "none" {
+                    Vec::new()
+                } else {
+                    parse_number_list(&value.replace("px", ""))?
+                }
+            }
+
+            "stroke-dashoffset" => self.stroke_options.dash_offset = parse_length(value)?,
+
+            _ => {}
+        }
+
+        Ok(())
+    }
+
+    fn resolve(&self, paint: Option<Paint>, opacity: f32) -> Option<Color> {
+        let color = match paint? {
+            Paint::Color(color) => color,
+            Paint::CurrentColor => self.color,
+        };
+
+        Some(color.with_alpha(color.a * opacity * self.opacity))
+    }
+}
+
+fn add_node(builder: &mut GeometryBuilder, node: Node<'_, '_>, parent: &Style) -> Result {
+    let mut style = parent.clone();
+    style.apply(node)?;
+
+    if !style.display {
+        return Ok(());
+    }
+
+    let path = match node.tag_name().name() {
+        "svg" | "g" | "a" => {
+            for child in node.children().filter(|n| n.is_element()) {
+                add_node(builder, child, &style)?;
+            }
+
+            return Ok(());
+        }
+
+        "path" => match node.attribute("d") {
+            Some(data) => parse_path_data(data)?,
+            None => return Ok(()),
+        },
+
+        "rect" => {
+            let x = length(node, "x")?;
+            let y = length(node, "y")?;
+            let width = length(node, "width")?;
+            let height = length(node, "height")?;
+
+            // If only one of the corner radii is specified, it's used for both axes.
+            let (rx, ry) = match (node.attribute("rx"), node.attribute("ry")) {
+                (Some(rx), Some(ry)) => (parse_length(rx)?, parse_length(ry)?),
+                (Some(r), None) | (None, Some(r)) => {
+                    let r = parse_length(r)?;
+                    (r, r)
+                }
+                (None, None) => (0.0, 0.0),
+            };
+
+            if width <= 0.0 || height <= 0.0 {
+                return Ok(());
+            }
+
+            rect_path(
+                x,
+                y,
+                width,
+                height,
+                rx.min(width / 2.0),
+                ry.min(height / 2.0),
+            )
+        }
+
+        "circle" => {
+            let mut path = LyonPath::builder();
+
+            path.add_circle(
+                Point::new(length(node, "cx")?, length(node, "cy")?),
+                length(node, "r")?,
+                Winding::Positive,
+            );
+
+            path.build()
+        }
+
+        "ellipse" => {
+            let mut path = LyonPath::builder();
+
+            path.add_ellipse(
+                Point::new(length(node, "cx")?, length(node, "cy")?),
+                Vector::new(length(node, "rx")?, length(node, "ry")?),
+                Angle::radians(0.0),
+                Winding::Positive,
+            );
+
+            path.build()
+        }
+
+        "line" => {
+            let mut path = LyonPath::builder();
+
+            path.add_line_segment(&LineSegment {
+                from: Point::new(length(node, "x1")?, length(node, "y1")?),
+                to: Point::new(length(node, "x2")?, length(node, "y2")?),
+            });
+
+            path.build()
+        }
+
+        name @ "polyline" | name @ "polygon" => {
+            let numbers = parse_number_list(node.attribute("points").unwrap_or(""))?;
+
+            let points: Vec<Point> = numbers
+                .chunks_exact(2)
+                .map(|point| Point::new(point[0], point[1]))
+                .collect();
+
+            if points.len() < 2 {
+                return Ok(());
+            }
+
+            let mut path = LyonPath::builder();
+
+            path.add_polygon(Polygon {
+                points: &points,
+                closed: name == "polygon",
+            });
+
+            path.build()
+        }
+
+        _ => return Ok(()),
+    };
+
+    draw_path(builder, &path.transformed(&style.transform), &style)
+}
+
+fn draw_path(builder: &mut GeometryBuilder, path: &LyonPath, style: &Style) -> Result {
+    if let Some(color) = style.resolve(style.fill, style.fill_opacity) {
+        builder.color = color;
+        builder.fill_options = FillOptions::new().fill_rule(style.fill_rule);
+        builder.add_path(ShapeStyle::Fill, path)?;
+    }
+
+    if let Some(color) = style.resolve(style.stroke, style.stroke_opacity) {
+        // Strokes are generated after the path has been transformed, so their
+        // dimensions need to be scaled to match.
+        let scale = style.transform.determinant().abs().sqrt();
+
+        let mut options = style.stroke_options.clone();
+        options.dash_offset *= scale;
+
+        for dash in &mut options.dash_pattern {
+            *dash *= scale;
+        }
+
+        if style.stroke_width > 0.0 {
+            builder.color = color;
+            builder.stroke_options = options;
+            builder.add_path(ShapeStyle::Stroke(style.stroke_width * scale), path)?;
+        }
+    }
+
+    Ok(())
+}
+
+fn rect_path(x: f32, y: f32, width: f32, height: f32, rx: f32, ry: f32) -> LyonPath {
+    let mut path = LyonPath::builder().with_svg();
+
+    if rx <= 0.0 || ry <= 0.0 {
+        path.move_to(Point::new(x, y));
+        path.line_to(Point::new(x + width, y));
+        path.line_to(Point::new(x + width, y + height));
+        path.line_to(Point::new(x, y + height));
+        path.close();
+
+        return path.build();
+    }
+
+    let radii = Vector::new(rx, ry);
+    let flags = ArcFlags {
+        large_arc: false,
+        sweep: true,
+    };
+
+    path.move_to(Point::new(x + rx, y));
+    path.line_to(Point::new(x + width - rx, y));
+    path.arc_to(radii, Angle::zero(), flags, Point::new(x + width, y + ry));
+    path.line_to(Point::new(x + width, y + height - ry));
+    path.arc_to(
+        radii,
+        Angle::zero(),
+        flags,
+        Point::new(x + width - rx, y + height),
+    );
+    path.line_to(Point::new(x + rx, y + height));
+    path.arc_to(radii, Angle::zero(), flags, Point::new(x, y + height - ry));
+    path.line_to(Point::new(x, y + ry));
+    path.arc_to(radii, Angle::zero(), flags, Point::new(x + rx, y));
+    path.close();
+
+    path.build()
+}
+
+fn length(node: Node<'_, '_>, name: &str) -> Result<f32> {
+    node.attribute(name).map_or(Ok(0.0), parse_length)
+}
+
+fn parse_number(value: &str) -> Result<f32> {
+    value
+        .trim()
+        .parse::<f32>()
+        .ok()
+        .filter(|number| number.is_finite())
+        .ok_or_else(|| invalid(format!("invalid number '{}'", value)))
+}
+
+fn parse_length(value: &str) -> Result<f32> {
+    let value = value.trim();
+
+    parse_number(value.strip_suffix("px").unwrap_or(value))
+        .map_err(|_| invalid(format!("unsupported length '{}'", value)))
+}
+
+fn parse_opacity(value: &str) -> Result<f32> {
+    Ok(parse_number(value)?.clamp(0.0, 1.0))
+}
+
+fn parse_paint(value: &str) -> Result<Option<Paint>> {
+    let value = value.trim();
+
+    if let Some(reference) = value.strip_prefix("url(") {
+        // Gradients and patterns aren't supported, so use the fallback color
+        // if there is one.
+        let fallback = reference
+            .split_once(')')
+            .map_or("", |(_, rest)| rest.trim());
+
+        return if fallback.is_empty() {
+            Ok(None)
+        } else {
+            parse_paint(fallback)
+        };
+    }
+
+    match value {
+        "none" => Ok(None),
+        "currentColor" => Ok(Some(Paint::CurrentColor)),
+        // Like gradients, colors that can't be parsed are skipped rather than failing
+        // the whole import.
+        _ => Ok(parse_color(value).ok().map(Paint::Color)),
+    }
+}
+
+fn parse_color(value: &str) -> Result<Color> {
+    let value = value.trim();
+    let error = || invalid(format!("unsupported color '{}'", value));
+
+    if let Some(hex) = value.strip_prefix('#') {
+        return match hex.len() {
+            3 | 4 => {
+                let expanded: String = hex.chars().flat_map(|c| vec![c, c]).collect();
+                Color::try_hex(&expanded).map_err(|_| error())
+            }
+            6 | 8 => Color::try_hex(hex).map_err(|_| error()),
+            _ => Err(error()),
+        };
+    }
+
+    if let Some(args) = value
+        .strip_prefix("rgba(")
+        .or_else(|| value.strip_prefix("rgb("))
+        .and_then(|args| args.strip_suffix(')'))
+    {
+        let channels = args
+            .split(',')
+            .enumerate()
+            .map(|(i, channel)| {
+                let channel = channel.trim();
+
+                // Unlike the color channels, alpha is specified as a number between 0 and 1.
+                let value = match channel.strip_suffix('%') {
+                    Some(percent) => parse_number(percent)? / 100.0,
+                    None if i == 3 => parse_number(channel)?,
+                    None => parse_number(channel)? / 255.0,
+                };
+
+                Ok(value.clamp(0.0, 1.0))
+            })
+            .collect::<Result<Vec<f32>>>()
+            .map_err(|_| error())?;
+
+        return match channels[..] {
+            [r, g, b] => Ok(Color::rgb(r, g, b)),
+            [r, g, b, a] => Ok(Color::rgba(r, g, b, a)),
+            _ => Err(error()),
+        };
+    }
+
+    let hex = match value.to_ascii_lowercase().as_str() {
+        "black" => "000000",
+        "silver" => "c0c0c0",
+        "gray" | "grey" => "808080",
+        "white" => "ffffff",
+        "maroon" => "800000",
+        "red" => "ff0000",
+        "purple" => "800080",
+        "fuchsia" | "magenta" => "ff00ff",
+        "green" => "008000",
+        "lime" => "00ff00",
+        "olive" => "808000",
+        "yellow" => "ffff00",
+        "navy" => "000080",
+        "blue" => "0000ff",
+        "teal" => "008080",
+        "aqua" | "cyan" => "00ffff",
+        "orange" => "ffa500",
+        "transparent" => return Ok(Color::rgba(0.0, 0.0, 0.0, 0.0)),
+        _ => return Err(error()),
+    };
+
+    Ok(Color::hex(hex))
+}
+
+fn parse_transform(value: &str) -> Result<Transform> {
+    let mut transform = Transform::identity();
+    let mut rest = value.trim();
+
+    while !rest.is_empty() {
+        let open = rest
+            .find('(')
+            .ok_or_else(|| invalid(format!("invalid transform '{}'", value)))?;
+
+        let close = rest
+            .find(')')
+            .ok_or_else(|| invalid(format!("invalid transform '{}'", value)))?;
+
+        if close < open {
+            return Err(invalid(format!("invalid transform '{}'", value)));
+        }
+
+        let name = rest[..open].trim();
+        let args = parse_number_list(&rest[open + 1..close])?;
+
+        let next = match (name, &args[..]) {
+            ("matrix", &[a, b, c, d, e, f]) => Transform::new(a, b, c, d, e, f),
+            ("translate", &[x]) => Transform::translation(x, 0.0),
+            ("translate", &[x, y]) => Transform::translation(x, y),
+            ("scale", &[s]) => Transform::scale(s, s),
+            ("scale", &[x, y]) => Transform::scale(x, y),
+            ("rotate", &[angle]) => Transform::rotation(Angle::degrees(angle)),
+            ("rotate", &[angle, x, y]) => Transform::translation(-x, -y)
+                .then_rotate(Angle::degrees(angle))
+                .then_translate(Vector::new(x, y)),
+            ("skewX", &[angle]) => {
+                Transform::new(1.0, 0.0, angle.to_radians().tan(), 1.0, 0.0, 0.0)
+            }
+            ("skewY", &[angle]) => {
+                Transform::new(1.0, angle.to_radians().tan(), 0.0, 1.0, 0.0, 0.0)
+            }
+            _ => return Err(invalid(format!("invalid transform '{}'", value))),
+        };
+
+        // Transforms in a list are applied from right to left.
+        transform = next.then(&transform);
+
+        rest = rest[close + 1..].trim_start_matches(|c: char| c.is_whitespace() || c == ',');
+    }
+
+    Ok(transform)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_colors() {
+        assert_eq!(parse_color("#f00").unwrap(), Color::rgb(1.0, 0.0, 0.0));
+        assert_eq!(parse_color("#0000ff").unwrap(), Color::rgb(0.0, 0.0, 1.0));
+        assert_eq!(parse_color("White").unwrap(), Color::WHITE);
+        assert_eq!(
+            parse_color("rgb(255, 0, 100%)").unwrap(),
+            Color::rgb(1.0, 0.0, 1.0)
+        );
+        assert_eq!(parse_color("#f008").unwrap(), Color::rgba8(255, 0, 0, 0x88));
+        assert_eq!(
+            parse_color("#0000ff80").unwrap(),
+            Color::rgba8(0, 0, 255, 0x80)
+        );
+        assert_eq!(
+            parse_color("rgba(255, 0, 0, 0.5)").unwrap(),
+            Color::rgba(1.0, 0.0, 0.0, 0.5)
+        );
+        assert_eq!(
+            parse_color("rgba(0, 0, 255, 25%)").unwrap(),
+            Color::rgba(0.0, 0.0, 1.0, 0.25)
+        );
+        assert!(parse_color("#ff").is_err());
+        assert!(parse_color("rgba(0, 0)").is_err());
+        assert!(parse_color("hsl(0, 0%, 0%)").is_err());
+    }
+
+    #[test]
+    fn parse_paints() {
+        assert!(parse_paint("none").unwrap().is_none());
+        assert!(parse_paint("url(#gradient)").unwrap().is_none());
+        assert!(parse_paint("hsl(0, 0%, 0%)").unwrap().is_none());
+        assert!(parse_paint("rebeccapurple").unwrap().is_none());
+
+        match parse_paint("url(#gradient) red").unwrap() {
+            Some(Paint::Color(color)) => assert_eq!(color, Color::RED),
+            other => panic!("unexpected paint: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn parse_transforms() {
+        let transform = parse_transform("translate(10, 20) scale(2)").unwrap();
+
+        assert_eq!(
+            transform.transform_point(Point::new(1.0, 1.0)),
+            Point::new(12.0, 22.0)
+        );
+
+        let transform = parse_transform("rotate(90 5 5)").unwrap();
+        let point = transform.transform_point(Point::new(10.0, 5.0));
+
+        assert!((point.x - 5.0).abs() < 0.0001);
+        assert!((point.y - 10.0).abs() < 0.0001);
+
+        assert!(parse_transform("translate(1, 2").is_err());
+        assert!(parse_transform("spin(90)").is_err());
+    }
+
+    #[test]
+    fn reject_non_finite_numbers() {
+        assert_eq!(parse_length("12px").unwrap(), 12.0);
+        assert!(parse_length("inf").is_err());
+        assert!(parse_number("NaN").is_err());
+        assert!(parse_number("1e39").is_err());
+        assert!(parse_transform("scale(1e39)").is_err());
+    }
+
+    #[test]
+    fn style_attributes() {
+        let document = Document::parse(
+            r#"<g fill="red" stroke-width="2" style="fill: blue; stroke-linecap: round" />"#,
+        )
+        .unwrap();
+
+        let mut style = Style::default();
+        style.apply(document.root_element()).unwrap();
+
+        match style.fill {
+            Some(Paint::Color(color)) => assert_eq!(color, Color::BLUE),
+            other => panic!("unexpected paint: {:?}", other),
+        }
+
+        assert_eq!(style.stroke_width, 2.0);
+        assert_eq!(style.stroke_options.start_cap, LineCap::Round);
+    }
+
+    #[test]
+    fn skip_unsupported_colors() {
+        let document =
+            Document::parse(r#"<g fill="hsl(0, 0%, 0%)" stroke="red" color="hwb(0 0% 0%)" />"#)
+                .unwrap();
+
+        let mut style = Style::default();
+        style.apply(document.root_element()).unwrap();
+
+        assert!(style.fill.is_none());
+
+        match style.stroke {
+            Some(Paint::Color(color)) => assert_eq!(color, Color::RED),
+            other => panic!("unexpected paint: {:?}", other),
+        }
+
+        assert_eq!(style.color, Color::BLACK);
+    }
+}
@@ -0,0 +1,537 @@
+//! Functions and types relating to particle effects.
+//!
+//! A [`ParticleEmitter`] spawns particles from a [`Texture`], simulates them, and draws
+//! them via the sprite batcher. Its behaviour is configured via [`ParticleSettings`],
+//! which describes how particles are spawned and how they change over their lifetime.
+//!
+//! # Determinism
+//!
+//! Each emitter has its own random number generator, which can be seeded via
+//! [`ParticleEmitter::with_seed`] or [`ParticleEmitter::set_seed`]. Given the same seed,
+//! settings and sequence of updates, an emitter will always produce the same particles.
+//! When using [`Timestep::Fixed`](crate::time::Timestep::Fixed), calling
+//! [`ParticleEmitter::update`] from your [`State::update`](crate::State::update) method
+//! will therefore give identical results on every run.
+
+use std::f32::consts::PI;
+use std::ops::Range;
+use std::time::Duration;
+
+use crate::context::Context;
+use crate::graphics::rng::Rng;
+use crate::graphics::{Color, DrawParams, Rectangle, Texture};
+use crate::math::Vec2;
+use crate::time;
+
+pub use crate::graphics::{Curve, CurveValue};
+
+/// The area that particles are spawned within, relative to the emitter's position.
+#[derive(Debug, Clone, PartialEq)]
+pub enum EmitterShape {
+    /// Particles are spawned at the emitter's position.
+    Point,
+
+    /// Particles are spawned within a rectangle, centered on the emitter's position.
+    Rectangle {
+        /// The width of the rectangle.
+        width: f32,
+
+        /// The height of the rectangle.
+        height: f32,
+    },
+
+    /// Particles are spawned within a circle, centered on the emitter's position.
+    Circle {
+        /// The radius of the circle.
+        radius: f32,
+    },
+
+    /// Particles are spawned along a line made up of multiple points.
+    ///
+    /// Longer segments of the line will spawn proportionally more particles.
+    Polyline(Vec<Vec2<f32>>),
+}
+
+impl EmitterShape {
+    fn sample(&self, rng: &mut Rng) -> Vec2<f32> {
+        match self {
+            EmitterShape::Point => Vec2::zero(),
+
+            EmitterShape::Rectangle { width, height } => Vec2::new(
+                (rng.next_f32() - 0.5) * width,
+                (rng.next_f32() - 0.5) * height,
+            ),
+
+            EmitterShape::Circle { radius } => {
+                // Taking the square root gives a uniform distribution over the circle's area.
+                let distance = radius * rng.next_f32().sqrt();
+                let angle = rng.next_f32() * PI * 2.0;
+
+                Vec2::new(angle.cos(), angle.sin()) * distance
+            }
+
+            EmitterShape::Polyline(points) => {
+                let total: f32 = points.windows(2).map(|w| w[0].distance(w[1])).sum();
+
+                if points.len() < 2 || total <= 0.0 {
+                    return points.first().copied().unwrap_or_else(Vec2::zero);
+                }
+
+                let mut remaining = rng.next_f32() * total;
+
+                for segment in points.windows(2) {
+                    let length = segment[0].distance(segment[1]);
+
+                    if remaining <= length {
+                        return CurveValue::lerp(segment[0], segment[1], remaining / length);
+                    }
+
+                    remaining -= length;
+                }
+
+                points[points.len() - 1]
+            }
+        }
+    }
+}
+
+/// Settings that control how a [`ParticleEmitter`] spawns and simulates particles.
+///
+/// All of the fields are public, and the default values can be obtained via
+/// [`ParticleSettings::default`], so you can override just the settings you need:
+///
+/// ```
+/// # use firecore_tetra::graphics::particles::{Curve, EmitterShape, ParticleSettings};
+/// # use firecore_tetra::graphics::Color;
+/// # use firecore_tetra::math::Vec2;
+/// let settings = ParticleSettings {
+///     shape: EmitterShape::Circle { radius: 8.0 },
+///     rate: 100.0,
+///     gravity: Vec2::new(0.0, 200.0),
+///     color: Curve::linear(Color::WHITE, Color::WHITE.with_alpha(0.0)),
+///     ..ParticleSettings::default()
+/// };
+/// ```
+///
+/// Fields that are expressed as a [`Range`] are randomized per particle, within that range.
+/// To use a fixed value, pass an empty range (e.g. `1.0..1.0`).
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParticleSettings {
+    /// The area that particles are spawned within. Defaults to [`EmitterShape::Point`].
+    pub shape: EmitterShape,
+
+    /// The number of particles spawned per second, while the emitter is active. Defaults to `10.0`.
+    pub rate: f32,
+
+    /// The maximum number of particles that can be alive at once. Defaults to `1000`.
+    pub max_particles: usize,
+
+    /// The lifetime of each particle, in seconds. Defaults to `1.0..1.0`.
+    pub lifetime: Range<f32>,
+
+    /// The initial speed of each particle, in pixels per second. Defaults to `50.0..50.0`.
+    pub speed: Range<f32>,
+
+    /// The direction that particles travel in, in radians. Defaults to `0.0` (to the right).
+    pub direction: f32,
+
+    /// The total angle (in radians) that particle directions are randomized within,
+    /// centered on [`direction`](Self::direction). Defaults to `2π` (all directions).
+    pub spread: f32,
+
+    /// The acceleration applied to every particle, in pixels per second squared.
+    /// Defaults to `(0.0, 0.0)`.
+    pub gravity: Vec2<f32>,
+
+    /// How quickly particles slow down, as a fraction of their velocity per second.
+    /// Defaults to `0.0`.
+    pub drag: f32,
+
+    /// The initial rotation of each particle, in radians. Defaults to `0.0..0.0`.
+    pub rotation: Range<f32>,
+
+    /// The rotation speed of each particle, in radians per second. Defaults to `0.0..0.0`.
+    pub angular_velocity: Range<f32>,
+
+    /// The scale of each particle over its lifetime. Defaults to a constant `1.0`.
+    pub size: Curve<f32>,
+
+    /// The color of each particle over its lifetime. Defaults to a constant [`Color::WHITE`].
+    pub color: Curve<Color>,
+
+    /// The regions of the texture that particles can be drawn from. Each particle
+    /// will pick one at random when it is spawned.
+    ///
+    /// If this is empty (the default), the entire texture will be used.
+    pub regions: Vec<Rectangle>,
+}
+
+impl Default for ParticleSettings {
+    fn default() -> ParticleSettings {
+        ParticleSettings {
+            shape: EmitterShape::Point,
+            rate: 10.0,
+            max_particles: 1000,
+            lifetime: 1.0..1.0,
+            speed: 50.0..50.0,
+            direction: 0.0,
+            spread: PI * 2.0,
+            gravity: Vec2::zero(),
+            drag: 0.0,
+            rotation: 0.0..0.0,
+            angular_velocity: 0.0..0.0,
+            size: Curve::new(1.0),
+            color: Curve::new(Color::WHITE),
+            regions: Vec::new(),
+        }
+    }
+}
+
+/// A single live particle.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Particle {
+    /// The position of the particle.
+    pub position: Vec2<f32>,
+
+    /// The velocity of the particle, in pixels per second.
+    pub velocity: Vec2<f32>,
+
+    /// The rotation of the particle, in radians.
+    pub rotation: f32,
+
+    /// The rotation speed of the particle, in radians per second.
+    pub angular_velocity: f32,
+
+    /// How long the particle has been alive for, in seconds.
+    pub age: f32,
+
+    /// How long the particle will live for, in seconds.
+    pub lifetime: f32,
+
+    /// The index of the texture region that the particle is drawn from.
+    pub region: usize,
+}
+
+impl Particle {
+    /// Returns how far through its lifetime the particle is, between 0.0 and 1.0.
+    pub fn progress(&self) -> f32 {
+        if self.lifetime <= 0.0 {
+            1.0
+        } else {
+            (self.age / self.lifetime).min(1.0)
+        }
+    }
+}
+
+/// A source of particles.
+///
+/// Emitters spawn particles at a steady [rate](ParticleSettings::rate) while they are
+/// active, and can also spawn bursts of particles via [`emit`](Self::emit). Particles are
+/// spawned at the emitter's current position, but are not affected by the emitter moving
+/// afterwards.
+///
+/// # Performance
+///
+/// Particles are drawn via the sprite batcher, so drawing an emitter will only cause a
+/// draw call if the texture differs from the previous thing that was drawn (or if the
+/// batch is full).
+///
+/// # Examples
+///
+/// The [`particles`](https://github.com/17cupsofcoffee/tetra/blob/main/examples/particles.rs)
+/// example demonstrates how to create emitters with different settings.
+#[derive(Debug, Clone)]
+pub struct ParticleEmitter {
+    texture: Texture,
+    simulation: Simulation,
+}
+
+/// The simulation state of a [`ParticleEmitter`], which is kept separate from the
+/// texture so that it can be run without one.
+#[derive(Debug, Clone)]
+struct Simulation {
+    settings: ParticleSettings,
+    position: Vec2<f32>,
+    particles: Vec<Particle>,
+    rng: Rng,
+    spawn_timer: f32,
+    active: bool,
+}
+
+impl ParticleEmitter {
+    /// Creates a new emitter, with a fixed default seed.
+    pub fn new(texture: Texture, settings: ParticleSettings) -> ParticleEmitter {
+        ParticleEmitter::with_seed(texture, settings, 0)
+    }
+
+    /// Creates a new emitter, using the given seed for its random number generator.
+    pub fn with_seed(texture: Texture, settings: ParticleSettings, seed: u64) -> ParticleEmitter {
+        ParticleEmitter {
+            texture,
+            simulation: Simulation::new(settings, seed),
+        }
+    }
+
+    /// Draws the emitter's particles to the screen (or to a canvas, if one is enabled).
+    pub fn draw(&self, ctx: &mut Context) {
+        let settings = &self.simulation.settings;
+
+        let texture_region = Rectangle::new(
+            0.0,
+            0.0,
+            self.texture.width() as f32,
+            self.texture.height() as f32,
+        );
+
+        for particle in &self.simulation.particles {
+            let region = settings
+                .regions
+                .get(particle.region)
+                .copied()
+                .unwrap_or(texture_region);
+
+            let progress = particle.progress();
+            let size = settings.size.sample(progress);
+
+            self.texture.draw_region(
+                ctx,
+                region,
+                DrawParams::new()
+                    .position(particle.position)
+                    .origin(Vec2::new(region.width / 2.0, region.height / 2.0))
+                    .rotation(particle.rotation)
+                    .scale(Vec2::broadcast(size))
+                    .color(settings.color.sample(progress)),
+            );
+        }
+    }
+
+    /// Advances the simulation.
+    ///
+    /// This method uses the current [delta time](crate::time::get_delta_time)
+    /// to calculate how much time has passed.
+    pub fn update(&mut self, ctx: &Context) {
+        self.update_by(time::get_delta_time(ctx));
+    }
+
+    /// Advances the simulation by a specified amount.
+    pub fn update_by(&mut self, duration: Duration) {
+        self.simulation.update_by(duration);
+    }
+
+    /// Immediately spawns the given number of particles.
+    ///
+    /// This works even if the emitter is not active, so it can be used for one-off
+    /// bursts (e.g. explosions). No more than [`max_particles`](ParticleSettings::max_particles)
+    /// will be alive at once.
+    pub fn emit(&mut self, count: usize) {
+        self.simulation.emit(count);
+    }
+
+    /// Removes all of the emitter's particles.
+    pub fn clear(&mut self) {
+        self.simulation.clear();
+    }
+
+    /// Returns whether the emitter is spawning particles over time.
+    pub fn is_active(&self) -> bool {
+        self.simulation.active
+    }
+
+    /// Sets whether the emitter should spawn particles over time.
+    ///
+    /// Existing particles will continue to be simulated while the emitter is inactive.
+    pub fn set_active(&mut self, active: bool) {
+        self.simulation.active = active;
+    }
+
+    /// Reseeds the emitter's random number generator.
+    ///
+    /// This does not affect particles that have already been spawned.
+    pub fn set_seed(&mut self, seed: u64) {
+        self.simulation.rng = Rng::new(seed);
+    }
+
+    /// Returns the position that particles are spawned relative to.
+    pub fn position(&self) -> Vec2<f32> {
+        self.simulation.position
+    }
+
+    /// Sets the position that particles are spawned relative to.
+    pub fn set_position(&mut self, position: Vec2<f32>) {
+        self.simulation.position = position;
+    }
+
+    /// Returns a reference to the texture that the particles are drawn from.
+    pub fn texture(&self) -> &Texture {
+        &self.texture
+    }
+
+    /// Sets the texture that the particles are drawn from.
+    pub fn set_texture(&mut self, texture: Texture) {
+        self.texture = texture;
+    }
+
+    /// Returns a reference to the emitter's settings.
+    pub fn settings(&self) -> &ParticleSettings {
+        &self.simulation.settings
+    }
+
+    /// Returns a mutable reference to the emitter's settings.
+    ///
+    /// Changes will only affect particles spawned after the settings are modified,
+    /// with the exception of the size/color curves and gravity/drag, which apply to all
+    /// live particles.
+    pub fn settings_mut(&mut self) -> &mut ParticleSettings {
+        &mut self.simulation.settings
+    }
+
+    /// Returns the particles that are currently alive.
+    pub fn particles(&self) -> &[Particle] {
+        &self.simulation.particles
+    }
+}
+
+impl Simulation {
+    fn new(settings: ParticleSettings, seed: u64) -> Simulation {
+        Simulation {
+            settings,
+            position: Vec2::zero(),
+            particles: Vec::new(),
+            rng: Rng::new(seed),
+            spawn_timer: 0.0,
+            active: true,
+        }
+    }
+
+    fn update_by(&mut self, duration: Duration) {
+        let dt = duration.as_secs_f32();
+
+        if self.active && self.settings.rate > 0.0 {
+            self.spawn_timer += dt * self.settings.rate;
+
+            let count = self.spawn_timer.floor();
+            self.spawn_timer -= count;
+
+            self.emit(count as usize);
+        }
+
+        let gravity = self.settings.gravity;
+        let damping = (1.0 - self.settings.drag * dt).max(0.0);
+
+        for particle in &mut self.particles {
+            particle.velocity += gravity * dt;
+            particle.velocity *= damping;
+            particle.position += particle.velocity * dt;
+            particle.rotation += particle.angular_velocity * dt;
+            particle.age += dt;
+        }
+
+        self.particles.retain(|p| p.age < p.lifetime);
+    }
+
+    fn emit(&mut self, count: usize) {
+        let available = self
+            .settings
+            .max_particles
+            .saturating_sub(self.particles.len());
+
+        for _ in 0..count.min(available) {
+            let particle = self.spawn_particle();
+            self.particles.push(particle);
+        }
+    }
+
+    fn spawn_particle(&mut self) -> Particle {
+        let settings = &self.settings;
+        let rng = &mut self.rng;
+
+        let offset = settings.shape.sample(rng);
+        let angle = settings.direction + (rng.next_f32() - 0.5) * settings.spread;
+        let speed = rng.range(&settings.speed);
+
+        let region = if settings.regions.is_empty() {
+            0
+        } else {
+            rng.below(settings.regions.len())
+        };
+
+        Particle {
+            position: self.position + offset,
+            velocity: Vec2::new(angle.cos(), angle.sin()) * speed,
+            rotation: rng.range(&settings.rotation),
+            angular_velocity: rng.range(&settings.angular_velocity),
+            age: 0.0,
+            lifetime: rng.range(&settings.lifetime),
+            region,
+        }
+    }
+
+    fn clear(&mut self) {
+        self.particles.clear();
+        self.spawn_timer = 0.0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn shapes_stay_in_bounds() {
+        let mut rng = Rng::new(0);
+
+        let rect = EmitterShape::Rectangle {
+            width: 10.0,
+            height: 4.0,
+        };
+
+        let circle = EmitterShape::Circle { radius: 5.0 };
+
+        let line = EmitterShape::Polyline(vec![Vec2::new(0.0, 0.0), Vec2::new(10.0, 0.0)]);
+
+        for _ in 0..1000 {
+            let point = rect.sample(&mut rng);
+            assert!(point.x.abs() <= 5.0 && point.y.abs() <= 2.0);
+
+            let point = circle.sample(&mut rng);
+            assert!(point.magnitude() <= 5.0);
+
+            let point = line.sample(&mut rng);
+            assert!(point.y == 0.0 && point.x >= 0.0 && point.x <= 10.0);
+        }
+
+        assert_eq!(EmitterShape::Point.sample(&mut rng), Vec2::zero());
+    }
+
+    #[test]
+    fn same_seed_is_deterministic() {
+        let settings = ParticleSettings {
+            shape: EmitterShape::Circle { radius: 10.0 },
+            rate: 50.0,
+            lifetime: 0.5..1.5,
+            speed: 20.0..80.0,
+            gravity: Vec2::new(0.0, 100.0),
+            drag: 0.5,
+            angular_velocity: -1.0..1.0,
+            regions: vec![Rectangle::new(0.0, 0.0, 8.0, 8.0); 3],
+            ..ParticleSettings::default()
+        };
+
+        let mut a = Simulation::new(settings.clone(), 1234);
+        let mut b = Simulation::new(settings.clone(), 1234);
+        let mut c = Simulation::new(settings, 5678);
+
+        for simulation in [&mut a, &mut b, &mut c].iter_mut() {
+            simulation.emit(5);
+
+            for _ in 0..30 {
+                simulation.update_by(Duration::from_secs_f32(1.0 / 60.0));
+            }
+        }
+
+        assert!(!a.particles.is_empty());
+        assert_eq!(a.particles, b.particles);
+        assert_ne!(a.particles, c.particles);
+    }
+}
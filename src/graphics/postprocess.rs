@@ -0,0 +1,324 @@
+//! Functions and types relating to post-processing effects.
+
+use std::fmt::{self, Debug, Formatter};
+
+use crate::context::Context;
+use crate::error::Result;
+use crate::graphics::{
+    self, ActiveCanvas, ActiveShader, BlendAlphaMode, BlendMode, Canvas, DrawParams, Shader,
+    ShaderType, UniformValue,
+};
+use crate::math::Mat4;
+use crate::window;
+
+struct Pass {
+    shader: Shader,
+    enabled: bool,
+    uniforms: Vec<PassUniform>,
+}
+
+struct PassUniform {
+    name: String,
+    value: Box<dyn UniformValue>,
+    accepts: fn(ShaderType) -> bool,
+}
+
+impl Debug for Pass {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Pass")
+            .field("shader", &self.shader)
+            .field("enabled", &self.enabled)
+            .field(
+                "uniforms",
+                &self.uniforms.iter().map(|u| &u.name).collect::<Vec<_>>(),
+            )
+            .finish()
+    }
+}
+
+/// A chain of full-screen shader effects.
+///
+/// A `PostProcess` owns a canvas that your scene should be rendered to, along with a
+/// pair of intermediate canvases of the same size. When the `PostProcess` is drawn,
+/// each enabled pass's shader is applied in order, with the output of each pass being
+/// fed into the next (a technique sometimes referred to as 'ping-ponging'). The final
+/// pass is drawn directly to whatever was the active canvas at the time (usually the
+/// window, or a [`ScreenScaler`](super::scaling::ScreenScaler)'s canvas).
+///
+/// Each pass is a [`Shader`] - usually only the fragment shader will need to be customized,
+/// via [`Shader::from_fragment_file`]. The previous pass's output is bound as the shader's
+/// `u_texture` sampler, as with any other texture drawn via Tetra's renderer.
+///
+/// If the scene canvas is multisampled, it will be resolved automatically when you switch
+/// away from it, before the passes are run.
+///
+/// # Performance
+///
+/// Each pass is a separate draw call, and the intermediate canvases take up
+/// as much video RAM as the scene canvas. Disabled passes are skipped entirely.
+///
+/// # Examples
+///
+/// The [`postprocess`](https://github.com/17cupsofcoffee/tetra/blob/main/examples/postprocess.rs)
+/// example demonstrates how to chain multiple effects together.
+#[derive(Debug)]
+pub struct PostProcess {
+    canvas: Canvas,
+    buffers: [Canvas; 2],
+    samples: u8,
+    passes: Vec<Pass>,
+}
+
+impl PostProcess {
+    /// Creates a new post-processing chain, with canvases of the given size.
+    ///
+    /// If `samples` is greater than zero, the scene canvas will be multisampled.
+    ///
+    /// # Errors
+    ///
+    /// * [`TetraError::PlatformError`](crate::TetraError::PlatformError) will be returned if the underlying
+    /// graphics API encounters an error.
+    pub fn new(ctx: &mut Context, width: i32, height: i32, samples: u8) -> Result<PostProcess> {
+        let (canvas, buffers) = create_canvases(ctx, width, height, samples)?;
+
+        Ok(PostProcess {
+            canvas,
+            buffers,
+            samples,
+            passes: Vec::new(),
+        })
+    }
+
+    /// Creates a new post-processing chain, with canvases the same size as the window.
+    ///
+    /// If `samples` is greater than zero, the scene canvas will be multisampled.
+    ///
+    /// # Errors
+    ///
+    /// * [`TetraError::PlatformError`](crate::TetraError::PlatformError) will be returned if the underlying
+    /// graphics API encounters an error.
+    pub fn with_window_size(ctx: &mut Context, samples: u8) -> Result<PostProcess> {
+        let (width, height) = window::get_size(ctx);
+        PostProcess::new(ctx, width, height, samples)
+    }
+
+    /// Resizes the canvases used by the chain.
+    ///
+    /// This should be called when the size of the output changes (e.g. when the window
+    /// is resized). If the size has not changed, this does nothing - otherwise, the contents
+    /// of the canvases will be lost.
+    ///
+    /// # Errors
+    ///
+    /// * [`TetraError::PlatformError`](crate::TetraError::PlatformError) will be returned if the underlying
+    /// graphics API encounters an error.
+    pub fn set_size(&mut self, ctx: &mut Context, width: i32, height: i32) -> Result {
+        if (width, height) != self.canvas.size() {
+            let (canvas, buffers) = create_canvases(ctx, width, height, self.samples)?;
+
+            self.canvas = canvas;
+            self.buffers = buffers;
+        }
+
+        Ok(())
+    }
+
+    /// Returns the canvas that the scene should be rendered to.
+    pub fn canvas(&self) -> &Canvas {
+        &self.canvas
+    }
+
+    /// Adds a pass to the end of the chain, returning its index.
+    pub fn add_pass(&mut self, shader: Shader) -> usize {
+        self.passes.push(Pass {
+            shader,
+            enabled: true,
+            uniforms: Vec::new(),
+        });
+
+        self.passes.len() - 1
+    }
+
+    /// Inserts a pass at the given index, shifting all passes after it along by one.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is greater than the number of passes.
+    pub fn insert_pass(&mut self, index: usize, shader: Shader) {
+        self.passes.insert(
+            index,
+            Pass {
+                shader,
+                enabled: true,
+                uniforms: Vec::new(),
+            },
+        );
+    }
+
+    /// Removes the pass at the given index, returning its shader.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds.
+    pub fn remove_pass(&mut self, index: usize) -> Shader {
+        self.passes.remove(index).shader
+    }
+
+    /// Removes all passes from the chain.
+    pub fn clear_passes(&mut self) {
+        self.passes.clear();
+    }
+
+    /// Returns the number of passes in the chain, including disabled passes.
+    pub fn pass_count(&self) -> usize {
+        self.passes.len()
+    }
+
+    /// Returns a reference to the shader used by the pass at the given index.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds.
+    pub fn shader(&self, index: usize) -> &Shader {
+        &self.passes[index].shader
+    }
+
+    /// Returns whether the pass at the given index is enabled.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds.
+    pub fn is_pass_enabled(&self, index: usize) -> bool {
+        self.passes[index].enabled
+    }
+
+    /// Sets whether the pass at the given index is enabled.
+    ///
+    /// Disabled passes will be skipped when the chain is drawn.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds.
+    pub fn set_pass_enabled(&mut self, index: usize, enabled: bool) {
+        self.passes[index].enabled = enabled;
+    }
+
+    /// Sets the value of a uniform for the pass at the given index.
+    ///
+    /// Unlike [`Shader::set_uniform`], the value is stored and applied each time the
+    /// pass runs, so the same shader can be used for multiple passes with different
+    /// parameters.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds.
+    pub fn set_uniform<V>(&mut self, index: usize, name: &str, value: V)
+    where
+        V: UniformValue + 'static,
+    {
+        let uniform = PassUniform {
+            name: name.to_owned(),
+            value: Box::new(value),
+            accepts: V::accepts,
+        };
+
+        let uniforms = &mut self.passes[index].uniforms;
+
+        match uniforms.iter_mut().find(|u| u.name == name) {
+            Some(existing) => *existing = uniform,
+            None => uniforms.push(uniform),
+        }
+    }
+
+    /// Runs the chain, and draws the result to the screen (or to a canvas, if one is enabled).
+    ///
+    /// The intermediate passes are drawn without any transformation or blending, but the
+    /// final pass will respect the current transform matrix and blend mode, as well
+    /// as the provided `params`.
+    ///
+    /// If there are no enabled passes, the scene canvas will be drawn as-is.
+    ///
+    /// This should not be called while the [scene canvas](Self::canvas) is the active canvas.
+    pub fn draw<P>(&self, ctx: &mut Context, params: P)
+    where
+        P: Into<DrawParams>,
+    {
+        let params = params.into();
+
+        let output = match &ctx.graphics.canvas {
+            ActiveCanvas::Window => ActiveCanvas::Window,
+            ActiveCanvas::User(canvas) => ActiveCanvas::User(canvas.clone()),
+        };
+
+        let previous_shader = match &ctx.graphics.shader {
+            ActiveShader::Default => ActiveShader::Default,
+            ActiveShader::User(shader) => ActiveShader::User(shader.clone()),
+        };
+
+        let previous_blend_mode = ctx.graphics.blend_mode;
+        let previous_transform = ctx.graphics.transform_matrix;
+
+        let passes: Vec<&Pass> = self.passes.iter().filter(|p| p.enabled).collect();
+
+        let mut input = &self.canvas;
+
+        if let Some((last, intermediate)) = passes.split_last() {
+            // The intermediate canvases are cleared to transparent, so drawing with
+            // premultiplied blending will copy the pass's output exactly.
+            graphics::set_blend_mode(ctx, BlendMode::Alpha(BlendAlphaMode::Premultiplied));
+            graphics::set_transform_matrix(ctx, Mat4::identity());
+
+            for (i, pass) in intermediate.iter().enumerate() {
+                let target = &self.buffers[i % 2];
+
+                graphics::set_canvas(ctx, target);
+                graphics::clear(ctx, graphics::Color::rgba(0.0, 0.0, 0.0, 0.0));
+
+                apply_pass(ctx, pass);
+                input.draw(ctx, DrawParams::new());
+
+                input = target;
+            }
+
+            graphics::set_canvas_ex(ctx, output);
+            graphics::set_blend_mode(ctx, previous_blend_mode);
+            graphics::set_transform_matrix(ctx, previous_transform);
+
+            apply_pass(ctx, last);
+            input.draw(ctx, params);
+
+            // The pass's uniforms may be overwritten by the next pass that uses
+            // the same shader, so the draw needs to happen now.
+            graphics::flush(ctx);
+            graphics::set_shader_ex(ctx, previous_shader);
+        } else {
+            input.draw(ctx, params);
+        }
+    }
+}
+
+fn apply_pass(ctx: &mut Context, pass: &Pass) {
+    graphics::set_shader(ctx, &pass.shader);
+
+    // This goes through the same path as `Shader::set_uniform`, so that built-in
+    // uniforms set by the pass aren't overwritten when the pass is drawn.
+    for uniform in &pass.uniforms {
+        pass.shader
+            .set_uniform_dyn(ctx, &uniform.name, &*uniform.value, uniform.accepts);
+    }
+}
+
+fn create_canvases(
+    ctx: &mut Context,
+    width: i32,
+    height: i32,
+    samples: u8,
+) -> Result<(Canvas, [Canvas; 2])> {
+    let canvas = Canvas::builder(width, height).samples(samples).build(ctx)?;
+    let buffers = [
+        Canvas::new(ctx, width, height)?,
+        Canvas::new(ctx, width, height)?,
+    ];
+
+    Ok((canvas, buffers))
+}
@@ -152,6 +152,39 @@ where
         }
     }
 
+    /// Returns the area where `self` and `other` overlap, or `None` if they
+    /// do not intersect.
+    pub fn intersection(&self, other: &Rectangle<T>) -> Option<Rectangle<T>>
+    where
+        T: Add<Output = T> + Sub<Output = T> + PartialOrd,
+    {
+        if !self.intersects(other) {
+            return None;
+        }
+
+        let x = if self.x > other.x { self.x } else { other.x };
+        let y = if self.y > other.y { self.y } else { other.y };
+
+        let right = if self.right() < other.right() {
+            self.right()
+        } else {
+            other.right()
+        };
+
+        let bottom = if self.bottom() < other.bottom() {
+            self.bottom()
+        } else {
+            other.bottom()
+        };
+
+        Some(Rectangle {
+            x,
+            y,
+            width: right - x,
+            height: bottom - y,
+        })
+    }
+
     /// Returns the X co-ordinate of the left side of the rectangle.
     ///
     /// You can also obtain this via the `x` field - this method is provided for
@@ -326,4 +359,17 @@ mod tests {
             Rectangle::new(8.0, 0.0, 40.0, 72.0),
         )
     }
+
+    #[test]
+    fn intersection() {
+        assert_eq!(
+            Rectangle::new(16, 8, 32, 64).intersection(&Rectangle::new(8, 0, 32, 16)),
+            Some(Rectangle::new(16, 8, 24, 8)),
+        );
+
+        assert_eq!(
+            Rectangle::new(0, 0, 8, 8).intersection(&Rectangle::new(8, 0, 8, 8)),
+            None,
+        );
+    }
 }
@@ -0,0 +1,64 @@
+use std::ops::Range;
+
+/// A small, fast pseudo-random number generator (SplitMix64).
+///
+/// This is not suitable for cryptographic purposes, but gives identical results
+/// across platforms for a given seed, which is what matters for deterministic
+/// simulations, such as particle effects and camera shake.
+#[derive(Debug, Clone)]
+pub(crate) struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub(crate) fn new(seed: u64) -> Rng {
+        Rng { state: seed }
+    }
+
+    pub(crate) fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Returns a value in the range `0.0..1.0`.
+    pub(crate) fn next_f32(&mut self) -> f32 {
+        // The top 24 bits fit exactly into an f32's mantissa.
+        (self.next_u64() >> 40) as f32 / (1u64 << 24) as f32
+    }
+
+    pub(crate) fn range(&mut self, range: &Range<f32>) -> f32 {
+        range.start + (range.end - range.start) * self.next_f32()
+    }
+
+    pub(crate) fn below(&mut self, max: usize) -> usize {
+        (self.next_u64() % max as u64) as usize
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rng_is_deterministic() {
+        let mut a = Rng::new(1234);
+        let mut b = Rng::new(1234);
+        let mut c = Rng::new(4321);
+
+        let a_values: Vec<u64> = (0..8).map(|_| a.next_u64()).collect();
+        let b_values: Vec<u64> = (0..8).map(|_| b.next_u64()).collect();
+        let c_values: Vec<u64> = (0..8).map(|_| c.next_u64()).collect();
+
+        assert_eq!(a_values, b_values);
+        assert_ne!(a_values, c_values);
+
+        for _ in 0..1000 {
+            let value = a.next_f32();
+            assert!((0.0..1.0).contains(&value));
+        }
+    }
+}
@@ -21,6 +21,7 @@ pub struct ScreenScaler {
     screen_rect: Rectangle,
     outer_width: i32,
     outer_height: i32,
+    subpixel_offset: Vec2<f32>,
 }
 
 impl ScreenScaler {
@@ -44,6 +45,7 @@ impl ScreenScaler {
             screen_rect,
             outer_width,
             outer_height,
+            subpixel_offset: Vec2::zero(),
         })
     }
 
@@ -68,7 +70,18 @@ impl ScreenScaler {
     }
 
     /// Draws the scaled image to the screen.
+    ///
+    /// If a [subpixel offset](Self::set_subpixel_offset) has been set, the image will be
+    /// shifted by that amount.
     pub fn draw(&self, ctx: &mut Context) {
+        let (width, height) = self.canvas.size();
+
+        // Shifting the texture co-ordinates rather than the quad means that the image
+        // stays within the screen rect - the pixels at the edges will be stretched
+        // slightly to fill the gap.
+        let u = -self.subpixel_offset.x / width as f32;
+        let v = -self.subpixel_offset.y / height as f32;
+
         graphics::set_texture(ctx, &self.canvas.texture);
 
         graphics::push_quad(
@@ -77,14 +90,35 @@ impl ScreenScaler {
             self.screen_rect.y,
             self.screen_rect.x + self.screen_rect.width,
             self.screen_rect.y + self.screen_rect.height,
-            0.0,
-            0.0,
-            1.0,
-            1.0,
+            u,
+            v,
+            1.0 + u,
+            1.0 + v,
             &DrawParams::new(),
         );
     }
 
+    /// Returns the offset that will be applied when drawing the scaled image, in
+    /// unscaled pixels.
+    pub fn subpixel_offset(&self) -> Vec2<f32> {
+        self.subpixel_offset
+    }
+
+    /// Sets an offset that will be applied when drawing the scaled image, in unscaled pixels.
+    ///
+    /// This is designed to be used alongside [`Camera::pixel_snapping`](crate::graphics::Camera::pixel_snapping).
+    /// Snapping the camera to the pixel grid of the canvas stops pixel art from shimmering,
+    /// and then passing the remaining [`Camera::subpixel_offset`](crate::graphics::Camera::subpixel_offset)
+    /// to this method allows the view to scroll smoothly at the screen's resolution.
+    pub fn set_subpixel_offset(&mut self, offset: Vec2<f32>) {
+        self.subpixel_offset = offset;
+    }
+
+    /// Removes the offset that is applied when drawing the scaled image.
+    pub fn reset_subpixel_offset(&mut self) {
+        self.subpixel_offset = Vec2::zero();
+    }
+
     /// Updates the scaler's outer size (i.e. the size of the box that the screen will be scaled to
     /// fit within).
     pub fn set_outer_size(&mut self, outer_width: i32, outer_height: i32) {
@@ -1,19 +1,24 @@
 //! Functions and types relating to shader programs.
 
+mod preprocessor;
+
 use std::cell::{Cell, RefCell};
-use std::path::Path;
+use std::fmt::{self, Display, Formatter};
+use std::marker::PhantomData;
+use std::path::{Path, PathBuf};
 use std::rc::Rc;
 use std::slice;
 
-use hashbrown::HashMap;
+use hashbrown::{HashMap, HashSet};
 
-use crate::error::Result;
-use crate::fs;
+use crate::error::{Result, TetraError};
 use crate::graphics::{Color, Texture};
 use crate::math::{Mat2, Mat3, Mat4, Vec2, Vec3, Vec4};
-use crate::platform::{GraphicsDevice, RawShader};
+use crate::platform::{GraphicsDevice, RawShader, UniformLocation};
 use crate::context::Context;
 
+pub(crate) use preprocessor::ShaderSource;
+
 /// The default vertex shader.
 ///
 /// The source code for this shader is available in [`src/resources/shader.vert`](https://github.com/17cupsofcoffee/tetra/blob/main/src/resources/shader.vert).
@@ -24,6 +29,14 @@ pub const DEFAULT_VERTEX_SHADER: &str = include_str!("../resources/shader.vert")
 /// The source code for this shader is available in [`src/resources/shader.vert`](https://github.com/17cupsofcoffee/tetra/blob/main/src/resources/shader.frag).
 pub const DEFAULT_FRAGMENT_SHADER: &str = include_str!("../resources/shader.frag");
 
+/// The default vertex shader for meshes that are drawn with an
+/// [`InstanceBuffer`](super::mesh::InstanceBuffer).
+///
+/// This applies the attributes of each [`Instance`](super::mesh::Instance) to the mesh.
+///
+/// The source code for this shader is available in [`src/resources/instanced.vert`](https://github.com/17cupsofcoffee/tetra/blob/main/src/resources/instanced.vert).
+pub const DEFAULT_INSTANCED_VERTEX_SHADER: &str = include_str!("../resources/instanced.vert");
+
 #[derive(Debug)]
 pub(crate) struct Sampler {
     pub(crate) texture: Texture,
@@ -35,6 +48,71 @@ pub(crate) struct ShaderSharedData {
     pub(crate) handle: RawShader,
     pub(crate) samplers: RefCell<HashMap<String, Sampler>>,
     pub(crate) next_unit: Cell<u32>,
+    pub(crate) uniforms: Vec<ShaderVariable>,
+    pub(crate) attributes: Vec<ShaderVariable>,
+    // Invalid uniforms are only reported in debug builds.
+    #[cfg_attr(not(debug_assertions), allow(dead_code))]
+    pub(crate) warned: RefCell<HashSet<String>>,
+    pub(crate) builtins: BuiltinUniforms,
+}
+
+/// The built-in uniforms, if they are declared by the shader.
+#[derive(Debug)]
+pub(crate) struct BuiltinUniforms {
+    time: BuiltinUniform,
+    delta_time: BuiltinUniform,
+    resolution: BuiltinUniform,
+    frame: BuiltinUniform,
+}
+
+impl BuiltinUniforms {
+    /// Stops Tetra from setting a built-in uniform, as the user has set it themselves.
+    fn mark_user_set(&self, name: &str) {
+        let name = split_array_index(name).map_or(name, |(base, _)| base);
+
+        let builtin = match name {
+            "u_time" => &self.time,
+            "u_delta_time" => &self.delta_time,
+            "u_resolution" => &self.resolution,
+            "u_frame" => &self.frame,
+            _ => return,
+        };
+
+        builtin.user_set.set(true);
+    }
+}
+
+#[derive(Debug)]
+struct BuiltinUniform {
+    location: Option<UniformLocation>,
+    user_set: Cell<bool>,
+}
+
+impl BuiltinUniform {
+    fn new(location: Option<UniformLocation>) -> BuiltinUniform {
+        BuiltinUniform {
+            location,
+            user_set: Cell::new(false),
+        }
+    }
+
+    /// Returns the location of the uniform, if Tetra should set its value.
+    fn location(&self) -> Option<&UniformLocation> {
+        if self.user_set.get() {
+            None
+        } else {
+            self.location.as_ref()
+        }
+    }
+}
+
+/// The per-frame values for the built-in uniforms.
+#[derive(Debug, Copy, Clone)]
+pub(crate) struct FrameUniforms {
+    pub(crate) time: f32,
+    pub(crate) delta_time: f32,
+    pub(crate) resolution: Vec2<f32>,
+    pub(crate) frame: i32,
 }
 
 impl PartialEq for ShaderSharedData {
@@ -51,14 +129,17 @@ impl PartialEq for ShaderSharedData {
 ///
 /// ## Vertex Shaders
 ///
-/// Vertex shaders take in data via three attributes:
+/// Vertex shaders take in data via four attributes:
 ///
 /// * `a_position` - A `vec2` representing the position of the vertex in world space.
 /// * `a_uv` - A `vec2` representing the texture co-ordinates that are associated with the vertex.
 /// * `a_color` - A `vec4` representing the color of the vertex. This will be multiplied by
 ///   `u_diffuse` and the color sampled from `u_texture` (see 'Uniforms' below).
+/// * `a_depth` - A `float` representing the depth of the vertex. This only needs to be used
+///   if you are using [depth testing](super::set_depth_state).
 ///
-/// Position data should be output as a `vec4` to the built-in `gl_Position` variable.
+/// Position data should be output as a `vec4` to the built-in `gl_Position` variable. The
+/// depth should be used as the Z component of the position, before the projection is applied.
 ///
 /// ## Fragment Shaders
 ///
@@ -75,13 +156,54 @@ impl PartialEq for ShaderSharedData {
 ///   pass through the [`DrawParams::color`](super::DrawParams::color) for a [`Mesh`](super::mesh::Mesh), and will
 ///   otherwise be set to [`Color::WHITE`].
 ///
+/// The following uniforms will also be set automatically, if your shader declares them:
+///
+/// * `u_time` - A `float` representing the number of seconds since the game loop started
+///   (see [`time::get_elapsed_time`](crate::time::get_elapsed_time)).
+/// * `u_delta_time` - A `float` representing the number of seconds since the last frame
+///   (see [`time::get_delta_time`](crate::time::get_delta_time)).
+/// * `u_resolution` - A `vec2` representing the size of the current render target
+///   (the window or a [`Canvas`](super::Canvas)), in physical pixels.
+/// * `u_frame` - An `int` representing the number of frames that have been completed
+///   since the game loop started (see [`time::get_frame_count`](crate::time::get_frame_count)).
+///
+/// If any of these are declared with a different type, they will be ignored. If you set
+/// one of them yourself (via [`set_uniform`](Shader::set_uniform) or a
+/// [`UniformHandle`]), Tetra will stop setting it for that shader, so that your
+/// value is not overwritten.
+///
 /// You can also set data into your own uniform variables via the [`set_uniform`](Shader::set_uniform) method.
+/// If you are setting the same uniform frequently, [`uniform_handle`](Shader::uniform_handle) can be
+/// used to look it up (and check its type) once, ahead of time.
+///
+/// The uniforms and attributes that are actually used by the compiled shader can be
+/// listed via [`uniforms`](Shader::uniforms) and [`attributes`](Shader::attributes).
+/// Note that the GLSL compiler will remove any variables that do not affect the output.
 ///
 /// Bear in mind that there is a hardware-defined limit on how many uniform locations can be used
 /// per shader. OpenGL 3.0 guarantees there will be at least 1024 of these locations available,
 /// which sounds like a lot - however, some types can use up multiple locations (e.g. a `vec2`
 /// uses 2, a `mat4` uses 16, an array of 4 `mat4`s uses 64, and so on).
 ///
+/// ## Preprocessor
+///
+/// Before a shader is compiled, Tetra will resolve any `#include "path"` directives in
+/// the source code, replacing them with the contents of the specified file. Paths are
+/// relative to the file containing the directive (or the current working directory,
+/// if the shader was loaded from a string). Includes can be nested, but cyclic
+/// includes will result in an error.
+///
+/// `#include`s are resolved before the GLSL compiler runs, so they will be processed even
+/// if they are inside an `#ifdef` block. However, included files can still use
+/// `#ifndef` guards to avoid being compiled more than once.
+///
+/// Extra `#define`s can be passed to the shader via [`ShaderBuilder::define`], which
+/// makes it possible to compile multiple variants of the same shader. These will be
+/// inserted after the `#version` directive.
+///
+/// If compilation fails, the line numbers in the error will be mapped back to the file
+/// that the line originated from.
+///
 /// # Performance
 ///
 /// Creating a `Shader` is a relatively expensive operation. If you can, store them in your
@@ -110,18 +232,23 @@ impl Shader {
     /// * [`TetraError::PlatformError`](crate::TetraError::PlatformError) will be returned if the
     /// underlying graphics API encounters an error.
     /// * [`TetraError::FailedToLoadAsset`](crate::TetraError::FailedToLoadAsset) will be returned
-    /// if the files could not be loaded.
+    /// if the files (or any files that they include) could not be loaded.
     /// * [`TetraError::InvalidShader`](crate::TetraError::InvalidShader) will be returned if the
-    /// shader could not be compiled.
+    /// shader could not be compiled, or if it contains an invalid or cyclic `#include`.
     pub fn new<P>(ctx: &mut Context, vertex_path: P, fragment_path: P) -> Result<Shader>
     where
         P: AsRef<Path>,
     {
-        Shader::with_device(
-            &mut ctx.device,
-            &fs::read_to_string(vertex_path)?,
-            &fs::read_to_string(fragment_path)?,
-        )
+        ShaderBuilder::new()
+            .vertex_file(vertex_path)
+            .fragment_file(fragment_path)
+            .build(ctx)
+    }
+
+    /// Creates a new shader builder, which can be used to create a shader with
+    /// extra `#define`s.
+    pub fn builder() -> ShaderBuilder {
+        ShaderBuilder::new()
     }
 
     /// Creates a new shader program from the given vertex shader file.
@@ -133,18 +260,14 @@ impl Shader {
     /// * [`TetraError::PlatformError`](crate::TetraError::PlatformError) will be returned if the
     /// underlying graphics API encounters an error.
     /// * [`TetraError::FailedToLoadAsset`](crate::TetraError::FailedToLoadAsset) will be returned
-    /// if the file could not be loaded.
+    /// if the file (or any files that it includes) could not be loaded.
     /// * [`TetraError::InvalidShader`](crate::TetraError::InvalidShader) will be returned if the
-    /// shader could not be compiled.
+    /// shader could not be compiled, or if it contains an invalid or cyclic `#include`.
     pub fn from_vertex_file<P>(ctx: &mut Context, path: P) -> Result<Shader>
     where
         P: AsRef<Path>,
     {
-        Shader::with_device(
-            &mut ctx.device,
-            &fs::read_to_string(path)?,
-            DEFAULT_FRAGMENT_SHADER,
-        )
+        ShaderBuilder::new().vertex_file(path).build(ctx)
     }
 
     /// Creates a new shader program from the given fragment shader file.
@@ -156,18 +279,14 @@ impl Shader {
     /// * [`TetraError::PlatformError`](crate::TetraError::PlatformError) will be returned if the
     /// underlying graphics API encounters an error.
     /// * [`TetraError::FailedToLoadAsset`](crate::TetraError::FailedToLoadAsset) will be returned
-    /// if the file could not be loaded.
+    /// if the file (or any files that it includes) could not be loaded.
     /// * [`TetraError::InvalidShader`](crate::TetraError::InvalidShader) will be returned if the
-    /// shader could not be compiled.
+    /// shader could not be compiled, or if it contains an invalid or cyclic `#include`.
     pub fn from_fragment_file<P>(ctx: &mut Context, path: P) -> Result<Shader>
     where
         P: AsRef<Path>,
     {
-        Shader::with_device(
-            &mut ctx.device,
-            DEFAULT_VERTEX_SHADER,
-            &fs::read_to_string(path)?,
-        )
+        ShaderBuilder::new().fragment_file(path).build(ctx)
     }
 
     /// Creates a new shader program from the given strings.
@@ -176,14 +295,19 @@ impl Shader {
     ///
     /// * [`TetraError::PlatformError`](crate::TetraError::PlatformError) will be returned if the
     /// underlying graphics API encounters an error.
+    /// * [`TetraError::FailedToLoadAsset`](crate::TetraError::FailedToLoadAsset) will be returned
+    /// if any included files could not be loaded.
     /// * [`TetraError::InvalidShader`](crate::TetraError::InvalidShader) will be returned if the
-    /// shader could not be compiled.
+    /// shader could not be compiled, or if it contains an invalid or cyclic `#include`.
     pub fn from_string(
         ctx: &mut Context,
         vertex_shader: &str,
         fragment_shader: &str,
     ) -> Result<Shader> {
-        Shader::with_device(&mut ctx.device, vertex_shader, fragment_shader)
+        ShaderBuilder::new()
+            .vertex_string(vertex_shader)
+            .fragment_string(fragment_shader)
+            .build(ctx)
     }
 
     /// Creates a new shader program from the given vertex shader string.
@@ -194,10 +318,12 @@ impl Shader {
     ///
     /// * [`TetraError::PlatformError`](crate::TetraError::PlatformError) will be returned if the
     /// underlying graphics API encounters an error.
+    /// * [`TetraError::FailedToLoadAsset`](crate::TetraError::FailedToLoadAsset) will be returned
+    /// if any included files could not be loaded.
     /// * [`TetraError::InvalidShader`](crate::TetraError::InvalidShader) will be returned if the
-    /// shader could not be compiled.
+    /// shader could not be compiled, or if it contains an invalid or cyclic `#include`.
     pub fn from_vertex_string<P>(ctx: &mut Context, shader: &str) -> Result<Shader> {
-        Shader::with_device(&mut ctx.device, shader, DEFAULT_FRAGMENT_SHADER)
+        ShaderBuilder::new().vertex_string(shader).build(ctx)
     }
 
     /// Creates a new shader program from the given fragment shader string.
@@ -208,44 +334,189 @@ impl Shader {
     ///
     /// * [`TetraError::PlatformError`](crate::TetraError::PlatformError) will be returned if the
     /// underlying graphics API encounters an error.
+    /// * [`TetraError::FailedToLoadAsset`](crate::TetraError::FailedToLoadAsset) will be returned
+    /// if any included files could not be loaded.
     /// * [`TetraError::InvalidShader`](crate::TetraError::InvalidShader) will be returned if the
-    /// shader could not be compiled.
+    /// shader could not be compiled, or if it contains an invalid or cyclic `#include`.
     pub fn from_fragment_string<P>(ctx: &mut Context, shader: &str) -> Result<Shader> {
-        Shader::with_device(&mut ctx.device, DEFAULT_VERTEX_SHADER, shader)
+        ShaderBuilder::new().fragment_string(shader).build(ctx)
     }
 
     pub(crate) fn with_device(
         device: &mut GraphicsDevice,
         vertex_shader: &str,
         fragment_shader: &str,
+    ) -> Result<Shader> {
+        Shader::with_sources(
+            device,
+            &ShaderSource::from_string(vertex_shader, "vertex shader", &[])?,
+            &ShaderSource::from_string(fragment_shader, "fragment shader", &[])?,
+        )
+    }
+
+    pub(crate) fn with_sources(
+        device: &mut GraphicsDevice,
+        vertex_shader: &ShaderSource,
+        fragment_shader: &ShaderSource,
     ) -> Result<Shader> {
         let handle = device.new_shader(vertex_shader, fragment_shader)?;
+        let uniforms = device.get_active_uniforms(&handle);
+        let attributes = device.get_active_attributes(&handle);
+
+        let builtin = |name: &str, ty: ShaderType| {
+            let location = if uniforms.iter().any(|u| u.name == name && u.ty == ty) {
+                device.get_uniform_location(&handle, name)
+            } else {
+                None
+            };
+
+            BuiltinUniform::new(location)
+        };
+
+        let builtins = BuiltinUniforms {
+            time: builtin("u_time", ShaderType::Float),
+            delta_time: builtin("u_delta_time", ShaderType::Float),
+            resolution: builtin("u_resolution", ShaderType::Vec2),
+            frame: builtin("u_frame", ShaderType::Int),
+        };
 
         Ok(Shader {
             data: Rc::new(ShaderSharedData {
                 handle,
                 samplers: RefCell::new(HashMap::new()),
                 next_unit: Cell::new(1),
+                uniforms,
+                attributes,
+                warned: RefCell::new(HashSet::new()),
+                builtins,
             }),
         })
     }
 
+    /// Returns the uniform variables that are active in the shader.
+    pub fn uniforms(&self) -> &[ShaderVariable] {
+        &self.data.uniforms
+    }
+
+    /// Returns the vertex attributes that are active in the shader.
+    pub fn attributes(&self) -> &[ShaderVariable] {
+        &self.data.attributes
+    }
+
+    /// Returns the active uniform variable with the given name, if it exists.
+    pub fn uniform(&self, name: &str) -> Option<&ShaderVariable> {
+        self.data.uniforms.iter().find(|u| u.name == name)
+    }
+
     /// Sets the value of the specifed uniform parameter.
     ///
     /// See the [`UniformValue`] trait's docs for a list of which types can be used as a uniform,
     /// and what their corresponding GLSL types are.
+    ///
+    /// If the uniform does not exist, or its type does not match the value, this
+    /// will do nothing. In debug builds, a warning will also be printed to stderr
+    /// (once per uniform). Use [`try_set_uniform`](Shader::try_set_uniform) if you
+    /// want to handle this case yourself.
     pub fn set_uniform<V>(&self, ctx: &mut Context, name: &str, value: V)
     where
         V: UniformValue,
     {
+        self.set_uniform_dyn(ctx, name, &value, V::accepts);
+    }
+
+    /// Sets a uniform from a type-erased value (e.g. one stored by a
+    /// [`PostProcess`](super::postprocess::PostProcess) pass), along with the
+    /// value type's [`accepts`](UniformValue::accepts) function.
+    // The type is only checked in debug builds.
+    #[cfg_attr(not(debug_assertions), allow(unused_variables))]
+    pub(crate) fn set_uniform_dyn(
+        &self,
+        ctx: &mut Context,
+        name: &str,
+        value: &dyn UniformValue,
+        accepts: fn(ShaderType) -> bool,
+    ) {
+        #[cfg(debug_assertions)]
+        {
+            if let Err(TetraError::InvalidUniform(reason)) = self.check_uniform_with(name, accepts)
+            {
+                if self.data.warned.borrow_mut().insert(name.to_owned()) {
+                    eprintln!("[tetra] {}", reason);
+                }
+            }
+        }
+
+        self.data.builtins.mark_user_set(name);
         value.set_uniform(ctx, self, name)
     }
 
+    /// Sets the value of the specifed uniform parameter, returning an error if
+    /// the uniform could not be set.
+    ///
+    /// # Errors
+    ///
+    /// * [`TetraError::InvalidUniform`](crate::TetraError::InvalidUniform) will be returned if
+    /// the uniform is not active in the shader, or if its type does not match the value.
+    pub fn try_set_uniform<V>(&self, ctx: &mut Context, name: &str, value: V) -> Result
+    where
+        V: UniformValue,
+    {
+        self.check_uniform::<V>(name)?;
+        self.data.builtins.mark_user_set(name);
+        value.set_uniform(ctx, self, name);
+
+        Ok(())
+    }
+
+    /// Looks up a uniform variable, returning a handle that can be used to set its value
+    /// without any further lookups.
+    ///
+    /// # Errors
+    ///
+    /// * [`TetraError::InvalidUniform`](crate::TetraError::InvalidUniform) will be returned if
+    /// the uniform is not active in the shader, or if its type does not match `V`.
+    pub fn uniform_handle<V>(&self, ctx: &mut Context, name: &str) -> Result<UniformHandle<V>>
+    where
+        V: UniformValue,
+    {
+        self.check_uniform::<V>(name)?;
+
+        let location = ctx.device.get_uniform_location(&self.data.handle, name);
+
+        Ok(UniformHandle {
+            shader: self.clone(),
+            name: name.to_owned(),
+            location,
+            _value: PhantomData,
+        })
+    }
+
+    fn check_uniform<V>(&self, name: &str) -> Result
+    where
+        V: UniformValue,
+    {
+        self.check_uniform_with(name, V::accepts)
+    }
+
+    fn check_uniform_with(&self, name: &str, accepts: fn(ShaderType) -> bool) -> Result {
+        let uniform = find_uniform(&self.data.uniforms, name)?;
+
+        if accepts(uniform.ty) {
+            Ok(())
+        } else {
+            Err(TetraError::InvalidUniform(format!(
+                "uniform '{}' has type '{}', which does not match the provided value",
+                name, uniform.ty
+            )))
+        }
+    }
+
     pub(crate) fn set_default_uniforms(
         &self,
         device: &mut GraphicsDevice,
         projection: Mat4<f32>,
         diffuse: Color,
+        frame: FrameUniforms,
     ) -> Result {
         let samplers = self.data.samplers.borrow();
 
@@ -269,84 +540,429 @@ impl Shader {
             &[diffuse.into()],
         );
 
+        let builtins = &self.data.builtins;
+
+        if let Some(location) = builtins.time.location() {
+            device.set_uniform_f32(&self.data.handle, Some(location), &[frame.time]);
+        }
+
+        if let Some(location) = builtins.delta_time.location() {
+            device.set_uniform_f32(&self.data.handle, Some(location), &[frame.delta_time]);
+        }
+
+        if let Some(location) = builtins.resolution.location() {
+            device.set_uniform_vec2(&self.data.handle, Some(location), &[frame.resolution]);
+        }
+
+        if let Some(location) = builtins.frame.location() {
+            device.set_uniform_i32(&self.data.handle, Some(location), &[frame.frame]);
+        }
+
         Ok(())
     }
 }
 
+#[derive(Debug, Clone)]
+enum ShaderInput {
+    Default,
+    File(PathBuf),
+    String(String),
+}
+
+impl ShaderInput {
+    fn load(
+        &self,
+        name: &str,
+        default: &str,
+        defines: &[(String, String)],
+    ) -> Result<ShaderSource> {
+        match self {
+            ShaderInput::Default => ShaderSource::from_string(default, name, defines),
+            ShaderInput::File(path) => ShaderSource::from_file(path, defines),
+            ShaderInput::String(source) => ShaderSource::from_string(source, name, defines),
+        }
+    }
+}
+
+/// A builder for creating shaders with extra `#define`s.
+///
+/// This allows multiple variants of a shader to be compiled from the same source code - for
+/// example, you could use `#ifdef` blocks to toggle features on and off.
+///
+/// If either stage of the shader is not specified, the default shader for that stage
+/// will be used.
+#[derive(Debug, Clone)]
+pub struct ShaderBuilder {
+    vertex: ShaderInput,
+    fragment: ShaderInput,
+    defines: Vec<(String, String)>,
+}
+
+impl ShaderBuilder {
+    /// Creates a new shader builder.
+    ///
+    /// You can also use [`Shader::builder`] as a shortcut for this, if you want
+    /// to avoid the extra import.
+    pub fn new() -> ShaderBuilder {
+        ShaderBuilder {
+            vertex: ShaderInput::Default,
+            fragment: ShaderInput::Default,
+            defines: Vec::new(),
+        }
+    }
+
+    /// Sets the vertex shader to be loaded from the given file.
+    pub fn vertex_file<P>(&mut self, path: P) -> &mut ShaderBuilder
+    where
+        P: AsRef<Path>,
+    {
+        self.vertex = ShaderInput::File(path.as_ref().to_owned());
+        self
+    }
+
+    /// Sets the vertex shader to the given source code.
+    pub fn vertex_string(&mut self, shader: &str) -> &mut ShaderBuilder {
+        self.vertex = ShaderInput::String(shader.to_owned());
+        self
+    }
+
+    /// Sets the fragment shader to be loaded from the given file.
+    pub fn fragment_file<P>(&mut self, path: P) -> &mut ShaderBuilder
+    where
+        P: AsRef<Path>,
+    {
+        self.fragment = ShaderInput::File(path.as_ref().to_owned());
+        self
+    }
+
+    /// Sets the fragment shader to the given source code.
+    pub fn fragment_string(&mut self, shader: &str) -> &mut ShaderBuilder {
+        self.fragment = ShaderInput::String(shader.to_owned());
+        self
+    }
+
+    /// Adds a `#define` to both stages of the shader.
+    ///
+    /// The value can be left empty if you only need to check whether the name
+    /// is defined.
+    pub fn define(&mut self, name: &str, value: &str) -> &mut ShaderBuilder {
+        self.defines.push((name.to_owned(), value.to_owned()));
+        self
+    }
+
+    /// Builds the shader.
+    ///
+    /// # Errors
+    ///
+    /// * [`TetraError::PlatformError`](crate::TetraError::PlatformError) will be returned if the
+    /// underlying graphics API encounters an error.
+    /// * [`TetraError::FailedToLoadAsset`](crate::TetraError::FailedToLoadAsset) will be returned
+    /// if the files (or any files that they include) could not be loaded.
+    /// * [`TetraError::InvalidShader`](crate::TetraError::InvalidShader) will be returned if the
+    /// shader could not be compiled, or if it contains an invalid or cyclic `#include`.
+    pub fn build(&self, ctx: &mut Context) -> Result<Shader> {
+        let vertex_shader =
+            self.vertex
+                .load("vertex shader", DEFAULT_VERTEX_SHADER, &self.defines)?;
+
+        let fragment_shader =
+            self.fragment
+                .load("fragment shader", DEFAULT_FRAGMENT_SHADER, &self.defines)?;
+
+        Shader::with_sources(&mut ctx.device, &vertex_shader, &fragment_shader)
+    }
+}
+
+impl Default for ShaderBuilder {
+    fn default() -> ShaderBuilder {
+        ShaderBuilder::new()
+    }
+}
+
+/// A handle to a uniform variable in a [`Shader`], which has already been looked up and
+/// checked against the type `V`.
+///
+/// Setting a uniform via a handle avoids the name lookup that
+/// [`Shader::set_uniform`] performs each time it is called.
+///
+/// Handles are created via [`Shader::uniform_handle`].
+#[derive(Debug, Clone)]
+pub struct UniformHandle<V> {
+    shader: Shader,
+    name: String,
+    location: Option<UniformLocation>,
+    _value: PhantomData<fn(V)>,
+}
+
+impl<V> UniformHandle<V>
+where
+    V: UniformValue,
+{
+    /// Sets the value of the uniform.
+    pub fn set(&self, ctx: &mut Context, value: V) {
+        self.shader.data.builtins.mark_user_set(&self.name);
+        value.set_uniform_location(ctx, &self.shader, &self.name, self.location.as_ref());
+    }
+
+    /// Returns the name of the uniform.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the shader that the uniform belongs to.
+    pub fn shader(&self) -> &Shader {
+        &self.shader
+    }
+}
+
+/// Finds the uniform that a name refers to.
+///
+/// Individual elements of an array can be referred to by index (e.g. `u_arr[2]`),
+/// so if the name has an index suffix, it is checked against the array's size.
+fn find_uniform<'a>(uniforms: &'a [ShaderVariable], name: &str) -> Result<&'a ShaderVariable> {
+    let (base, index) = match split_array_index(name) {
+        Some((base, index)) => (base, Some(index)),
+        None => (name, None),
+    };
+
+    let uniform = uniforms.iter().find(|u| u.name == base).ok_or_else(|| {
+        TetraError::InvalidUniform(format!("uniform '{}' is not active in the shader", name))
+    })?;
+
+    match index {
+        Some(index) if index >= uniform.array_size => Err(TetraError::InvalidUniform(format!(
+            "uniform '{}' is out of bounds, as '{}' has {} element(s)",
+            name, base, uniform.array_size
+        ))),
+        _ => Ok(uniform),
+    }
+}
+
+/// Splits a name like `u_arr[2]` into its base name and index.
+fn split_array_index(name: &str) -> Option<(&str, usize)> {
+    let (base, rest) = name.strip_suffix(']')?.rsplit_once('[')?;
+
+    if rest.is_empty() || !rest.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+
+    Some((base, rest.parse().ok()?))
+}
+
+/// A uniform variable or vertex attribute that is active in a [`Shader`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ShaderVariable {
+    /// The name of the variable.
+    ///
+    /// For arrays, this will not include the `[0]` suffix that OpenGL reports.
+    pub name: String,
+
+    /// The GLSL type of the variable.
+    pub ty: ShaderType,
+
+    /// The number of elements in the variable, if it is an array. This will be `1` for
+    /// variables that are not arrays.
+    pub array_size: usize,
+}
+
+/// The GLSL type of a [`ShaderVariable`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum ShaderType {
+    /// A `float`.
+    Float,
+
+    /// A `vec2`.
+    Vec2,
+
+    /// A `vec3`.
+    Vec3,
+
+    /// A `vec4`.
+    Vec4,
+
+    /// An `int`.
+    Int,
+
+    /// An `ivec2`.
+    IVec2,
+
+    /// An `ivec3`.
+    IVec3,
+
+    /// An `ivec4`.
+    IVec4,
+
+    /// A `uint`.
+    UInt,
+
+    /// A `uvec2`.
+    UVec2,
+
+    /// A `uvec3`.
+    UVec3,
+
+    /// A `uvec4`.
+    UVec4,
+
+    /// A `bool`.
+    Bool,
+
+    /// A `mat2`.
+    Mat2,
+
+    /// A `mat3`.
+    Mat3,
+
+    /// A `mat4`.
+    Mat4,
+
+    /// A `sampler2D`.
+    Sampler2D,
+
+    /// A type that Tetra does not currently support.
+    Other,
+}
+
+impl Display for ShaderType {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            ShaderType::Float => "float",
+            ShaderType::Vec2 => "vec2",
+            ShaderType::Vec3 => "vec3",
+            ShaderType::Vec4 => "vec4",
+            ShaderType::Int => "int",
+            ShaderType::IVec2 => "ivec2",
+            ShaderType::IVec3 => "ivec3",
+            ShaderType::IVec4 => "ivec4",
+            ShaderType::UInt => "uint",
+            ShaderType::UVec2 => "uvec2",
+            ShaderType::UVec3 => "uvec3",
+            ShaderType::UVec4 => "uvec4",
+            ShaderType::Bool => "bool",
+            ShaderType::Mat2 => "mat2",
+            ShaderType::Mat3 => "mat3",
+            ShaderType::Mat4 => "mat4",
+            ShaderType::Sampler2D => "sampler2D",
+            ShaderType::Other => "unknown",
+        };
+
+        f.write_str(name)
+    }
+}
+
 /// Implemented for types that can be passed as a uniform value to a shader.
 ///
 /// As the implementation of this trait currently interacts directly with the platform layer,
 /// it cannot be implemented outside of Tetra itself. This may change in the future!
 pub trait UniformValue {
     #[doc(hidden)]
-    fn set_uniform(&self, ctx: &mut Context, shader: &Shader, name: &str);
+    fn set_uniform(&self, ctx: &mut Context, shader: &Shader, name: &str) {
+        let location = ctx.device.get_uniform_location(&shader.data.handle, name);
+        self.set_uniform_location(ctx, shader, name, location.as_ref());
+    }
+
+    #[doc(hidden)]
+    fn set_uniform_location(
+        &self,
+        ctx: &mut Context,
+        shader: &Shader,
+        name: &str,
+        location: Option<&UniformLocation>,
+    );
+
+    #[doc(hidden)]
+    fn accepts(ty: ShaderType) -> bool
+    where
+        Self: Sized;
 }
 
 macro_rules! simple_uniforms {
-    ($($t:ty => $f:ident, $doc:expr, $arraydoc:expr),* $(,)?) => {
+    ($($t:ty => $f:ident, [$($ty:ident),*], $doc:expr, $arraydoc:expr),* $(,)?) => {
         $(
             #[doc = $doc]
             impl UniformValue for $t {
                 #[doc(hidden)]
-                 fn set_uniform(
+                fn set_uniform_location(
                     &self,
                     ctx: &mut Context,
                     shader: &Shader,
-                    name: &str,
+                    _: &str,
+                    location: Option<&UniformLocation>,
                 ) {
-                    let location = ctx.device.get_uniform_location(&shader.data.handle, name);
-                    ctx.device.$f(&shader.data.handle, location.as_ref(), slice::from_ref(self));
+                    ctx.device.$f(&shader.data.handle, location, slice::from_ref(self));
+                }
+
+                #[doc(hidden)]
+                fn accepts(ty: ShaderType) -> bool {
+                    matches!(ty, $(ShaderType::$ty)|*)
                 }
             }
 
             #[doc = $arraydoc]
             impl UniformValue for &[$t] {
                 #[doc(hidden)]
-                 fn set_uniform(
+                fn set_uniform_location(
                     &self,
                     ctx: &mut Context,
                     shader: &Shader,
-                    name: &str,
+                    _: &str,
+                    location: Option<&UniformLocation>,
                 ) {
-                    let location = ctx.device.get_uniform_location(&shader.data.handle, name);
-                    ctx.device.$f(&shader.data.handle, location.as_ref(), self);
+                    ctx.device.$f(&shader.data.handle, location, self);
+                }
+
+                #[doc(hidden)]
+                fn accepts(ty: ShaderType) -> bool {
+                    <$t>::accepts(ty)
                 }
             }
 
             #[doc = $arraydoc]
             impl<const N: usize> UniformValue for [$t; N] {
                 #[doc(hidden)]
-                 fn set_uniform(
+                fn set_uniform_location(
                     &self,
                     ctx: &mut Context,
                     shader: &Shader,
-                    name: &str,
+                    _: &str,
+                    location: Option<&UniformLocation>,
                 ) {
-                    let location = ctx.device.get_uniform_location(&shader.data.handle, name);
-                    ctx.device.$f(&shader.data.handle, location.as_ref(), self);
+                    ctx.device.$f(&shader.data.handle, location, self);
+                }
+
+                #[doc(hidden)]
+                fn accepts(ty: ShaderType) -> bool {
+                    <$t>::accepts(ty)
                 }
             }
         )*
     };
 }
 
+// Booleans are set via the integer/float functions in GLSL, and samplers via glUniform1i.
 simple_uniforms! {
-    i32 => set_uniform_i32, "Can be accessed as an `int` in your shader.", "Can be accessed as an array of `int`s in your shader.",
-    u32 => set_uniform_u32, "Can be accessed as a `uint` in your shader.", "Can be accessed as an array of `uint`s in your shader.",
-    f32 => set_uniform_f32, "Can be accessed as a `float` in your shader.", "Can be accessed as an array of `float`s in your shader.",
-    Vec2<f32> => set_uniform_vec2, "Can be accessed as a `vec2` in your shader.", "Can be accessed as an array of `vec2`s in your shader.",
-    Vec3<f32> => set_uniform_vec3, "Can be accessed as a `vec3` in your shader.", "Can be accessed as an array of `vec3`s in your shader.",
-    Vec4<f32> => set_uniform_vec4, "Can be accessed as a `vec4` in your shader.", "Can be accessed as an array of `vec4`s in your shader.",
-    Mat2<f32> => set_uniform_mat2, "Can be accessed as a `mat2` in your shader.", "Can be accessed as an array of `mat2`s in your shader.",
-    Mat3<f32> => set_uniform_mat3, "Can be accessed as a `mat3` in your shader.", "Can be accessed as an array of `mat3`s in your shader.",
-    Mat4<f32> => set_uniform_mat4, "Can be accessed as a `mat4` in your shader.", "Can be accessed as an array of `mat4`s in your shader.",
-    Color => set_uniform_color, "Can be accessed as a `vec4` in your shader.", "Can be accessed as an array of `vec4`s in your shader.",
+    i32 => set_uniform_i32, [Int, Bool, Sampler2D], "Can be accessed as an `int` in your shader.", "Can be accessed as an array of `int`s in your shader.",
+    u32 => set_uniform_u32, [UInt, Bool], "Can be accessed as a `uint` in your shader.", "Can be accessed as an array of `uint`s in your shader.",
+    f32 => set_uniform_f32, [Float, Bool], "Can be accessed as a `float` in your shader.", "Can be accessed as an array of `float`s in your shader.",
+    Vec2<f32> => set_uniform_vec2, [Vec2], "Can be accessed as a `vec2` in your shader.", "Can be accessed as an array of `vec2`s in your shader.",
+    Vec3<f32> => set_uniform_vec3, [Vec3], "Can be accessed as a `vec3` in your shader.", "Can be accessed as an array of `vec3`s in your shader.",
+    Vec4<f32> => set_uniform_vec4, [Vec4], "Can be accessed as a `vec4` in your shader.", "Can be accessed as an array of `vec4`s in your shader.",
+    Mat2<f32> => set_uniform_mat2, [Mat2], "Can be accessed as a `mat2` in your shader.", "Can be accessed as an array of `mat2`s in your shader.",
+    Mat3<f32> => set_uniform_mat3, [Mat3], "Can be accessed as a `mat3` in your shader.", "Can be accessed as an array of `mat3`s in your shader.",
+    Mat4<f32> => set_uniform_mat4, [Mat4], "Can be accessed as a `mat4` in your shader.", "Can be accessed as an array of `mat4`s in your shader.",
+    Color => set_uniform_color, [Vec4], "Can be accessed as a `vec4` in your shader.", "Can be accessed as an array of `vec4`s in your shader.",
 }
 
 /// Can be accessed via a `sampler2D` in your shader.
 impl UniformValue for Texture {
     #[doc(hidden)]
-    fn set_uniform(&self, ctx: &mut Context, shader: &Shader, name: &str) {
+    fn set_uniform_location(
+        &self,
+        ctx: &mut Context,
+        shader: &Shader,
+        name: &str,
+        location: Option<&UniformLocation>,
+    ) {
         let mut samplers = shader.data.samplers.borrow_mut();
 
         if let Some(sampler) = samplers.get_mut(name) {
@@ -365,11 +981,16 @@ impl UniformValue for Texture {
             );
 
             // Sampler uniforms have to be set via glUniform1i
-            (next_unit as i32).set_uniform(ctx, shader, name);
+            (next_unit as i32).set_uniform_location(ctx, shader, name, location);
 
             shader.data.next_unit.set(next_unit + 1);
         }
     }
+
+    #[doc(hidden)]
+    fn accepts(ty: ShaderType) -> bool {
+        ty == ShaderType::Sampler2D
+    }
 }
 
 /// Any type that can be passed by value to a shader can also be passed by reference.
@@ -384,4 +1005,88 @@ where
             inner.set_uniform(ctx, shader, name);
         }
     }
+
+    #[doc(hidden)]
+    fn set_uniform_location(
+        &self,
+        ctx: &mut Context,
+        shader: &Shader,
+        name: &str,
+        location: Option<&UniformLocation>,
+    ) {
+        let inner = *self;
+        inner.set_uniform_location(ctx, shader, name, location);
+    }
+
+    #[doc(hidden)]
+    fn accepts(ty: ShaderType) -> bool {
+        T::accepts(ty)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn uniforms() -> Vec<ShaderVariable> {
+        vec![
+            ShaderVariable {
+                name: "u_value".into(),
+                ty: ShaderType::Float,
+                array_size: 1,
+            },
+            ShaderVariable {
+                name: "u_arr".into(),
+                ty: ShaderType::Vec2,
+                array_size: 4,
+            },
+        ]
+    }
+
+    #[test]
+    fn user_set_builtins_are_skipped() {
+        let builtins = BuiltinUniforms {
+            time: BuiltinUniform::new(None),
+            delta_time: BuiltinUniform::new(None),
+            resolution: BuiltinUniform::new(None),
+            frame: BuiltinUniform::new(None),
+        };
+
+        builtins.mark_user_set("u_custom");
+        builtins.mark_user_set("u_time");
+        builtins.mark_user_set("u_resolution[0]");
+
+        assert!(builtins.time.user_set.get());
+        assert!(!builtins.delta_time.user_set.get());
+        assert!(builtins.resolution.user_set.get());
+        assert!(!builtins.frame.user_set.get());
+    }
+
+    #[test]
+    fn split_array_indices() {
+        assert_eq!(split_array_index("u_arr[0]"), Some(("u_arr", 0)));
+        assert_eq!(split_array_index("u_arr[12]"), Some(("u_arr", 12)));
+        assert_eq!(split_array_index("u_arr"), None);
+        assert_eq!(split_array_index("u_arr[]"), None);
+        assert_eq!(split_array_index("u_arr[-1]"), None);
+        assert_eq!(split_array_index("u_arr[x]"), None);
+    }
+
+    #[test]
+    fn find_array_elements() {
+        let uniforms = uniforms();
+
+        assert_eq!(find_uniform(&uniforms, "u_value").unwrap().name, "u_value");
+        assert_eq!(
+            find_uniform(&uniforms, "u_value[0]").unwrap().name,
+            "u_value"
+        );
+        assert_eq!(find_uniform(&uniforms, "u_arr").unwrap().name, "u_arr");
+        assert_eq!(find_uniform(&uniforms, "u_arr[0]").unwrap().name, "u_arr");
+        assert_eq!(find_uniform(&uniforms, "u_arr[3]").unwrap().name, "u_arr");
+
+        assert!(find_uniform(&uniforms, "u_arr[4]").is_err());
+        assert!(find_uniform(&uniforms, "u_value[1]").is_err());
+        assert!(find_uniform(&uniforms, "u_missing[0]").is_err());
+    }
 }
@@ -0,0 +1,532 @@
+//! A simple preprocessor for GLSL source code, which resolves `#include` directives
+//! and injects user-provided `#define`s.
+//!
+//! Everything else (including `#if`/`#ifdef` blocks) is left for the GLSL compiler
+//! to handle - this means that `#include`s are always resolved, even if they are
+//! inside a block that will be excluded from compilation.
+
+use std::path::{Component, Path, PathBuf};
+
+use crate::error::{Result, TetraError};
+use crate::fs;
+
+/// Where a line of preprocessed source came from.
+#[derive(Debug, Copy, Clone, PartialEq)]
+enum LineOrigin {
+    Define,
+    File { file: usize, line: usize },
+}
+
+/// GLSL source code that has been run through the preprocessor, along with
+/// the information required to map compiler errors back to the original files.
+#[derive(Debug, Clone)]
+pub(crate) struct ShaderSource {
+    source: String,
+    files: Vec<String>,
+    lines: Vec<LineOrigin>,
+}
+
+impl ShaderSource {
+    /// Loads and preprocesses a shader from a file. Includes are resolved relative to the
+    /// directory that the file is in.
+    pub(crate) fn from_file(path: &Path, defines: &[(String, String)]) -> Result<ShaderSource> {
+        let source = fs::read_to_string(path)?;
+
+        Preprocessor::new(defines, |p: &Path| fs::read_to_string(p)).run(
+            &source,
+            path.display().to_string(),
+            Some(path),
+        )
+    }
+
+    /// Preprocesses a shader from a string. Includes are resolved relative to the current
+    /// working directory.
+    pub(crate) fn from_string(
+        source: &str,
+        name: &str,
+        defines: &[(String, String)],
+    ) -> Result<ShaderSource> {
+        Preprocessor::new(defines, |p: &Path| fs::read_to_string(p)).run(
+            source,
+            name.to_owned(),
+            None,
+        )
+    }
+
+    pub(crate) fn source(&self) -> &str {
+        &self.source
+    }
+
+    /// Rewrites the line numbers in a compiler log so that they refer to the original files,
+    /// rather than the preprocessed source.
+    ///
+    /// This understands the `0:LINE` format used by most drivers (e.g. Mesa, AMD and Intel),
+    /// as well as the `0(LINE)` format used by Nvidia. Any lines that do not contain
+    /// a location will be passed through as-is.
+    pub(crate) fn map_log(&self, log: &str) -> String {
+        let mut output = String::with_capacity(log.len());
+
+        for line in log.lines() {
+            match self.map_log_line(line) {
+                Some(mapped) => output.push_str(&mapped),
+                None => output.push_str(line),
+            }
+
+            output.push('\n');
+        }
+
+        output
+    }
+
+    fn map_log_line(&self, line: &str) -> Option<String> {
+        let bytes = line.as_bytes();
+        let mut i = 0;
+
+        while i < bytes.len() {
+            let at_boundary = i == 0 || !bytes[i - 1].is_ascii_alphanumeric();
+
+            // The source string number will always be 0, as we only pass in a single string.
+            if at_boundary && bytes[i] == b'0' && i + 1 < bytes.len() {
+                let (separator, close) = match bytes[i + 1] {
+                    b':' => (':', None),
+                    b'(' => ('(', Some(")")),
+                    _ => {
+                        i += 1;
+                        continue;
+                    }
+                };
+
+                let digits_start = i + 2;
+                let digits_end = bytes[digits_start..]
+                    .iter()
+                    .position(|b| !b.is_ascii_digit())
+                    .map(|len| digits_start + len)
+                    .unwrap_or(bytes.len());
+
+                let closed = match close {
+                    Some(c) => line[digits_end..].starts_with(c),
+                    None => true,
+                };
+
+                if digits_end > digits_start && closed {
+                    if let Some(location) = line[digits_start..digits_end]
+                        .parse::<usize>()
+                        .ok()
+                        .and_then(|n| self.describe_line(n))
+                    {
+                        let end = if close.is_some() {
+                            digits_end + 1
+                        } else {
+                            digits_end
+                        };
+
+                        return Some(format!(
+                            "{}{}{}{}{}{}",
+                            &line[..i],
+                            location.0,
+                            separator,
+                            location.1,
+                            close.unwrap_or(""),
+                            &line[end..]
+                        ));
+                    }
+                }
+            }
+
+            i += 1;
+        }
+
+        None
+    }
+
+    fn describe_line(&self, line: usize) -> Option<(&str, usize)> {
+        match self.lines.get(line.checked_sub(1)?)? {
+            LineOrigin::Define => Some(("<defines>", 0)),
+            LineOrigin::File { file, line } => Some((&self.files[*file], *line)),
+        }
+    }
+}
+
+struct Preprocessor<'a, F> {
+    defines: &'a [(String, String)],
+    load: F,
+    stack: Vec<PathBuf>,
+    output: ShaderSource,
+}
+
+impl<'a, F> Preprocessor<'a, F>
+where
+    F: FnMut(&Path) -> Result<String>,
+{
+    fn new(defines: &'a [(String, String)], load: F) -> Preprocessor<'a, F> {
+        Preprocessor {
+            defines,
+            load,
+            stack: Vec::new(),
+            output: ShaderSource {
+                source: String::new(),
+                files: Vec::new(),
+                lines: Vec::new(),
+            },
+        }
+    }
+
+    fn run(mut self, source: &str, name: String, path: Option<&Path>) -> Result<ShaderSource> {
+        if let Some(path) = path {
+            self.stack.push(normalize(path));
+        }
+
+        let file = self.add_file(name);
+        let mut lines = source.lines().enumerate().peekable();
+
+        // Defines have to go after the `#version` directive, as it must be the first
+        // thing in the shader.
+        let mut in_block_comment = false;
+
+        while let Some((_, line)) = lines.peek() {
+            let code = strip_comments(line, &mut in_block_comment);
+
+            if code.is_empty() {
+                let (i, line) = lines.next().unwrap();
+                self.push_line(line, LineOrigin::File { file, line: i + 1 });
+                continue;
+            }
+
+            if code.starts_with("#version") {
+                let (i, line) = lines.next().unwrap();
+                self.push_line(line, LineOrigin::File { file, line: i + 1 });
+            }
+
+            break;
+        }
+
+        for (name, value) in self.defines {
+            let define = format!("#define {} {}", name, value);
+            self.push_line(define.trim_end(), LineOrigin::Define);
+        }
+
+        for (i, line) in lines {
+            self.process_line(line, file, i + 1, path)?;
+        }
+
+        Ok(self.output)
+    }
+
+    fn process_file(&mut self, path: &Path) -> Result {
+        let normalized = normalize(path);
+
+        if let Some(start) = self.stack.iter().position(|p| *p == normalized) {
+            let cycle = self.stack[start..]
+                .iter()
+                .chain(Some(&normalized))
+                .map(|p| p.display().to_string())
+                .collect::<Vec<_>>()
+                .join(" -> ");
+
+            return Err(TetraError::InvalidShader(format!(
+                "cyclic #include detected: {}",
+                cycle
+            )));
+        }
+
+        let source = (self.load)(path)?;
+        let file = self.add_file(path.display().to_string());
+
+        self.stack.push(normalized);
+
+        for (i, line) in source.lines().enumerate() {
+            self.process_line(line, file, i + 1, Some(path))?;
+        }
+
+        self.stack.pop();
+
+        Ok(())
+    }
+
+    fn process_line(
+        &mut self,
+        line: &str,
+        file: usize,
+        line_number: usize,
+        path: Option<&Path>,
+    ) -> Result {
+        match parse_include(line) {
+            Some(Ok(include)) => {
+                let include_path = match path.and_then(Path::parent) {
+                    Some(parent) => parent.join(include),
+                    None => PathBuf::from(include),
+                };
+
+                self.process_file(&include_path)
+            }
+
+            Some(Err(())) => Err(TetraError::InvalidShader(format!(
+                "{}:{}: malformed #include directive (expected `#include \"path\"`)",
+                self.output.files[file], line_number
+            ))),
+
+            None => {
+                self.push_line(
+                    line,
+                    LineOrigin::File {
+                        file,
+                        line: line_number,
+                    },
+                );
+
+                Ok(())
+            }
+        }
+    }
+
+    fn add_file(&mut self, name: String) -> usize {
+        self.output.files.push(name);
+        self.output.files.len() - 1
+    }
+
+    fn push_line(&mut self, line: &str, origin: LineOrigin) {
+        self.output.source.push_str(line);
+        self.output.source.push('\n');
+        self.output.lines.push(origin);
+    }
+}
+
+/// Strips any comments from the start of a line, returning the code that follows them.
+///
+/// `in_block_comment` tracks whether a `/* ... */` comment is still open from a
+/// previous line, and will be updated if this line opens or closes one.
+fn strip_comments<'a>(line: &'a str, in_block_comment: &mut bool) -> &'a str {
+    let mut rest = line;
+
+    loop {
+        if *in_block_comment {
+            match rest.find("*/") {
+                Some(end) => {
+                    rest = &rest[end + 2..];
+                    *in_block_comment = false;
+                }
+                None => return "",
+            }
+        }
+
+        rest = rest.trim();
+
+        if rest.starts_with("//") {
+            return "";
+        }
+
+        match rest.strip_prefix("/*") {
+            Some(comment) => {
+                rest = comment;
+                *in_block_comment = true;
+            }
+            None => return rest,
+        }
+    }
+}
+
+/// Returns `None` if the line is not an include, and `Some(Err(()))` if it is an include,
+/// but the path could not be parsed.
+fn parse_include(line: &str) -> Option<std::result::Result<&str, ()>> {
+    let directive = line.trim().strip_prefix('#')?.trim_start();
+    let rest = directive.strip_prefix("include")?;
+
+    if !rest.is_empty() && !rest.starts_with(char::is_whitespace) && !rest.starts_with('"') {
+        return None;
+    }
+
+    let rest = rest.trim();
+
+    let path = rest
+        .strip_prefix('"')
+        .and_then(|r| r.strip_suffix('"'))
+        .filter(|p| !p.is_empty() && !p.contains('"'));
+
+    Some(path.ok_or(()))
+}
+
+/// Normalizes a path lexically, so that the same file included via different
+/// relative paths can be detected as part of a cycle.
+fn normalize(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                if !normalized.pop() {
+                    normalized.push("..");
+                }
+            }
+            c => normalized.push(c),
+        }
+    }
+
+    normalized
+}
+
+#[cfg(test)]
+mod tests {
+    use hashbrown::HashMap;
+
+    use super::*;
+
+    fn preprocess(
+        files: &[(&str, &str)],
+        entry: &str,
+        defines: &[(String, String)],
+    ) -> Result<ShaderSource> {
+        let files: HashMap<PathBuf, String> = files
+            .iter()
+            .map(|(p, s)| (PathBuf::from(p), s.to_string()))
+            .collect();
+
+        let source = files[Path::new(entry)].clone();
+
+        Preprocessor::new(defines, |p: &Path| {
+            files
+                .get(&normalize(p))
+                .cloned()
+                .ok_or_else(|| TetraError::InvalidShader(format!("missing {}", p.display())))
+        })
+        .run(&source, entry.to_owned(), Some(Path::new(entry)))
+    }
+
+    #[test]
+    fn resolves_includes_relative_to_file() {
+        let output = preprocess(
+            &[
+                ("shaders/main.frag", "a\n#include \"lib/noise.glsl\"\nb"),
+                (
+                    "shaders/lib/noise.glsl",
+                    "#include \"../common.glsl\"\nnoise",
+                ),
+                ("shaders/common.glsl", "common"),
+            ],
+            "shaders/main.frag",
+            &[],
+        )
+        .unwrap();
+
+        assert_eq!("a\ncommon\nnoise\nb\n", output.source());
+    }
+
+    #[test]
+    fn detects_include_cycles() {
+        let result = preprocess(
+            &[
+                ("a.glsl", "#include \"b.glsl\""),
+                ("b.glsl", "#include \"./a.glsl\""),
+            ],
+            "a.glsl",
+            &[],
+        );
+
+        match result {
+            Err(TetraError::InvalidShader(msg)) => {
+                assert_eq!("cyclic #include detected: a.glsl -> b.glsl -> a.glsl", msg)
+            }
+            _ => panic!("expected cycle error"),
+        }
+    }
+
+    #[test]
+    fn rejects_malformed_includes() {
+        let result = preprocess(&[("a.glsl", "\n#include <b.glsl>")], "a.glsl", &[]);
+
+        match result {
+            Err(TetraError::InvalidShader(msg)) => assert!(msg.starts_with("a.glsl:2:")),
+            _ => panic!("expected malformed include error"),
+        }
+    }
+
+    #[test]
+    fn inserts_defines_after_version() {
+        let defines = vec![
+            ("USE_LIGHTING".to_owned(), String::new()),
+            ("COUNT".to_owned(), "4".to_owned()),
+        ];
+
+        let output = preprocess(
+            &[("a.frag", "// comment\n#version 150\nvoid main() {}")],
+            "a.frag",
+            &defines,
+        )
+        .unwrap();
+
+        assert_eq!(
+            "// comment\n#version 150\n#define USE_LIGHTING\n#define COUNT 4\nvoid main() {}\n",
+            output.source()
+        );
+
+        let output = preprocess(&[("b.frag", "void main() {}")], "b.frag", &defines).unwrap();
+
+        assert_eq!(
+            "#define USE_LIGHTING\n#define COUNT 4\nvoid main() {}\n",
+            output.source()
+        );
+    }
+
+    #[test]
+    fn inserts_defines_after_block_comments() {
+        let defines = vec![("COUNT".to_owned(), "4".to_owned())];
+
+        let output = preprocess(
+            &[(
+                "a.frag",
+                "/*\n * License\n */\n/* a */ /* b\n c */\n#version 150\nvoid main() {}",
+            )],
+            "a.frag",
+            &defines,
+        )
+        .unwrap();
+
+        assert_eq!(
+            "/*\n * License\n */\n/* a */ /* b\n c */\n#version 150\n#define COUNT 4\nvoid main() {}\n",
+            output.source()
+        );
+
+        let output = preprocess(
+            &[("b.frag", "/* comment */ void main() {}")],
+            "b.frag",
+            &defines,
+        )
+        .unwrap();
+
+        assert_eq!(
+            "#define COUNT 4\n/* comment */ void main() {}\n",
+            output.source()
+        );
+    }
+
+    #[test]
+    fn maps_log_lines_to_original_files() {
+        let defines = vec![("FOO".to_owned(), "1".to_owned())];
+
+        let output = preprocess(
+            &[
+                ("main.frag", "#version 150\n#include \"lib.glsl\"\nbad"),
+                ("lib.glsl", "one\ntwo"),
+            ],
+            "main.frag",
+            &defines,
+        )
+        .unwrap();
+
+        // 1: #version, 2: #define, 3-4: lib.glsl, 5: main.frag line 3
+        assert_eq!(
+            "main.frag:3(1): error: oops\n",
+            output.map_log("0:5(1): error: oops")
+        );
+
+        assert_eq!(
+            "lib.glsl(2) : error C0000: oops\n",
+            output.map_log("0(4) : error C0000: oops")
+        );
+
+        assert_eq!(
+            "ERROR: lib.glsl:1: oops\nno location here\n",
+            output.map_log("ERROR: 0:3: oops\nno location here")
+        );
+    }
+}
@@ -0,0 +1,219 @@
+//! Functions for drawing primitive shapes in immediate mode.
+
+use std::mem;
+
+use crate::context::Context;
+use crate::error::Result;
+use crate::graphics::mesh::{
+    BorderRadii, BufferUsage, GeometryBuilder, Gradient, IndexBuffer, Mesh, Path, ShapeStyle,
+    VertexBuffer,
+};
+use crate::graphics::{self, ActiveTexture, Color, DrawParams, Rectangle};
+use crate::math::Vec2;
+
+/// Draws a line between two points.
+///
+/// Shapes are tessellated on the CPU and added to the same batch as sprites, so many
+/// shapes can be drawn with a single draw call. They are drawn using a plain white texture,
+/// so switching between drawing shapes and drawing textures will cause the batch to
+/// be flushed.
+///
+/// If you are drawing the same complex shape every frame, it may be more efficient to
+/// build a [`Mesh`] ahead of time.
+///
+/// # Errors
+///
+/// * [`TetraError::TessellationError`](crate::TetraError::TessellationError) will be returned if the shape
+/// could not be turned into vertex data.
+/// * [`TetraError::PlatformError`](crate::TetraError::PlatformError) will be returned if the shape was too
+/// large to be batched, and the underlying graphics API encountered an error while drawing it separately.
+pub fn draw_line(
+    ctx: &mut Context,
+    from: Vec2<f32>,
+    to: Vec2<f32>,
+    width: f32,
+    color: Color,
+) -> Result {
+    draw_polyline(ctx, &[from, to], width, color)
+}
+
+/// Draws a line through a series of points.
+///
+/// See [`draw_line`] for more information on how shapes are drawn.
+///
+/// # Errors
+///
+/// * [`TetraError::TessellationError`](crate::TetraError::TessellationError) will be returned if the shape
+/// could not be turned into vertex data.
+/// * [`TetraError::PlatformError`](crate::TetraError::PlatformError) will be returned if the shape was too
+/// large to be batched, and the underlying graphics API encountered an error while drawing it separately.
+pub fn draw_polyline(ctx: &mut Context, points: &[Vec2<f32>], width: f32, color: Color) -> Result {
+    draw_shape(ctx, color, |b| b.polyline(width, points).map(|_| ()))
+}
+
+/// Draws a rectangle.
+///
+/// See [`draw_line`] for more information on how shapes are drawn.
+///
+/// # Errors
+///
+/// * [`TetraError::TessellationError`](crate::TetraError::TessellationError) will be returned if the shape
+/// could not be turned into vertex data.
+/// * [`TetraError::PlatformError`](crate::TetraError::PlatformError) will be returned if the shape was too
+/// large to be batched, and the underlying graphics API encountered an error while drawing it separately.
+pub fn draw_rect(
+    ctx: &mut Context,
+    style: ShapeStyle,
+    rectangle: Rectangle,
+    color: Color,
+) -> Result {
+    draw_shape(ctx, color, |b| b.rectangle(style, rectangle).map(|_| ()))
+}
+
+/// Draws a rectangle with rounded corners.
+///
+/// See [`draw_line`] for more information on how shapes are drawn.
+///
+/// # Errors
+///
+/// * [`TetraError::TessellationError`](crate::TetraError::TessellationError) will be returned if the shape
+/// could not be turned into vertex data.
+/// * [`TetraError::PlatformError`](crate::TetraError::PlatformError) will be returned if the shape was too
+/// large to be batched, and the underlying graphics API encountered an error while drawing it separately.
+pub fn draw_rounded_rect(
+    ctx: &mut Context,
+    style: ShapeStyle,
+    rectangle: Rectangle,
+    radii: BorderRadii,
+    color: Color,
+) -> Result {
+    draw_shape(ctx, color, |b| {
+        b.rounded_rectangle(style, rectangle, radii).map(|_| ())
+    })
+}
+
+/// Draws a circle.
+///
+/// See [`draw_line`] for more information on how shapes are drawn.
+///
+/// # Errors
+///
+/// * [`TetraError::TessellationError`](crate::TetraError::TessellationError) will be returned if the shape
+/// could not be turned into vertex data.
+/// * [`TetraError::PlatformError`](crate::TetraError::PlatformError) will be returned if the shape was too
+/// large to be batched, and the underlying graphics API encountered an error while drawing it separately.
+pub fn draw_circle(
+    ctx: &mut Context,
+    style: ShapeStyle,
+    center: Vec2<f32>,
+    radius: f32,
+    color: Color,
+) -> Result {
+    draw_shape(ctx, color, |b| b.circle(style, center, radius).map(|_| ()))
+}
+
+/// Draws an ellipse.
+///
+/// See [`draw_line`] for more information on how shapes are drawn.
+///
+/// # Errors
+///
+/// * [`TetraError::TessellationError`](crate::TetraError::TessellationError) will be returned if the shape
+/// could not be turned into vertex data.
+/// * [`TetraError::PlatformError`](crate::TetraError::PlatformError) will be returned if the shape was too
+/// large to be batched, and the underlying graphics API encountered an error while drawing it separately.
+pub fn draw_ellipse(
+    ctx: &mut Context,
+    style: ShapeStyle,
+    center: Vec2<f32>,
+    radii: Vec2<f32>,
+    color: Color,
+) -> Result {
+    draw_shape(ctx, color, |b| b.ellipse(style, center, radii).map(|_| ()))
+}
+
+/// Draws a polygon.
+///
+/// See [`draw_line`] for more information on how shapes are drawn.
+///
+/// # Errors
+///
+/// * [`TetraError::TessellationError`](crate::TetraError::TessellationError) will be returned if the shape
+/// could not be turned into vertex data.
+/// * [`TetraError::PlatformError`](crate::TetraError::PlatformError) will be returned if the shape was too
+/// large to be batched, and the underlying graphics API encountered an error while drawing it separately.
+pub fn draw_polygon(
+    ctx: &mut Context,
+    style: ShapeStyle,
+    points: &[Vec2<f32>],
+    color: Color,
+) -> Result {
+    draw_shape(ctx, color, |b| b.polygon(style, points).map(|_| ()))
+}
+
+/// Draws a path.
+///
+/// See [`draw_line`] for more information on how shapes are drawn.
+///
+/// # Errors
+///
+/// * [`TetraError::TessellationError`](crate::TetraError::TessellationError) will be returned if the shape
+/// could not be turned into vertex data.
+/// * [`TetraError::PlatformError`](crate::TetraError::PlatformError) will be returned if the shape was too
+/// large to be batched, and the underlying graphics API encountered an error while drawing it separately.
+pub fn draw_path(ctx: &mut Context, style: ShapeStyle, path: &Path, color: Color) -> Result {
+    draw_shape(ctx, color, |b| b.path(style, path).map(|_| ()))
+}
+
+/// Sets the gradient that will be used to color shapes drawn via the `graphics::draw_*`
+/// functions.
+///
+/// The gradient's colors will be multiplied by the color passed to each function, so
+/// passing [`Color::WHITE`] will draw the gradient as-is. The gradient's points are specified
+/// in the same co-ordinate space as the shapes.
+///
+/// As gradients are applied to the vertices of the shapes, changing the gradient does
+/// not cause the batch to be flushed.
+pub fn set_shape_gradient(ctx: &mut Context, gradient: Gradient) {
+    ctx.graphics.shape_builder.set_gradient(gradient);
+}
+
+/// Stops applying a gradient to shapes drawn via the `graphics::draw_*` functions.
+pub fn reset_shape_gradient(ctx: &mut Context) {
+    ctx.graphics.shape_builder.reset_gradient();
+}
+
+fn draw_shape<F>(ctx: &mut Context, color: Color, build: F) -> Result
+where
+    F: FnOnce(&mut GeometryBuilder) -> Result,
+{
+    // The builder is stored on the context so that its buffers can be reused between
+    // shapes - it needs to be temporarily moved out so that the batch can be modified.
+    let mut builder = mem::take(&mut ctx.graphics.shape_builder);
+
+    builder.clear();
+    builder.set_color(color);
+
+    let result = build(&mut builder).and_then(|_| push_shape(ctx, &builder));
+
+    ctx.graphics.shape_builder = builder;
+
+    result
+}
+
+fn push_shape(ctx: &mut Context, builder: &GeometryBuilder) -> Result {
+    let default_texture = ctx.graphics.default_texture.clone();
+    graphics::set_texture_ex(ctx, ActiveTexture::User(default_texture));
+
+    if !graphics::push_geometry(ctx, builder.vertices(), builder.indices()) {
+        // The shape is too big to fit into the batch, so it has to be drawn on its own.
+        graphics::flush(ctx);
+
+        let vertex_buffer = VertexBuffer::with_usage(ctx, builder.vertices(), BufferUsage::Stream)?;
+        let index_buffer = IndexBuffer::with_usage(ctx, builder.indices(), BufferUsage::Stream)?;
+
+        Mesh::indexed(vertex_buffer, index_buffer).draw(ctx, DrawParams::new());
+    }
+
+    Ok(())
+}
@@ -1,4 +1,4 @@
-use crate::graphics::{FilterMode, Texture};
+use crate::graphics::{FilterMode, Texture, TextureFormat};
 use crate::platform::GraphicsDevice;
 use crate::{context::Context, Result};
 
@@ -33,6 +33,7 @@ impl ShelfPacker {
                 device,
                 texture_width,
                 texture_height,
+                TextureFormat::Rgba8,
                 filter_mode,
             )?,
             shelves: Vec::new(),
@@ -64,6 +65,7 @@ impl ShelfPacker {
             device,
             texture_width,
             texture_height,
+            TextureFormat::Rgba8,
             self.texture.filter_mode(),
         )?;
 
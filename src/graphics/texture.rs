@@ -17,12 +17,15 @@ use crate::context::Context;
 pub(crate) struct TextureSharedData {
     pub(crate) handle: RawTexture,
     filter_mode: Cell<FilterMode>,
+    wrap_mode: Cell<(WrapMode, WrapMode)>,
+    border_color: Cell<Color>,
+    mipmaps: Cell<bool>,
 }
 
 impl PartialEq for TextureSharedData {
     fn eq(&self, other: &TextureSharedData) -> bool {
-        // filter_mode should always match what's set on the GPU,
-        // so we can ignore it for equality checks.
+        // The sampler settings should always match what's set on the GPU,
+        // so we can ignore them for equality checks.
 
         self.handle.eq(&other.handle)
     }
@@ -127,10 +130,34 @@ impl Texture {
     /// * [`TetraError::NotEnoughData`] will be returned if not enough data is provided to fill
     /// the texture. This is to prevent the graphics API from trying to read uninitialized memory.
     pub fn from_rgba(ctx: &mut Context, width: i32, height: i32, data: &[u8]) -> Result<Texture> {
+        Texture::from_data(ctx, width, height, TextureFormat::Rgba8, data)
+    }
+
+    /// Creates a new texture from a slice of pixel data in the given format.
+    ///
+    /// The data should be laid out as described in the docs for [`TextureFormat`].
+    ///
+    /// This method requires you to provide enough data to fill the texture.
+    /// If you provide too little data, an error will be returned.
+    /// If you provide too much data, it will be truncated.
+    ///
+    /// # Errors
+    ///
+    /// * [`TetraError::PlatformError`] will be returned if the underlying graphics API encounters an error.
+    /// * [`TetraError::NotEnoughData`] will be returned if not enough data is provided to fill
+    /// the texture. This is to prevent the graphics API from trying to read uninitialized memory.
+    pub fn from_data(
+        ctx: &mut Context,
+        width: i32,
+        height: i32,
+        format: TextureFormat,
+        data: &[u8],
+    ) -> Result<Texture> {
         Texture::with_device(
             &mut ctx.device,
             width,
             height,
+            format,
             data,
             ctx.graphics.default_filter_mode,
         )
@@ -141,6 +168,9 @@ impl Texture {
             data: Rc::new(TextureSharedData {
                 handle,
                 filter_mode: Cell::new(filter_mode),
+                wrap_mode: Cell::new((WrapMode::ClampToEdge, WrapMode::ClampToEdge)),
+                border_color: Cell::new(Color::rgba(0.0, 0.0, 0.0, 0.0)),
+                mipmaps: Cell::new(false),
             }),
         }
     }
@@ -149,66 +179,47 @@ impl Texture {
         device: &mut GraphicsDevice,
         width: i32,
         height: i32,
+        format: TextureFormat,
         data: &[u8],
         filter_mode: FilterMode,
     ) -> Result<Texture> {
-        let handle = device.new_texture(width, height, filter_mode)?;
+        let handle = device.new_texture(width, height, format, filter_mode)?;
 
         device.set_texture_data(&handle, &data, 0, 0, width, height)?;
 
-        Ok(Texture {
-            data: Rc::new(TextureSharedData {
-                handle,
-                filter_mode: Cell::new(filter_mode),
-            }),
-        })
+        Ok(Texture::from_raw(handle, filter_mode))
     }
 
     pub(crate) fn with_device_empty(
         device: &mut GraphicsDevice,
         width: i32,
         height: i32,
+        format: TextureFormat,
         filter_mode: FilterMode,
     ) -> Result<Texture> {
         // TODO: There's probably more efficient ways of doing this, but it seems fast enough
         // for now.
-        let data = vec![0; (width * height * 4) as usize];
+        let data = vec![0; (width * height) as usize * format.bytes_per_pixel()];
 
-        Texture::with_device(device, width, height, &data, filter_mode)
+        Texture::with_device(device, width, height, format, &data, filter_mode)
     }
 
     /// Draws the texture to the screen (or to a canvas, if one is enabled).
+    ///
+    /// If [`DrawParams::source`] is set, only that region of the texture will be drawn.
     pub fn draw<P>(&self, ctx: &mut Context, params: P)
     where
         P: Into<DrawParams>,
     {
         let params = params.into();
 
-        graphics::set_texture(ctx, self);
-        graphics::push_quad(
-            ctx,
-            0.0,
-            0.0,
-            self.width() as f32,
-            self.height() as f32,
-            0.0,
-            0.0,
-            1.0,
-            1.0,
-            &params,
-        );
-    }
-
-    /// Draws a region of the texture to the screen (or to a canvas, if one is enabled).
-    pub fn draw_region<P>(&self, ctx: &mut Context, region: Rectangle, params: P)
-    where
-        P: Into<DrawParams>,
-    {
-        let params = params.into();
-
         let texture_width = self.width() as f32;
         let texture_height = self.height() as f32;
 
+        let region = params
+            .source
+            .unwrap_or_else(|| Rectangle::new(0.0, 0.0, texture_width, texture_height));
+
         graphics::set_texture(ctx, self);
         graphics::push_quad(
             ctx,
@@ -224,8 +235,22 @@ impl Texture {
         );
     }
 
+    /// Draws a region of the texture to the screen (or to a canvas, if one is enabled).
+    ///
+    /// This is a shortcut for calling [`draw`](Self::draw) with [`DrawParams::source`]
+    /// set to `region`.
+    pub fn draw_region<P>(&self, ctx: &mut Context, region: Rectangle, params: P)
+    where
+        P: Into<DrawParams>,
+    {
+        self.draw(ctx, params.into().source(region));
+    }
+
     /// Draws a region of the texture by splitting it into nine slices, allowing it to be stretched or
     /// squashed without distorting the borders.
+    ///
+    /// The edges and center of the nine slice can either be stretched or tiled, depending on the
+    /// [`SliceMode`]s set in the config.
     pub fn draw_nine_slice<P>(
         &self,
         ctx: &mut Context,
@@ -259,34 +284,44 @@ impl Texture {
         let u4 = (config.region.x + config.region.width) / texture_width;
         let v4 = (config.region.y + config.region.height) / texture_height;
 
-        graphics::set_texture(ctx, self);
-
-        // Top left
-        graphics::push_quad(ctx, x1, y1, x2, y2, u1, v1, u2, v2, &params);
-
-        // Top
-        graphics::push_quad(ctx, x2, y1, x3, y2, u2, v1, u3, v2, &params);
+        let center_width = config.region.width - config.left - config.right;
+        let center_height = config.region.height - config.top - config.bottom;
 
-        // Top right
-        graphics::push_quad(ctx, x3, y1, x4, y2, u3, v1, u4, v2, &params);
+        let columns = [
+            Slice::new(x1, x2, u1, u2, config.left),
+            Slice::new(x2, x3, u2, u3, center_width),
+            Slice::new(x3, x4, u3, u4, config.right),
+        ];
 
-        // Left
-        graphics::push_quad(ctx, x1, y2, x2, y3, u1, v2, u2, v3, &params);
+        let rows = [
+            Slice::new(y1, y2, v1, v2, config.top),
+            Slice::new(y2, y3, v2, v3, center_height),
+            Slice::new(y3, y4, v3, v4, config.bottom),
+        ];
 
-        // Center
-        graphics::push_quad(ctx, x2, y2, x3, y3, u2, v2, u3, v3, &params);
-
-        // Right
-        graphics::push_quad(ctx, x3, y2, x4, y3, u3, v2, u4, v3, &params);
-
-        // Bottom left
-        graphics::push_quad(ctx, x1, y3, x2, y4, u1, v3, u2, v4, &params);
-
-        // Bottom
-        graphics::push_quad(ctx, x2, y3, x3, y4, u2, v3, u3, v4, &params);
+        graphics::set_texture(ctx, self);
 
-        // Bottom right
-        graphics::push_quad(ctx, x3, y3, x4, y4, u3, v3, u4, v4, &params);
+        for (row_index, row) in rows.iter().enumerate() {
+            for (column_index, column) in columns.iter().enumerate() {
+                // Corners are always stretched, as they're drawn at their native size
+                // unless the border offsets are larger than the target size.
+                let (horizontal, vertical) = match (column_index, row_index) {
+                    (1, 0) => (config.top_mode, SliceMode::Stretch),
+                    (1, 2) => (config.bottom_mode, SliceMode::Stretch),
+                    (0, 1) => (SliceMode::Stretch, config.left_mode),
+                    (2, 1) => (SliceMode::Stretch, config.right_mode),
+                    (1, 1) if !config.draw_center => continue,
+                    (1, 1) => (config.center_mode, config.center_mode),
+                    _ => (SliceMode::Stretch, SliceMode::Stretch),
+                };
+
+                for (x1, x2, u1, u2) in column.segments(horizontal) {
+                    for (y1, y2, v1, v2) in row.segments(vertical) {
+                        graphics::push_quad(ctx, x1, y1, x2, y2, u1, v1, u2, v2, &params);
+                    }
+                }
+            }
+        }
     }
 
     /// Returns the width of the texture.
@@ -304,12 +339,20 @@ impl Texture {
         (self.data.handle.width(), self.data.handle.height())
     }
 
+    /// Returns the format of the texture's pixel data.
+    pub fn format(&self) -> TextureFormat {
+        self.data.handle.format()
+    }
+
     /// Returns the filter mode being used by the texture.
     pub fn filter_mode(&self) -> FilterMode {
         self.data.filter_mode.get()
     }
 
     /// Sets the filter mode that should be used by the texture.
+    ///
+    /// The mipmapped filter modes will only have an effect once mipmaps have been
+    /// [generated](Self::generate_mipmaps) for the texture.
     pub fn set_filter_mode(&mut self, ctx: &mut Context, filter_mode: FilterMode) {
         ctx.device
             .set_texture_filter_mode(&self.data.handle, filter_mode);
@@ -317,11 +360,76 @@ impl Texture {
         self.data.filter_mode.set(filter_mode);
     }
 
+    /// Returns the wrap modes being used by the texture, on the horizontal and
+    /// vertical axes respectively.
+    pub fn wrap_mode(&self) -> (WrapMode, WrapMode) {
+        self.data.wrap_mode.get()
+    }
+
+    /// Sets the wrap modes that should be used by the texture, on the horizontal and
+    /// vertical axes respectively.
+    ///
+    /// This determines what happens when the texture is sampled outside of its bounds
+    /// (e.g. when drawing a region that is larger than the texture, or when using
+    /// custom UVs in a [`Mesh`](super::mesh::Mesh)).
+    pub fn set_wrap_mode(&mut self, ctx: &mut Context, horizontal: WrapMode, vertical: WrapMode) {
+        ctx.device
+            .set_texture_wrap_mode(&self.data.handle, horizontal, vertical);
+
+        self.data.wrap_mode.set((horizontal, vertical));
+    }
+
+    /// Returns the border color being used by the texture.
+    pub fn border_color(&self) -> Color {
+        self.data.border_color.get()
+    }
+
+    /// Sets the color that will be returned when sampling outside of the texture's bounds
+    /// on an axis that is using [`WrapMode::ClampToBorder`].
+    ///
+    /// This defaults to transparent black.
+    pub fn set_border_color(&mut self, ctx: &mut Context, color: Color) {
+        ctx.device
+            .set_texture_border_color(&self.data.handle, color);
+
+        self.data.border_color.set(color);
+    }
+
+    /// Returns whether mipmaps have been generated for the texture.
+    pub fn has_mipmaps(&self) -> bool {
+        self.data.mipmaps.get()
+    }
+
+    /// Generates mipmaps for the texture.
+    ///
+    /// Mipmaps are progressively smaller copies of the texture, which allow it to be
+    /// drawn at a reduced scale (e.g. when zooming out a camera) without aliasing.
+    /// In order for them to be used, the texture must also be using one of the
+    /// mipmapped [`FilterMode`]s.
+    ///
+    /// Once this has been called, the mipmaps will be regenerated automatically
+    /// whenever the texture's data is changed via [`set_data`](Self::set_data)
+    /// or [`replace_data`](Self::replace_data).
+    ///
+    /// # Performance
+    ///
+    /// Mipmaps use roughly a third more video RAM than the texture itself, and
+    /// regenerating them is relatively expensive.
+    pub fn generate_mipmaps(&mut self, ctx: &mut Context) {
+        ctx.device.generate_texture_mipmaps(&self.data.handle);
+        self.data.mipmaps.set(true);
+    }
+
     /// Gets the texture's data from the GPU.
     ///
     /// This can be useful if you need to do some image processing on the CPU,
     /// or if you want to output the image data somewhere. This is a fairly
     /// slow operation, so avoid doing it too often!
+    ///
+    /// If the texture is not in the [`Rgba8`](TextureFormat::Rgba8) format, the data
+    /// will be converted - missing channels will be filled in, and floating point values
+    /// will be clamped to the range of `0.0` to `1.0`. Use [`get_raw_data`](Self::get_raw_data)
+    /// if you need the data in the texture's own format.
     pub fn get_data(&self, ctx: &mut Context) -> ImageData {
         let (width, height) = self.size();
         let buffer = ctx.device.get_texture_data(&self.data.handle);
@@ -329,7 +437,18 @@ impl Texture {
         ImageData::from_rgba8(width, height, buffer).expect("buffer should be exact size for image")
     }
 
-    /// Writes RGBA pixel data to a specified region of the texture.
+    /// Gets the texture's data from the GPU, in the texture's [format](Self::format).
+    ///
+    /// The data will be laid out as described in the docs for [`TextureFormat`]. This is a
+    /// fairly slow operation, so avoid doing it too often!
+    pub fn get_raw_data(&self, ctx: &mut Context) -> Vec<u8> {
+        ctx.device.get_texture_raw_data(&self.data.handle)
+    }
+
+    /// Writes pixel data to a specified region of the texture.
+    ///
+    /// The data should be in the texture's [format](Self::format), laid out as described
+    /// in the docs for [`TextureFormat`] (for the default format, this is RGBA8).
     ///
     /// This method requires you to provide enough data to fill the target rectangle.
     /// If you provide too little data, an error will be returned.
@@ -357,10 +476,19 @@ impl Texture {
         data: &[u8],
     ) -> Result {
         ctx.device
-            .set_texture_data(&self.data.handle, &data, x, y, width, height)
+            .set_texture_data(&self.data.handle, &data, x, y, width, height)?;
+
+        if self.has_mipmaps() {
+            ctx.device.generate_texture_mipmaps(&self.data.handle);
+        }
+
+        Ok(())
     }
 
-    /// Overwrites the entire texture with new RGBA pixel data.
+    /// Overwrites the entire texture with new pixel data.
+    ///
+    /// The data should be in the texture's [format](Self::format), laid out as described
+    /// in the docs for [`TextureFormat`] (for the default format, this is RGBA8).
     ///
     /// This method requires you to provide enough data to fill the texture.
     /// If you provide too little data, an error will be returned.
@@ -391,6 +519,124 @@ pub enum FilterMode {
 
     /// Linear interpolation. This smooths images when scaling them up or down.
     Linear,
+
+    /// Nearest-neighbor interpolation, with linear interpolation between mipmap levels.
+    ///
+    /// This keeps pixel art crisp when it is scaled up, while reducing shimmering when
+    /// it is scaled down. If the texture does not have mipmaps, this behaves the same
+    /// as `Nearest`.
+    NearestMipmap,
+
+    /// Linear interpolation, both within and between mipmap levels (also known as
+    /// trilinear filtering).
+    ///
+    /// This gives the smoothest results when scaling a texture down. If the texture
+    /// does not have mipmaps, this behaves the same as `Linear`.
+    Trilinear,
+}
+
+/// Formats that a texture's pixel data can be stored in.
+///
+/// Pixel data is always laid out row by row, starting from the top left of the texture,
+/// with no padding between rows. The number of bytes used by each pixel is given by
+/// [`bytes_per_pixel`](Self::bytes_per_pixel).
+///
+/// Formats with fewer than four channels will be sampled in shaders with the missing
+/// color channels set to zero, and the alpha channel set to one.
+///
+/// Tetra currently defaults to using `Rgba8` for all newly created textures.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TextureFormat {
+    /// A single 8-bit channel.
+    ///
+    /// This can be useful for things like masks, as it uses a quarter of the memory of `Rgba8`.
+    R8,
+
+    /// Two 8-bit channels.
+    Rg8,
+
+    /// Four 8-bit channels (red, green, blue and alpha).
+    Rgba8,
+
+    /// Four 16-bit floating point channels, stored as half-precision floats.
+    ///
+    /// This can store values outside of the `0.0` to `1.0` range, making it useful for
+    /// HDR rendering (e.g. accumulating light).
+    Rgba16F,
+
+    /// Four 32-bit floating point channels, stored as native endian `f32`s.
+    ///
+    /// This can be useful for storing arbitrary data for use in shaders.
+    Rgba32F,
+
+    /// Four 8-bit channels, with the color channels stored in the sRGB color space.
+    ///
+    /// The colors will be converted to linear space when sampled in a shader.
+    Srgba8,
+}
+
+impl TextureFormat {
+    /// Returns the number of bytes used to store each pixel in this format.
+    pub fn bytes_per_pixel(self) -> usize {
+        match self {
+            TextureFormat::R8 => 1,
+            TextureFormat::Rg8 => 2,
+            TextureFormat::Rgba8 | TextureFormat::Srgba8 => 4,
+            TextureFormat::Rgba16F => 8,
+            TextureFormat::Rgba32F => 16,
+        }
+    }
+}
+
+/// Algorithms that can be used when sampling a texture outside of its bounds.
+///
+/// Tetra currently defaults to using `ClampToEdge` on both axes for all newly created
+/// textures.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WrapMode {
+    /// The edge pixels of the texture will be stretched out infinitely.
+    ClampToEdge,
+
+    /// The texture's [border color](Texture::set_border_color) will be used.
+    ClampToBorder,
+
+    /// The texture will be tiled.
+    ///
+    /// This is useful for things like scrolling backgrounds.
+    Repeat,
+
+    /// The texture will be tiled, with every other repetition being mirrored.
+    MirroredRepeat,
+}
+
+/// Ways of filling the edges and center of a [`NineSlice`].
+///
+/// # Serde
+///
+/// Serialization and deserialization of this type (via [Serde](https://serde.rs/))
+/// can be enabled via the `serde_support` feature.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde_support",
+    derive(serde::Serialize, serde::Deserialize)
+)]
+pub enum SliceMode {
+    /// The slice will be stretched to fill the available space.
+    Stretch,
+
+    /// The slice will be repeated at its native size. If the available space is not an exact
+    /// multiple of the slice's size, the final repetition will be cut off.
+    Tile,
+
+    /// The slice will be repeated a whole number of times, and then stretched or squashed
+    /// slightly to fill the available space.
+    TileFit,
+}
+
+impl Default for SliceMode {
+    fn default() -> Self {
+        SliceMode::Stretch
+    }
 }
 
 /// Information on how to slice a texture so that it can be stretched or squashed without
@@ -398,6 +644,10 @@ pub enum FilterMode {
 ///
 /// This can be used with [`Texture::draw_nine_slice`] to easily draw things like UI panels.
 ///
+/// By default, the edges and center of the nine slice will be stretched to fill the
+/// space - this can be changed via the [`SliceMode`] fields, which is useful for textures with
+/// patterned borders (e.g. pixel art UI panels).
+///
 /// # Examples
 ///
 /// The [`nineslice`](https://github.com/17cupsofcoffee/tetra/blob/main/examples/nineslice.rs)
@@ -418,6 +668,26 @@ pub struct NineSlice {
 
     /// The offset of the border on the bottom side.
     pub bottom: f32,
+
+    /// How the left edge should fill the available space.
+    pub left_mode: SliceMode,
+
+    /// How the right edge should fill the available space.
+    pub right_mode: SliceMode,
+
+    /// How the top edge should fill the available space.
+    pub top_mode: SliceMode,
+
+    /// How the bottom edge should fill the available space.
+    pub bottom_mode: SliceMode,
+
+    /// How the center should fill the available space.
+    pub center_mode: SliceMode,
+
+    /// Whether the center should be drawn.
+    ///
+    /// Disabling this can be useful for drawing frames around other content.
+    pub draw_center: bool,
 }
 
 impl NineSlice {
@@ -429,19 +699,93 @@ impl NineSlice {
             right,
             top,
             bottom,
+            left_mode: SliceMode::Stretch,
+            right_mode: SliceMode::Stretch,
+            top_mode: SliceMode::Stretch,
+            bottom_mode: SliceMode::Stretch,
+            center_mode: SliceMode::Stretch,
+            draw_center: true,
         }
     }
 
     /// Creates a new nine slice configuration, using the same offset for all edges.
     pub fn with_border(region: Rectangle, border: f32) -> NineSlice {
-        NineSlice {
-            region,
-            left: border,
-            right: border,
-            top: border,
-            bottom: border,
+        NineSlice::new(region, border, border, border, border)
+    }
+
+    /// Sets how all four edges should fill the available space.
+    pub fn edge_mode(mut self, mode: SliceMode) -> NineSlice {
+        self.left_mode = mode;
+        self.right_mode = mode;
+        self.top_mode = mode;
+        self.bottom_mode = mode;
+        self
+    }
+
+    /// Sets how the center should fill the available space.
+    pub fn center_mode(mut self, mode: SliceMode) -> NineSlice {
+        self.center_mode = mode;
+        self
+    }
+
+    /// Sets whether the center should be drawn.
+    pub fn draw_center(mut self, draw_center: bool) -> NineSlice {
+        self.draw_center = draw_center;
+        self
+    }
+}
+
+/// One row or column of a nine slice.
+struct Slice {
+    start: f32,
+    end: f32,
+    uv_start: f32,
+    uv_end: f32,
+    source_size: f32,
+}
+
+impl Slice {
+    fn new(start: f32, end: f32, uv_start: f32, uv_end: f32, source_size: f32) -> Slice {
+        Slice {
+            start,
+            end,
+            uv_start,
+            uv_end,
+            source_size,
         }
     }
+
+    /// Returns the positions and UVs of the quads needed to fill the slice.
+    fn segments(&self, mode: SliceMode) -> impl Iterator<Item = (f32, f32, f32, f32)> + '_ {
+        let length = self.end - self.start;
+
+        let (size, count) = match mode {
+            _ if length <= 0.0 => (0.0, 0),
+            SliceMode::Tile if self.source_size > 0.0 => {
+                // Small rounding errors shouldn't cause a sliver of an extra tile to be drawn.
+                let count = (length / self.source_size - 0.001).ceil().max(1.0);
+                (self.source_size, count as usize)
+            }
+            SliceMode::TileFit if self.source_size > 0.0 => {
+                let count = (length / self.source_size).round().max(1.0);
+                (length / count, count as usize)
+            }
+            _ => (length, 1),
+        };
+
+        (0..count).map(move |i| {
+            let start = i as f32 * size;
+            let end = (start + size).min(length);
+            let fraction = (end - start) / size;
+
+            (
+                self.start + start,
+                self.start + end,
+                self.uv_start,
+                self.uv_start + (self.uv_end - self.uv_start) * fraction,
+            )
+        })
+    }
 }
 
 /// Raw image data.
@@ -644,3 +988,56 @@ impl ImageData {
         self.transform(|_, color| color.to_premultiplied())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn segments(length: f32, source_size: f32, mode: SliceMode) -> Vec<(f32, f32, f32, f32)> {
+        Slice::new(10.0, 10.0 + length, 0.0, 1.0, source_size)
+            .segments(mode)
+            .collect()
+    }
+
+    #[test]
+    fn stretch_slice() {
+        assert_eq!(
+            segments(50.0, 20.0, SliceMode::Stretch),
+            vec![(10.0, 60.0, 0.0, 1.0)]
+        );
+    }
+
+    #[test]
+    fn tile_slice() {
+        assert_eq!(
+            segments(50.0, 20.0, SliceMode::Tile),
+            vec![
+                (10.0, 30.0, 0.0, 1.0),
+                (30.0, 50.0, 0.0, 1.0),
+                (50.0, 60.0, 0.0, 0.5),
+            ]
+        );
+
+        assert_eq!(segments(40.0, 20.0, SliceMode::Tile).len(), 2);
+    }
+
+    #[test]
+    fn tile_fit_slice() {
+        assert_eq!(
+            segments(45.0, 20.0, SliceMode::TileFit),
+            vec![(10.0, 32.5, 0.0, 1.0), (32.5, 55.0, 0.0, 1.0)]
+        );
+
+        assert_eq!(
+            segments(5.0, 20.0, SliceMode::TileFit),
+            vec![(10.0, 15.0, 0.0, 1.0)]
+        );
+    }
+
+    #[test]
+    fn empty_slice() {
+        assert!(segments(0.0, 20.0, SliceMode::Tile).is_empty());
+        assert!(segments(-5.0, 20.0, SliceMode::Stretch).is_empty());
+        assert_eq!(segments(50.0, 0.0, SliceMode::Tile).len(), 1);
+    }
+}
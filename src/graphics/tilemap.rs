@@ -0,0 +1,1215 @@
+//! Functions and types relating to tile-based maps.
+//!
+//! Maps can be loaded from the [Tiled](https://www.mapeditor.org/) editor's TMX (XML)
+//! and JSON formats, via [`MapData`]. Support for each format is enabled by the
+//! `tilemap_tmx` and `tilemap_json` feature flags respectively.
+//!
+//! The loaded data is plain Rust structs, so object layers, properties and tile metadata
+//! can be read directly by your game code. To draw a map's tile layers, load it
+//! into a [`Tilemap`], which will upload the tiles to the GPU as a set of static,
+//! chunked meshes.
+
+#[cfg(any(feature = "tilemap_tmx", feature = "tilemap_json"))]
+mod encoding;
+#[cfg(feature = "tilemap_json")]
+mod json;
+#[cfg(feature = "tilemap_tmx")]
+mod tmx;
+
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use hashbrown::HashMap;
+
+use crate::context::Context;
+use crate::graphics::mesh::{BufferUsage, IndexBuffer, Mesh, Vertex, VertexBuffer};
+use crate::graphics::{Color, DrawParams, Rectangle, Texture};
+use crate::math::Vec2;
+use crate::time;
+use crate::{Result, TetraError};
+
+const FLIPPED_HORIZONTALLY: u32 = 0x8000_0000;
+const FLIPPED_VERTICALLY: u32 = 0x4000_0000;
+const FLIPPED_DIAGONALLY: u32 = 0x2000_0000;
+const ROTATED_HEXAGONAL: u32 = 0x1000_0000;
+const GID_MASK: u32 =
+    !(FLIPPED_HORIZONTALLY | FLIPPED_VERTICALLY | FLIPPED_DIAGONALLY | ROTATED_HEXAGONAL);
+
+/// The width and height of each chunk of a [`Tilemap`], in tiles.
+pub const CHUNK_SIZE: u32 = 16;
+
+/// A collection of custom properties, as defined in Tiled.
+pub type Properties = HashMap<String, PropertyValue>;
+
+/// The value of a custom property.
+#[derive(Debug, Clone, PartialEq)]
+pub enum PropertyValue {
+    /// A string property.
+    String(String),
+
+    /// An integer property.
+    Int(i64),
+
+    /// A floating point property.
+    Float(f64),
+
+    /// A boolean property.
+    Bool(bool),
+
+    /// A color property.
+    Color(Color),
+
+    /// A file property. The path is relative to the map or tileset that it was defined in.
+    File(String),
+
+    /// A reference to an object, by ID. An ID of `0` means that no object was selected.
+    Object(u32),
+}
+
+/// The orientation of a map.
+///
+/// Only orthogonal maps can currently be drawn by a [`Tilemap`], but the data
+/// for other orientations can still be loaded.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Orientation {
+    /// A map made up of square or rectangular tiles.
+    Orthogonal,
+
+    /// A map made up of diamond-shaped tiles.
+    Isometric,
+
+    /// An isometric map where every other row is offset.
+    Staggered,
+
+    /// A map made up of hexagonal tiles.
+    Hexagonal,
+}
+
+/// A single tile within a tile layer or tile object.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Tile {
+    /// The global ID of the tile, with the flip flags removed.
+    ///
+    /// This can be mapped back to a tileset via [`MapData::tileset_for_gid`].
+    pub gid: u32,
+
+    /// Whether the tile is flipped horizontally.
+    pub flip_horizontal: bool,
+
+    /// Whether the tile is flipped vertically.
+    pub flip_vertical: bool,
+
+    /// Whether the tile is flipped diagonally (i.e. its X and Y axes are swapped).
+    ///
+    /// This is applied before the horizontal and vertical flips, and is how Tiled
+    /// represents rotated tiles.
+    pub flip_diagonal: bool,
+}
+
+impl Tile {
+    /// Decodes a tile from a raw global ID, as stored in Tiled's map formats.
+    ///
+    /// Returns [`None`] if the ID represents an empty tile.
+    pub fn from_raw_gid(raw: u32) -> Option<Tile> {
+        let gid = raw & GID_MASK;
+
+        if gid == 0 {
+            return None;
+        }
+
+        Some(Tile {
+            gid,
+            flip_horizontal: raw & FLIPPED_HORIZONTALLY != 0,
+            flip_vertical: raw & FLIPPED_VERTICALLY != 0,
+            flip_diagonal: raw & FLIPPED_DIAGONALLY != 0,
+        })
+    }
+
+    fn uvs(&self, region: Rectangle, texture_size: Vec2<f32>) -> [Vec2<f32>; 4] {
+        let corners = [
+            Vec2::new(0.0, 0.0),
+            Vec2::new(1.0, 0.0),
+            Vec2::new(1.0, 1.0),
+            Vec2::new(0.0, 1.0),
+        ];
+
+        let mut uvs = [Vec2::zero(); 4];
+
+        for (uv, corner) in uvs.iter_mut().zip(corners.iter()) {
+            let mut sample = *corner;
+
+            if self.flip_horizontal {
+                sample.x = 1.0 - sample.x;
+            }
+
+            if self.flip_vertical {
+                sample.y = 1.0 - sample.y;
+            }
+
+            if self.flip_diagonal {
+                sample = Vec2::new(sample.y, sample.x);
+            }
+
+            *uv = Vec2::new(
+                (region.x + sample.x * region.width) / texture_size.x,
+                (region.y + sample.y * region.height) / texture_size.y,
+            );
+        }
+
+        uvs
+    }
+}
+
+/// A frame of an animated tile.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Frame {
+    /// The local ID of the tile to display, within the same tileset.
+    pub tile_id: u32,
+
+    /// How long the frame should be displayed for.
+    pub duration: Duration,
+}
+
+/// Extra data attached to a tile in a tileset.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TileData {
+    /// The class (or type) of the tile.
+    pub class: String,
+
+    /// The tile's custom properties.
+    pub properties: Properties,
+
+    /// The frames of the tile's animation. This will be empty if the tile is not animated.
+    pub animation: Vec<Frame>,
+
+    /// The collision shapes that have been defined for the tile, relative to the
+    /// top-left corner of the tile.
+    pub objects: Vec<MapObject>,
+}
+
+/// A set of tiles, sliced from a single image.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Tileset {
+    /// The global ID of the first tile in the tileset.
+    pub first_gid: u32,
+
+    /// The name of the tileset.
+    pub name: String,
+
+    /// The width of each tile, in pixels.
+    pub tile_width: u32,
+
+    /// The height of each tile, in pixels.
+    pub tile_height: u32,
+
+    /// The space between each tile in the image, in pixels.
+    pub spacing: u32,
+
+    /// The space around the edge of the image, in pixels.
+    pub margin: u32,
+
+    /// The number of tiles in the tileset.
+    pub tile_count: u32,
+
+    /// The number of tile columns in the image.
+    pub columns: u32,
+
+    /// The path to the tileset's image.
+    ///
+    /// This will be [`None`] for 'collection of images' tilesets, which are not
+    /// currently supported for rendering.
+    pub image: Option<PathBuf>,
+
+    /// An offset that should be applied when drawing tiles from this tileset, in pixels.
+    pub offset: Vec2<f32>,
+
+    /// Extra data for individual tiles, keyed by their local ID.
+    ///
+    /// Tiles which do not have any properties, animations or collision shapes
+    /// will not be present.
+    pub tiles: HashMap<u32, TileData>,
+
+    /// The tileset's custom properties.
+    pub properties: Properties,
+}
+
+impl Tileset {
+    /// Returns `true` if the given global ID belongs to this tileset.
+    pub fn contains(&self, gid: u32) -> bool {
+        gid >= self.first_gid && gid < self.first_gid + self.tile_count
+    }
+
+    /// Returns the region of the tileset's image that contains the tile with the
+    /// given local ID.
+    pub fn tile_region(&self, tile_id: u32) -> Rectangle {
+        let columns = self.columns.max(1);
+        let column = tile_id % columns;
+        let row = tile_id / columns;
+
+        Rectangle::new(
+            (self.margin + column * (self.tile_width + self.spacing)) as f32,
+            (self.margin + row * (self.tile_height + self.spacing)) as f32,
+            self.tile_width as f32,
+            self.tile_height as f32,
+        )
+    }
+}
+
+/// A layer of tiles, laid out in a grid.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TileLayer {
+    /// The name of the layer.
+    pub name: String,
+
+    /// Whether the layer is visible.
+    pub visible: bool,
+
+    /// The opacity of the layer, between 0.0 and 1.0.
+    pub opacity: f32,
+
+    /// An offset that should be applied when drawing the layer, in pixels.
+    pub offset: Vec2<f32>,
+
+    /// The position of the top-left tile in the layer, in tiles.
+    ///
+    /// This will always be zero for fixed-size maps, but may be negative for infinite maps.
+    pub origin: Vec2<i32>,
+
+    /// The width of the layer, in tiles.
+    pub width: u32,
+
+    /// The height of the layer, in tiles.
+    pub height: u32,
+
+    /// The tiles in the layer, in row-major order.
+    pub tiles: Vec<Option<Tile>>,
+
+    /// The layer's custom properties.
+    pub properties: Properties,
+}
+
+impl TileLayer {
+    /// Returns the tile at the given position (in tiles), if there is one.
+    ///
+    /// The position is relative to the map, so it takes the layer's
+    /// [`origin`](Self::origin) into account.
+    pub fn get(&self, x: i32, y: i32) -> Option<Tile> {
+        let x = x - self.origin.x;
+        let y = y - self.origin.y;
+
+        if x < 0 || y < 0 || x as u32 >= self.width || y as u32 >= self.height {
+            return None;
+        }
+
+        let index = y as usize * self.width as usize + x as usize;
+        self.tiles.get(index).copied().flatten()
+    }
+}
+
+/// The shape of an object.
+///
+/// All co-ordinates are in pixels, relative to the map (or to the tile,
+/// for a tile's collision shapes).
+#[derive(Debug, Clone, PartialEq)]
+pub enum ObjectShape {
+    /// A rectangle.
+    Rectangle(Rectangle),
+
+    /// An ellipse, contained within the given bounds.
+    Ellipse(Rectangle),
+
+    /// A single point.
+    Point(Vec2<f32>),
+
+    /// A closed polygon.
+    Polygon(Vec<Vec2<f32>>),
+
+    /// An open line made up of multiple points.
+    Polyline(Vec<Vec2<f32>>),
+}
+
+/// An object placed on an object layer.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MapObject {
+    /// The unique ID of the object.
+    pub id: u32,
+
+    /// The name of the object.
+    pub name: String,
+
+    /// The class (or type) of the object.
+    pub class: String,
+
+    /// The position of the object, in pixels.
+    ///
+    /// For tile objects, this is the bottom-left corner of the tile. For all other
+    /// objects, this is the top-left corner.
+    pub position: Vec2<f32>,
+
+    /// The rotation of the object around its position, in degrees.
+    ///
+    /// This is not applied to the [`shape`](Self::shape).
+    pub rotation: f32,
+
+    /// Whether the object is visible.
+    pub visible: bool,
+
+    /// The tile that should be displayed for the object, if it is a tile object.
+    pub tile: Option<Tile>,
+
+    /// The shape of the object.
+    pub shape: ObjectShape,
+
+    /// The object's custom properties.
+    pub properties: Properties,
+}
+
+/// A layer of free-form objects.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ObjectLayer {
+    /// The name of the layer.
+    pub name: String,
+
+    /// Whether the layer is visible.
+    pub visible: bool,
+
+    /// The opacity of the layer, between 0.0 and 1.0.
+    pub opacity: f32,
+
+    /// An offset that should be applied to the layer's objects, in pixels.
+    pub offset: Vec2<f32>,
+
+    /// The color used to display the objects in the editor, if one was set.
+    pub color: Option<Color>,
+
+    /// The objects in the layer.
+    pub objects: Vec<MapObject>,
+
+    /// The layer's custom properties.
+    pub properties: Properties,
+}
+
+/// A layer that contains other layers.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GroupLayer {
+    /// The name of the layer.
+    pub name: String,
+
+    /// Whether the layer is visible.
+    ///
+    /// This also affects the visibility of the child layers.
+    pub visible: bool,
+
+    /// The opacity of the layer, between 0.0 and 1.0.
+    ///
+    /// This is multiplied with the opacity of the child layers.
+    pub opacity: f32,
+
+    /// An offset that should be applied to the child layers, in pixels.
+    pub offset: Vec2<f32>,
+
+    /// The child layers.
+    pub layers: Vec<Layer>,
+
+    /// The layer's custom properties.
+    pub properties: Properties,
+}
+
+/// A layer within a map.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Layer {
+    /// A layer of tiles.
+    Tile(TileLayer),
+
+    /// A layer of objects.
+    Object(ObjectLayer),
+
+    /// A group of layers.
+    Group(GroupLayer),
+}
+
+impl Layer {
+    /// Returns the name of the layer.
+    pub fn name(&self) -> &str {
+        match self {
+            Layer::Tile(layer) => &layer.name,
+            Layer::Object(layer) => &layer.name,
+            Layer::Group(layer) => &layer.name,
+        }
+    }
+
+    /// Returns the layer's custom properties.
+    pub fn properties(&self) -> &Properties {
+        match self {
+            Layer::Tile(layer) => &layer.properties,
+            Layer::Object(layer) => &layer.properties,
+            Layer::Group(layer) => &layer.properties,
+        }
+    }
+}
+
+/// The data for a map, loaded from one of Tiled's file formats.
+///
+/// Image layers are not currently supported, and will be skipped when loading.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MapData {
+    /// The orientation of the map.
+    pub orientation: Orientation,
+
+    /// The width of the map, in tiles.
+    ///
+    /// For infinite maps, this is the size of the area that was visible in the editor.
+    pub width: u32,
+
+    /// The height of the map, in tiles.
+    ///
+    /// For infinite maps, this is the size of the area that was visible in the editor.
+    pub height: u32,
+
+    /// The width of each tile in the map's grid, in pixels.
+    pub tile_width: u32,
+
+    /// The height of each tile in the map's grid, in pixels.
+    pub tile_height: u32,
+
+    /// Whether the map is infinite.
+    pub infinite: bool,
+
+    /// The background color of the map, if one was set.
+    pub background_color: Option<Color>,
+
+    /// The tilesets used by the map, in order of their first global ID.
+    pub tilesets: Vec<Tileset>,
+
+    /// The layers of the map, from bottom to top.
+    pub layers: Vec<Layer>,
+
+    /// The map's custom properties.
+    pub properties: Properties,
+}
+
+impl MapData {
+    /// Loads a map from the given file.
+    ///
+    /// The format will be determined based on the file extension - `.tmx` files are
+    /// loaded as XML, and `.json`/`.tmj` files are loaded as JSON. Any external
+    /// tilesets will be loaded relative to the map's directory.
+    ///
+    /// # Errors
+    ///
+    /// * [`TetraError::FailedToLoadAsset`] will be returned if the map or one of its
+    /// tilesets could not be loaded.
+    /// * [`TetraError::InvalidTilemap`] will be returned if the data was invalid, or if
+    /// the format is not supported (e.g. because the corresponding feature flag is disabled).
+    pub fn new<P>(path: P) -> Result<MapData>
+    where
+        P: AsRef<Path>,
+    {
+        let path = path.as_ref();
+
+        match extension(path).as_deref() {
+            #[cfg(feature = "tilemap_tmx")]
+            Some("tmx") => MapData::from_tmx(&crate::fs::read_to_string(path)?, base_dir(path)),
+
+            #[cfg(feature = "tilemap_json")]
+            Some("json") | Some("tmj") => {
+                MapData::from_json(&crate::fs::read_to_string(path)?, base_dir(path))
+            }
+
+            _ => Err(unsupported_format(path)),
+        }
+    }
+
+    /// Loads a map from a string containing TMX (XML) data.
+    ///
+    /// Any external tilesets and images will be loaded relative to `base_dir`.
+    ///
+    /// # Errors
+    ///
+    /// * [`TetraError::FailedToLoadAsset`] will be returned if one of the map's
+    /// tilesets could not be loaded.
+    /// * [`TetraError::InvalidTilemap`] will be returned if the data was invalid.
+    #[cfg(feature = "tilemap_tmx")]
+    pub fn from_tmx<P>(data: &str, base_dir: P) -> Result<MapData>
+    where
+        P: AsRef<Path>,
+    {
+        tmx::parse_map(data, base_dir.as_ref())
+    }
+
+    /// Loads a map from a string containing JSON data.
+    ///
+    /// Any external tilesets and images will be loaded relative to `base_dir`.
+    ///
+    /// # Errors
+    ///
+    /// * [`TetraError::FailedToLoadAsset`] will be returned if one of the map's
+    /// tilesets could not be loaded.
+    /// * [`TetraError::InvalidTilemap`] will be returned if the data was invalid.
+    #[cfg(feature = "tilemap_json")]
+    pub fn from_json<P>(data: &str, base_dir: P) -> Result<MapData>
+    where
+        P: AsRef<Path>,
+    {
+        json::parse_map(data, base_dir.as_ref())
+    }
+
+    /// Returns the tileset that contains the tile with the given global ID.
+    pub fn tileset_for_gid(&self, gid: u32) -> Option<&Tileset> {
+        self.tilesets.iter().rev().find(|t| t.first_gid <= gid)
+    }
+
+    /// Finds the first layer with the given name, searching inside groups.
+    pub fn layer(&self, name: &str) -> Option<&Layer> {
+        find_layer(&self.layers, name)
+    }
+
+    /// Finds the first tile layer with the given name, searching inside groups.
+    pub fn tile_layer(&self, name: &str) -> Option<&TileLayer> {
+        match self.layer(name)? {
+            Layer::Tile(layer) => Some(layer),
+            _ => None,
+        }
+    }
+
+    /// Finds the first object layer with the given name, searching inside groups.
+    pub fn object_layer(&self, name: &str) -> Option<&ObjectLayer> {
+        match self.layer(name)? {
+            Layer::Object(layer) => Some(layer),
+            _ => None,
+        }
+    }
+
+    /// Returns an iterator over all of the objects in the map, searching inside groups.
+    pub fn objects(&self) -> impl Iterator<Item = &MapObject> {
+        let mut layers = Vec::new();
+        collect_object_layers(&self.layers, &mut layers);
+
+        layers.into_iter().flat_map(|l| l.objects.iter())
+    }
+}
+
+fn find_layer<'a>(layers: &'a [Layer], name: &str) -> Option<&'a Layer> {
+    for layer in layers {
+        if layer.name() == name {
+            return Some(layer);
+        }
+
+        if let Layer::Group(group) = layer {
+            if let Some(found) = find_layer(&group.layers, name) {
+                return Some(found);
+            }
+        }
+    }
+
+    None
+}
+
+fn collect_object_layers<'a>(layers: &'a [Layer], out: &mut Vec<&'a ObjectLayer>) {
+    for layer in layers {
+        match layer {
+            Layer::Object(object_layer) => out.push(object_layer),
+            Layer::Group(group) => collect_object_layers(&group.layers, out),
+            Layer::Tile(_) => {}
+        }
+    }
+}
+
+fn extension(path: &Path) -> Option<String> {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .map(|e| e.to_ascii_lowercase())
+}
+
+fn unsupported_format(path: &Path) -> TetraError {
+    TetraError::InvalidTilemap(format!(
+        "unsupported file format for {} (is the corresponding feature flag enabled?)",
+        path.to_string_lossy()
+    ))
+}
+
+#[cfg(any(feature = "tilemap_tmx", feature = "tilemap_json"))]
+fn base_dir(path: &Path) -> &Path {
+    path.parent().unwrap_or_else(|| Path::new(""))
+}
+
+#[cfg(any(feature = "tilemap_tmx", feature = "tilemap_json"))]
+fn load_external_tileset(path: &Path, first_gid: u32) -> Result<Tileset> {
+    let base_dir = base_dir(path);
+
+    match extension(path).as_deref() {
+        #[cfg(feature = "tilemap_tmx")]
+        Some("tsx") => tmx::parse_tileset(&crate::fs::read_to_string(path)?, first_gid, base_dir),
+
+        #[cfg(feature = "tilemap_json")]
+        Some("json") | Some("tsj") => {
+            json::parse_tileset(&crate::fs::read_to_string(path)?, first_gid, base_dir)
+        }
+
+        _ => Err(unsupported_format(path)),
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Chunk {
+    bounds: Rectangle,
+    meshes: Vec<Mesh>,
+}
+
+#[derive(Debug, Clone)]
+struct AnimatedTile {
+    tileset: usize,
+    tile: Tile,
+    bounds: Rectangle,
+}
+
+#[derive(Debug, Clone)]
+struct RenderLayer {
+    name: String,
+    visible: bool,
+    color: Color,
+    chunks: Vec<Chunk>,
+    animated: Vec<AnimatedTile>,
+}
+
+/// A map that has been uploaded to the GPU, ready to be drawn.
+///
+/// Each tile layer is split into chunks of [`CHUNK_SIZE`] x [`CHUNK_SIZE`] tiles,
+/// and each chunk is stored as a static [`Mesh`] per tileset. When drawing, only the
+/// chunks that overlap the visible area will be rendered - usually this will be
+/// the area returned by [`Camera::visible_rect`](super::Camera::visible_rect).
+///
+/// Animated tiles are not included in the static meshes - instead, they are drawn via
+/// the sprite batcher, using the current frame of their animation. Call
+/// [`advance`](Self::advance) once per update to keep them moving.
+///
+/// Only orthogonal maps are supported. Tilesets without a single image (i.e. 'collection
+/// of images' tilesets) will be skipped.
+///
+/// # Performance
+///
+/// Creating a `Tilemap` is a relatively expensive operation, as the tileset textures
+/// must be loaded and the chunk meshes built. If you can, store them in your
+/// [`State`](crate::State) struct rather than recreating them each frame.
+///
+/// # Examples
+///
+/// The [`tilemap`](https://github.com/17cupsofcoffee/tetra/blob/main/examples/tilemap.rs)
+/// example demonstrates how to load and draw a map, and how to read its object layers.
+#[derive(Debug, Clone)]
+pub struct Tilemap {
+    data: MapData,
+    textures: Vec<Option<Texture>>,
+    layers: Vec<RenderLayer>,
+    timer: Duration,
+}
+
+impl Tilemap {
+    /// Loads a map from the given file, along with the textures for its tilesets.
+    ///
+    /// See [`MapData::new`] for details of which formats are supported.
+    ///
+    /// # Errors
+    ///
+    /// * [`TetraError::FailedToLoadAsset`] will be returned if the map, one of its tilesets
+    /// or one of its images could not be loaded.
+    /// * [`TetraError::InvalidTilemap`] will be returned if the map's data was invalid or unsupported.
+    /// * [`TetraError::InvalidTexture`] will be returned if a tileset's image was invalid.
+    /// * [`TetraError::PlatformError`] will be returned if the GPU buffers could not be created.
+    pub fn new<P>(ctx: &mut Context, path: P) -> Result<Tilemap>
+    where
+        P: AsRef<Path>,
+    {
+        let data = MapData::new(path)?;
+        Tilemap::from_data(ctx, data)
+    }
+
+    /// Creates a tilemap from previously loaded map data, loading the textures for
+    /// its tilesets.
+    ///
+    /// # Errors
+    ///
+    /// * [`TetraError::FailedToLoadAsset`] will be returned if one of the tileset images could
+    /// not be loaded.
+    /// * [`TetraError::InvalidTilemap`] will be returned if the map is not orthogonal.
+    /// * [`TetraError::InvalidTexture`] will be returned if a tileset's image was invalid.
+    /// * [`TetraError::PlatformError`] will be returned if the GPU buffers could not be created.
+    pub fn from_data(ctx: &mut Context, data: MapData) -> Result<Tilemap> {
+        let textures = data
+            .tilesets
+            .iter()
+            .map(|tileset| match &tileset.image {
+                Some(image) => Texture::new(ctx, image).map(Some),
+                None => Ok(None),
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Tilemap::with_textures(ctx, data, textures)
+    }
+
+    /// Creates a tilemap from previously loaded map data, using the given textures
+    /// for its tilesets.
+    ///
+    /// This can be useful if you have already loaded the textures (e.g. from a texture atlas,
+    /// or from memory). There should be one entry per tileset, in the same order as
+    /// [`MapData::tilesets`] - tilesets with no texture will not be drawn.
+    ///
+    /// # Errors
+    ///
+    /// * [`TetraError::InvalidTilemap`] will be returned if the map is not orthogonal.
+    /// * [`TetraError::PlatformError`] will be returned if the GPU buffers could not be created.
+    pub fn with_textures(
+        ctx: &mut Context,
+        data: MapData,
+        textures: Vec<Option<Texture>>,
+    ) -> Result<Tilemap> {
+        if data.orientation != Orientation::Orthogonal {
+            return Err(TetraError::InvalidTilemap(format!(
+                "{:?} maps cannot be drawn, only orthogonal maps are supported",
+                data.orientation
+            )));
+        }
+
+        let mut layers = Vec::new();
+
+        build_layers(
+            ctx,
+            &data,
+            &textures,
+            &data.layers,
+            Vec2::zero(),
+            1.0,
+            true,
+            &mut layers,
+        )?;
+
+        Ok(Tilemap {
+            data,
+            textures,
+            layers,
+            timer: Duration::from_secs(0),
+        })
+    }
+
+    /// Draws all of the map's visible tile layers to the screen (or to a canvas, if
+    /// one is enabled).
+    ///
+    /// Only chunks and animated tiles that overlap `visible_area` will be drawn.
+    /// The map is drawn at the origin, in the same co-ordinate space as the objects
+    /// in the map data - use a [`Camera`](super::Camera) to move around it.
+    pub fn draw(&self, ctx: &mut Context, visible_area: Rectangle) {
+        for layer in &self.layers {
+            if layer.visible {
+                self.draw_render_layer(ctx, layer, visible_area);
+            }
+        }
+    }
+
+    /// Draws a single tile layer to the screen (or to a canvas, if one is enabled).
+    ///
+    /// The layer will be drawn even if it is marked as invisible. Only chunks and
+    /// animated tiles that overlap `visible_area` will be drawn.
+    ///
+    /// Returns `false` if there is no tile layer with the given name.
+    pub fn draw_layer(&self, ctx: &mut Context, name: &str, visible_area: Rectangle) -> bool {
+        match self.layers.iter().find(|l| l.name == name) {
+            Some(layer) => {
+                self.draw_render_layer(ctx, layer, visible_area);
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn draw_render_layer(&self, ctx: &mut Context, layer: &RenderLayer, visible_area: Rectangle) {
+        for chunk in &layer.chunks {
+            if chunk.bounds.intersects(&visible_area) {
+                for mesh in &chunk.meshes {
+                    mesh.draw(ctx, DrawParams::new());
+                }
+            }
+        }
+
+        for animated in &layer.animated {
+            if !animated.bounds.intersects(&visible_area) {
+                continue;
+            }
+
+            let tileset = &self.data.tilesets[animated.tileset];
+
+            if let Some(texture) = &self.textures[animated.tileset] {
+                let local_id = animated.tile.gid - tileset.first_gid;
+                let frame_id = tileset
+                    .tiles
+                    .get(&local_id)
+                    .and_then(|t| current_frame(&t.animation, self.timer))
+                    .unwrap_or(local_id);
+
+                let region = tileset.tile_region(frame_id);
+                let center = animated.bounds.center();
+                let (rotation, scale) = flip_transform(&animated.tile);
+
+                texture.draw_region(
+                    ctx,
+                    region,
+                    DrawParams::new()
+                        .position(center)
+                        .origin(Vec2::new(region.width / 2.0, region.height / 2.0))
+                        .rotation(rotation)
+                        .scale(scale)
+                        .color(layer.color),
+                );
+            }
+        }
+    }
+
+    /// Advances the map's tile animations.
+    ///
+    /// This method uses the current [delta time](crate::time::get_delta_time)
+    /// to calculate how much time has passed.
+    pub fn advance(&mut self, ctx: &Context) {
+        self.advance_by(time::get_delta_time(ctx));
+    }
+
+    /// Advances the map's tile animations by a specified amount.
+    pub fn advance_by(&mut self, duration: Duration) {
+        self.timer += duration;
+    }
+
+    /// Restarts the map's tile animations.
+    pub fn restart(&mut self) {
+        self.timer = Duration::from_secs(0);
+    }
+
+    /// Returns a reference to the map's data.
+    ///
+    /// This can be used to access the object layers and properties of the map.
+    pub fn data(&self) -> &MapData {
+        &self.data
+    }
+
+    /// Returns the texture used for the tileset at the given index, if one was loaded.
+    pub fn texture(&self, tileset: usize) -> Option<&Texture> {
+        self.textures.get(tileset).and_then(|t| t.as_ref())
+    }
+
+    /// Returns the size of the map, in pixels.
+    ///
+    /// For infinite maps, this is the size of the area that was visible in the editor.
+    pub fn size(&self) -> Vec2<f32> {
+        Vec2::new(
+            (self.data.width * self.data.tile_width) as f32,
+            (self.data.height * self.data.tile_height) as f32,
+        )
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn build_layers(
+    ctx: &mut Context,
+    data: &MapData,
+    textures: &[Option<Texture>],
+    layers: &[Layer],
+    offset: Vec2<f32>,
+    opacity: f32,
+    visible: bool,
+    out: &mut Vec<RenderLayer>,
+) -> Result {
+    for layer in layers {
+        match layer {
+            Layer::Tile(tile_layer) => {
+                out.push(build_tile_layer(
+                    ctx,
+                    data,
+                    textures,
+                    tile_layer,
+                    offset + tile_layer.offset,
+                    opacity * tile_layer.opacity,
+                    visible && tile_layer.visible,
+                )?);
+            }
+
+            Layer::Group(group) => {
+                build_layers(
+                    ctx,
+                    data,
+                    textures,
+                    &group.layers,
+                    offset + group.offset,
+                    opacity * group.opacity,
+                    visible && group.visible,
+                    out,
+                )?;
+            }
+
+            Layer::Object(_) => {}
+        }
+    }
+
+    Ok(())
+}
+
+fn build_tile_layer(
+    ctx: &mut Context,
+    data: &MapData,
+    textures: &[Option<Texture>],
+    layer: &TileLayer,
+    offset: Vec2<f32>,
+    opacity: f32,
+    visible: bool,
+) -> Result<RenderLayer> {
+    let color = Color::WHITE.with_alpha(opacity);
+
+    let mut chunks = Vec::new();
+    let mut animated = Vec::new();
+
+    let chunks_x = layer.width.div_ceil(CHUNK_SIZE);
+    let chunks_y = layer.height.div_ceil(CHUNK_SIZE);
+
+    for chunk_y in 0..chunks_y {
+        for chunk_x in 0..chunks_x {
+            // One set of geometry per tileset, as each mesh can only have a single texture.
+            let mut geometry: Vec<(Vec<Vertex>, Vec<u32>)> =
+                vec![Default::default(); textures.len()];
+            let mut bounds: Option<Rectangle> = None;
+
+            let start_x = chunk_x * CHUNK_SIZE;
+            let start_y = chunk_y * CHUNK_SIZE;
+            let end_x = (start_x + CHUNK_SIZE).min(layer.width);
+            let end_y = (start_y + CHUNK_SIZE).min(layer.height);
+
+            for y in start_y..end_y {
+                for x in start_x..end_x {
+                    let index = y as usize * layer.width as usize + x as usize;
+
+                    let tile = match layer.tiles.get(index).copied().flatten() {
+                        Some(tile) => tile,
+                        None => continue,
+                    };
+
+                    let index = match data.tilesets.iter().rposition(|t| t.first_gid <= tile.gid) {
+                        Some(index) => index,
+                        None => continue,
+                    };
+
+                    let tileset = &data.tilesets[index];
+
+                    let texture = match &textures.get(index) {
+                        Some(Some(texture)) => texture,
+                        _ => continue,
+                    };
+
+                    // Tiles that are larger than the grid are aligned to the bottom-left
+                    // of their cell, as in Tiled.
+                    let cell_x = (layer.origin.x + x as i32) as f32 * data.tile_width as f32;
+                    let cell_bottom =
+                        (layer.origin.y + y as i32 + 1) as f32 * data.tile_height as f32;
+
+                    let tile_bounds = Rectangle::new(
+                        cell_x + offset.x + tileset.offset.x,
+                        cell_bottom - tileset.tile_height as f32 + offset.y + tileset.offset.y,
+                        tileset.tile_width as f32,
+                        tileset.tile_height as f32,
+                    );
+
+                    let local_id = tile.gid - tileset.first_gid;
+
+                    let is_animated = tileset
+                        .tiles
+                        .get(&local_id)
+                        .map(|t| !t.animation.is_empty())
+                        .unwrap_or(false);
+
+                    if is_animated {
+                        animated.push(AnimatedTile {
+                            tileset: index,
+                            tile,
+                            bounds: tile_bounds,
+                        });
+
+                        continue;
+                    }
+
+                    let uvs = tile.uvs(
+                        tileset.tile_region(local_id),
+                        Vec2::new(texture.width() as f32, texture.height() as f32),
+                    );
+
+                    let (vertices, indices) = &mut geometry[index];
+                    let first = vertices.len() as u32;
+
+                    let corners = [
+                        tile_bounds.top_left(),
+                        tile_bounds.top_right(),
+                        tile_bounds.bottom_right(),
+                        tile_bounds.bottom_left(),
+                    ];
+
+                    for (position, uv) in corners.iter().zip(uvs.iter()) {
+                        vertices.push(Vertex::new(*position, *uv, color));
+                    }
+
+                    indices.extend_from_slice(&[
+                        first,
+                        first + 1,
+                        first + 2,
+                        first + 2,
+                        first + 3,
+                        first,
+                    ]);
+
+                    bounds = Some(match bounds {
+                        Some(bounds) => bounds.combine(&tile_bounds),
+                        None => tile_bounds,
+                    });
+                }
+            }
+
+            if let Some(bounds) = bounds {
+                let mut meshes = Vec::new();
+
+                for (index, (vertices, indices)) in geometry.iter().enumerate() {
+                    if vertices.is_empty() {
+                        continue;
+                    }
+
+                    let vertex_buffer =
+                        VertexBuffer::with_usage(ctx, vertices, BufferUsage::Static)?;
+                    let index_buffer = IndexBuffer::with_usage(ctx, indices, BufferUsage::Static)?;
+
+                    let mut mesh = Mesh::indexed(vertex_buffer, index_buffer);
+
+                    if let Some(texture) = &textures[index] {
+                        mesh.set_texture(texture.clone());
+                    }
+
+                    meshes.push(mesh);
+                }
+
+                chunks.push(Chunk { bounds, meshes });
+            }
+        }
+    }
+
+    Ok(RenderLayer {
+        name: layer.name.clone(),
+        visible,
+        color,
+        chunks,
+        animated,
+    })
+}
+
+fn current_frame(frames: &[Frame], timer: Duration) -> Option<u32> {
+    let total: Duration = frames.iter().map(|f| f.duration).sum();
+
+    if total.as_nanos() == 0 {
+        return frames.first().map(|f| f.tile_id);
+    }
+
+    let mut remaining = timer.as_nanos() % total.as_nanos();
+
+    for frame in frames {
+        if remaining < frame.duration.as_nanos() {
+            return Some(frame.tile_id);
+        }
+
+        remaining -= frame.duration.as_nanos();
+    }
+
+    frames.last().map(|f| f.tile_id)
+}
+
+/// Converts a tile's flip flags into an equivalent rotation and scale, for drawing
+/// via `DrawParams`.
+fn flip_transform(tile: &Tile) -> (f32, Vec2<f32>) {
+    let h = if tile.flip_horizontal { -1.0 } else { 1.0 };
+    let v = if tile.flip_vertical { -1.0 } else { 1.0 };
+
+    if tile.flip_diagonal {
+        // Swapping the axes is equivalent to a quarter turn plus a vertical flip.
+        (std::f32::consts::FRAC_PI_2, Vec2::new(v, -h))
+    } else {
+        (0.0, Vec2::new(h, v))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decode_flip_flags() {
+        assert_eq!(Tile::from_raw_gid(0), None);
+        assert_eq!(Tile::from_raw_gid(FLIPPED_HORIZONTALLY), None);
+
+        let tile = Tile::from_raw_gid(5 | FLIPPED_HORIZONTALLY | FLIPPED_DIAGONALLY).unwrap();
+
+        assert_eq!(tile.gid, 5);
+        assert!(tile.flip_horizontal);
+        assert!(!tile.flip_vertical);
+        assert!(tile.flip_diagonal);
+    }
+
+    #[test]
+    fn flipped_uvs() {
+        let region = Rectangle::new(0.0, 0.0, 16.0, 16.0);
+        let size = Vec2::new(32.0, 32.0);
+
+        let tile = Tile::from_raw_gid(1 | FLIPPED_HORIZONTALLY).unwrap();
+        let uvs = tile.uvs(region, size);
+
+        assert_eq!(uvs[0], Vec2::new(0.5, 0.0));
+        assert_eq!(uvs[1], Vec2::new(0.0, 0.0));
+
+        let tile = Tile::from_raw_gid(1 | FLIPPED_DIAGONALLY).unwrap();
+        let uvs = tile.uvs(region, size);
+
+        assert_eq!(uvs[1], Vec2::new(0.0, 0.5));
+        assert_eq!(uvs[3], Vec2::new(0.5, 0.0));
+    }
+
+    #[test]
+    fn tile_regions() {
+        let tileset = Tileset {
+            first_gid: 1,
+            name: String::new(),
+            tile_width: 16,
+            tile_height: 16,
+            spacing: 2,
+            margin: 1,
+            tile_count: 8,
+            columns: 4,
+            image: None,
+            offset: Vec2::zero(),
+            tiles: HashMap::new(),
+            properties: HashMap::new(),
+        };
+
+        assert_eq!(tileset.tile_region(0), Rectangle::new(1.0, 1.0, 16.0, 16.0));
+        assert_eq!(
+            tileset.tile_region(5),
+            Rectangle::new(19.0, 19.0, 16.0, 16.0)
+        );
+        assert!(tileset.contains(8));
+        assert!(!tileset.contains(9));
+    }
+
+    #[test]
+    fn animation_frames() {
+        let frames = [
+            Frame {
+                tile_id: 1,
+                duration: Duration::from_millis(100),
+            },
+            Frame {
+                tile_id: 2,
+                duration: Duration::from_millis(200),
+            },
+        ];
+
+        assert_eq!(current_frame(&frames, Duration::from_millis(0)), Some(1));
+        assert_eq!(current_frame(&frames, Duration::from_millis(150)), Some(2));
+        assert_eq!(current_frame(&frames, Duration::from_millis(350)), Some(1));
+    }
+}
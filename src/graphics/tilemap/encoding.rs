@@ -0,0 +1,272 @@
+//! Decoding of the tile data formats shared between TMX and JSON maps.
+
+use std::convert::TryFrom;
+use std::io::Read;
+
+use flate2::read::{GzDecoder, ZlibDecoder};
+
+use crate::graphics::tilemap::{PropertyValue, Tile, TileLayer};
+use crate::graphics::Color;
+use crate::math::Vec2;
+use crate::{Result, TetraError};
+
+pub(crate) fn invalid<S>(message: S) -> TetraError
+where
+    S: Into<String>,
+{
+    TetraError::InvalidTilemap(message.into())
+}
+
+pub(crate) fn decode_csv(data: &str) -> Result<Vec<Option<Tile>>> {
+    data.split(',')
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(|s| {
+            s.parse::<u32>()
+                .map(Tile::from_raw_gid)
+                .map_err(|_| invalid(format!("invalid tile ID '{}'", s)))
+        })
+        .collect()
+}
+
+pub(crate) fn decode_base64(data: &str, compression: Option<&str>) -> Result<Vec<Option<Tile>>> {
+    let bytes = base64(data)?;
+
+    let bytes = match compression {
+        None | Some("") => bytes,
+        Some("zlib") => decompress(ZlibDecoder::new(&bytes[..]))?,
+        Some("gzip") => decompress(GzDecoder::new(&bytes[..]))?,
+        Some(other) => return Err(invalid(format!("unsupported compression '{}'", other))),
+    };
+
+    if bytes.len() % 4 != 0 {
+        return Err(invalid("tile data is not a multiple of four bytes"));
+    }
+
+    Ok(bytes
+        .chunks_exact(4)
+        .map(|b| Tile::from_raw_gid(u32::from_le_bytes([b[0], b[1], b[2], b[3]])))
+        .collect())
+}
+
+fn decompress<R>(mut reader: R) -> Result<Vec<u8>>
+where
+    R: Read,
+{
+    let mut out = Vec::new();
+
+    reader
+        .read_to_end(&mut out)
+        .map_err(|e| invalid(format!("failed to decompress tile data: {}", e)))?;
+
+    Ok(out)
+}
+
+fn base64(data: &str) -> Result<Vec<u8>> {
+    let mut out = Vec::with_capacity(data.len() * 3 / 4);
+    let mut buffer = 0u32;
+    let mut bits = 0;
+
+    for byte in data.bytes() {
+        let value = match byte {
+            b'A'..=b'Z' => byte - b'A',
+            b'a'..=b'z' => byte - b'a' + 26,
+            b'0'..=b'9' => byte - b'0' + 52,
+            b'+' => 62,
+            b'/' => 63,
+            b'=' => break,
+            b' ' | b'\t' | b'\r' | b'\n' => continue,
+            _ => return Err(invalid("invalid base64 tile data")),
+        };
+
+        buffer = (buffer << 6) | u32::from(value);
+        bits += 6;
+
+        if bits >= 8 {
+            bits -= 8;
+            out.push((buffer >> bits) as u8);
+        }
+    }
+
+    Ok(out)
+}
+
+/// A chunk of an infinite map's tile layer.
+pub(crate) struct RawChunk {
+    pub(crate) x: i32,
+    pub(crate) y: i32,
+    pub(crate) width: u32,
+    pub(crate) height: u32,
+    pub(crate) tiles: Vec<Option<Tile>>,
+}
+
+/// Returns the number of tiles in a grid of the given size, or an error if it is
+/// too large to be represented.
+fn tile_count(width: u32, height: u32) -> Result<usize> {
+    u64::from(width)
+        .checked_mul(u64::from(height))
+        .and_then(|count| usize::try_from(count).ok())
+        .ok_or_else(|| invalid(format!("tile grid of {}x{} is too large", width, height)))
+}
+
+/// Checks that a tile layer contains exactly one entry for each tile in its grid.
+pub(crate) fn check_tile_count(layer: &TileLayer) -> Result {
+    if layer.tiles.len() != tile_count(layer.width, layer.height)? {
+        return Err(invalid(format!(
+            "tile layer '{}' has the wrong number of tiles",
+            layer.name
+        )));
+    }
+
+    Ok(())
+}
+
+/// Combines the chunks of an infinite map's tile layer into a single grid, covering
+/// the bounds of all of the chunks.
+///
+/// # Errors
+///
+/// * [`TetraError::InvalidTilemap`] will be returned if a chunk is empty, or does not
+/// contain exactly one entry for each of its tiles.
+pub(crate) fn merge_chunks(layer: &mut TileLayer, chunks: &[RawChunk]) -> Result {
+    for chunk in chunks {
+        if chunk.width == 0 || chunk.height == 0 {
+            return Err(invalid(format!(
+                "chunk at ({}, {}) in tile layer '{}' has no size",
+                chunk.x, chunk.y, layer.name
+            )));
+        }
+
+        if chunk.tiles.len() != tile_count(chunk.width, chunk.height)? {
+            return Err(invalid(format!(
+                "chunk at ({}, {}) in tile layer '{}' has the wrong number of tiles",
+                chunk.x, chunk.y, layer.name
+            )));
+        }
+    }
+
+    let name = layer.name.clone();
+    let bounds_error = || invalid(format!("tile layer '{}' is too large", name));
+
+    let left = chunks.iter().map(|c| c.x).min().unwrap_or(0);
+    let top = chunks.iter().map(|c| c.y).min().unwrap_or(0);
+    let right = chunks
+        .iter()
+        .map(|c| i64::from(c.x) + i64::from(c.width))
+        .max()
+        .unwrap_or(0);
+    let bottom = chunks
+        .iter()
+        .map(|c| i64::from(c.y) + i64::from(c.height))
+        .max()
+        .unwrap_or(0);
+
+    layer.origin = Vec2::new(left, top);
+    layer.width = u32::try_from(right - i64::from(left)).map_err(|_| bounds_error())?;
+    layer.height = u32::try_from(bottom - i64::from(top)).map_err(|_| bounds_error())?;
+    layer.tiles = vec![None; tile_count(layer.width, layer.height)?];
+
+    for chunk in chunks {
+        let chunk_x = (i64::from(chunk.x) - i64::from(left)) as usize;
+        let chunk_y = (i64::from(chunk.y) - i64::from(top)) as usize;
+        let chunk_width = chunk.width as usize;
+
+        for (i, tile) in chunk.tiles.iter().enumerate() {
+            let x = chunk_x + i % chunk_width;
+            let y = chunk_y + i / chunk_width;
+
+            layer.tiles[y * layer.width as usize + x] = *tile;
+        }
+    }
+
+    Ok(())
+}
+
+/// Parses a color in Tiled's `#AARRGGBB` or `#RRGGBB` format.
+pub(crate) fn parse_color(value: &str) -> Result<Color> {
+    let hex = value.trim_start_matches('#');
+
+    let channel = |i: usize| {
+        hex.get(i..i + 2)
+            .and_then(|c| u8::from_str_radix(c, 16).ok())
+            .ok_or_else(|| invalid(format!("invalid color '{}'", value)))
+    };
+
+    match hex.len() {
+        6 => Ok(Color::rgb8(channel(0)?, channel(2)?, channel(4)?)),
+        8 => Ok(Color::rgba8(
+            channel(2)?,
+            channel(4)?,
+            channel(6)?,
+            channel(0)?,
+        )),
+        _ => Err(invalid(format!("invalid color '{}'", value))),
+    }
+}
+
+/// Parses a property from its type name and string representation.
+pub(crate) fn parse_property(kind: &str, value: &str) -> Result<PropertyValue> {
+    let error = || invalid(format!("invalid {} property '{}'", kind, value));
+
+    Ok(match kind {
+        "int" => PropertyValue::Int(value.parse().map_err(|_| error())?),
+        "float" => PropertyValue::Float(value.parse().map_err(|_| error())?),
+        "bool" => PropertyValue::Bool(value.parse().map_err(|_| error())?),
+        "color" if value.is_empty() => PropertyValue::Color(Color::rgba(0.0, 0.0, 0.0, 0.0)),
+        "color" => PropertyValue::Color(parse_color(value)?),
+        "file" => PropertyValue::File(value.to_owned()),
+        "object" => PropertyValue::Object(value.parse().map_err(|_| error())?),
+        _ => PropertyValue::String(value.to_owned()),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decode_csv_data() {
+        let tiles = decode_csv("1,0,\n2147483651, 4\n").unwrap();
+
+        assert_eq!(tiles.len(), 4);
+        assert_eq!(tiles[0].unwrap().gid, 1);
+        assert_eq!(tiles[1], None);
+        assert_eq!(tiles[2].unwrap().gid, 3);
+        assert!(tiles[2].unwrap().flip_horizontal);
+
+        assert!(decode_csv("1,x").is_err());
+    }
+
+    #[test]
+    fn decode_base64_data() {
+        // [1, 0, 2] as little-endian u32s.
+        let tiles = decode_base64("AQAAAAAAAAACAAAA", None).unwrap();
+
+        assert_eq!(tiles.len(), 3);
+        assert_eq!(tiles[0].unwrap().gid, 1);
+        assert_eq!(tiles[1], None);
+        assert_eq!(tiles[2].unwrap().gid, 2);
+
+        assert!(decode_base64("AQAAAAAAAAACAAAA", Some("zstd")).is_err());
+    }
+
+    #[test]
+    fn decode_zlib_data() {
+        // [1, 2] as little-endian u32s, compressed with zlib.
+        let tiles = decode_base64("eJxjZGBgYAJiAAAYAAQ=", Some("zlib")).unwrap();
+
+        assert_eq!(tiles.len(), 2);
+        assert_eq!(tiles[0].unwrap().gid, 1);
+        assert_eq!(tiles[1].unwrap().gid, 2);
+    }
+
+    #[test]
+    fn parse_colors() {
+        assert_eq!(parse_color("#ff0000").unwrap(), Color::rgb8(255, 0, 0));
+        assert_eq!(
+            parse_color("#80ff0000").unwrap(),
+            Color::rgba8(255, 0, 0, 128)
+        );
+        assert!(parse_color("#ff00").is_err());
+    }
+}
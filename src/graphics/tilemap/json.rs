@@ -0,0 +1,561 @@
+//! Parsing of Tiled's JSON map and tileset formats.
+
+use std::path::Path;
+use std::time::Duration;
+
+use hashbrown::HashMap;
+use serde_json::{Map, Value};
+
+use crate::graphics::tilemap::encoding::{self, invalid, RawChunk};
+use crate::graphics::tilemap::{
+    Frame, GroupLayer, Layer, MapData, MapObject, ObjectLayer, ObjectShape, Orientation,
+    Properties, PropertyValue, Tile, TileData, TileLayer, Tileset,
+};
+use crate::graphics::Rectangle;
+use crate::math::Vec2;
+use crate::Result;
+
+type Object = Map<String, Value>;
+
+pub(crate) fn parse_map(data: &str, base_dir: &Path) -> Result<MapData> {
+    let map = parse_document(data)?;
+
+    let orientation = match str_or(&map, "orientation", "orthogonal") {
+        "orthogonal" => Orientation::Orthogonal,
+        "isometric" => Orientation::Isometric,
+        "staggered" => Orientation::Staggered,
+        "hexagonal" => Orientation::Hexagonal,
+        other => return Err(invalid(format!("unknown orientation '{}'", other))),
+    };
+
+    let background_color = map
+        .get("backgroundcolor")
+        .and_then(Value::as_str)
+        .map(encoding::parse_color)
+        .transpose()?;
+
+    let mut tilesets = Vec::new();
+
+    for tileset in array(&map, "tilesets")? {
+        let tileset = as_object(tileset)?;
+        let first_gid = required_u32(tileset, "firstgid")?;
+
+        let tileset = match tileset.get("source").and_then(Value::as_str) {
+            Some(source) => super::load_external_tileset(&base_dir.join(source), first_gid)?,
+            None => read_tileset(tileset, first_gid, base_dir)?,
+        };
+
+        tilesets.push(tileset);
+    }
+
+    tilesets.sort_by_key(|t| t.first_gid);
+
+    Ok(MapData {
+        orientation,
+        width: required_u32(&map, "width")?,
+        height: required_u32(&map, "height")?,
+        tile_width: required_u32(&map, "tilewidth")?,
+        tile_height: required_u32(&map, "tileheight")?,
+        infinite: bool_or(&map, "infinite", false),
+        background_color,
+        tilesets,
+        layers: read_layers(&map)?,
+        properties: read_properties(&map)?,
+    })
+}
+
+pub(crate) fn parse_tileset(data: &str, first_gid: u32, base_dir: &Path) -> Result<Tileset> {
+    read_tileset(&parse_document(data)?, first_gid, base_dir)
+}
+
+fn parse_document(data: &str) -> Result<Object> {
+    match serde_json::from_str(data) {
+        Ok(Value::Object(object)) => Ok(object),
+        Ok(_) => Err(invalid("expected a JSON object")),
+        Err(e) => Err(invalid(format!("invalid JSON: {}", e))),
+    }
+}
+
+fn as_object(value: &Value) -> Result<&Object> {
+    value
+        .as_object()
+        .ok_or_else(|| invalid("expected a JSON object"))
+}
+
+fn array<'a>(object: &'a Object, key: &str) -> Result<&'a [Value]> {
+    match object.get(key) {
+        Some(Value::Array(values)) => Ok(values),
+        Some(_) => Err(invalid(format!("'{}' should be an array", key))),
+        None => Ok(&[]),
+    }
+}
+
+fn required_u32(object: &Object, key: &str) -> Result<u32> {
+    object
+        .get(key)
+        .and_then(Value::as_u64)
+        .map(|v| v as u32)
+        .ok_or_else(|| invalid(format!("missing or invalid field '{}'", key)))
+}
+
+fn u32_or(object: &Object, key: &str, default: u32) -> u32 {
+    object
+        .get(key)
+        .and_then(Value::as_u64)
+        .map(|v| v as u32)
+        .unwrap_or(default)
+}
+
+fn i32_or(object: &Object, key: &str, default: i32) -> i32 {
+    object
+        .get(key)
+        .and_then(Value::as_i64)
+        .map(|v| v as i32)
+        .unwrap_or(default)
+}
+
+fn f32_or(object: &Object, key: &str, default: f32) -> f32 {
+    object
+        .get(key)
+        .and_then(Value::as_f64)
+        .map(|v| v as f32)
+        .unwrap_or(default)
+}
+
+fn bool_or(object: &Object, key: &str, default: bool) -> bool {
+    object.get(key).and_then(Value::as_bool).unwrap_or(default)
+}
+
+fn str_or<'a>(object: &'a Object, key: &str, default: &'a str) -> &'a str {
+    object.get(key).and_then(Value::as_str).unwrap_or(default)
+}
+
+fn read_offset(object: &Object) -> Vec2<f32> {
+    Vec2::new(
+        f32_or(object, "offsetx", 0.0),
+        f32_or(object, "offsety", 0.0),
+    )
+}
+
+fn read_class(object: &Object) -> String {
+    object
+        .get("class")
+        .or_else(|| object.get("type"))
+        .and_then(Value::as_str)
+        .unwrap_or("")
+        .to_owned()
+}
+
+fn read_properties(object: &Object) -> Result<Properties> {
+    let mut properties = HashMap::new();
+
+    for property in array(object, "properties")? {
+        let property = as_object(property)?;
+
+        let name = property
+            .get("name")
+            .and_then(Value::as_str)
+            .ok_or_else(|| invalid("missing property name"))?;
+
+        let kind = str_or(property, "type", "string");
+
+        let value = match property.get("value") {
+            Some(Value::String(value)) => encoding::parse_property(kind, value)?,
+            Some(Value::Bool(value)) => PropertyValue::Bool(*value),
+            Some(Value::Number(value)) => match kind {
+                "int" | "object" => encoding::parse_property(kind, &value.to_string())?,
+                _ => PropertyValue::Float(value.as_f64().unwrap_or(0.0)),
+            },
+            Some(other) => PropertyValue::String(other.to_string()),
+            None => PropertyValue::String(String::new()),
+        };
+
+        properties.insert(name.to_owned(), value);
+    }
+
+    Ok(properties)
+}
+
+fn read_tileset(tileset: &Object, first_gid: u32, base_dir: &Path) -> Result<Tileset> {
+    let image = tileset
+        .get("image")
+        .and_then(Value::as_str)
+        .map(|source| base_dir.join(source));
+
+    let offset = match tileset.get("tileoffset").and_then(Value::as_object) {
+        Some(offset) => Vec2::new(f32_or(offset, "x", 0.0), f32_or(offset, "y", 0.0)),
+        None => Vec2::zero(),
+    };
+
+    let mut tiles = HashMap::new();
+
+    for tile in array(tileset, "tiles")? {
+        let tile = as_object(tile)?;
+        let id = required_u32(tile, "id")?;
+
+        let animation = array(tile, "animation")?
+            .iter()
+            .map(|frame| {
+                let frame = as_object(frame)?;
+
+                Ok(Frame {
+                    tile_id: required_u32(frame, "tileid")?,
+                    duration: Duration::from_millis(u64::from(required_u32(frame, "duration")?)),
+                })
+            })
+            .collect::<Result<_>>()?;
+
+        let objects = match tile.get("objectgroup").and_then(Value::as_object) {
+            Some(group) => read_object_layer(group)?.objects,
+            None => Vec::new(),
+        };
+
+        tiles.insert(
+            id,
+            TileData {
+                class: read_class(tile),
+                properties: read_properties(tile)?,
+                animation,
+                objects,
+            },
+        );
+    }
+
+    Ok(Tileset {
+        first_gid,
+        name: str_or(tileset, "name", "").to_owned(),
+        tile_width: required_u32(tileset, "tilewidth")?,
+        tile_height: required_u32(tileset, "tileheight")?,
+        spacing: u32_or(tileset, "spacing", 0),
+        margin: u32_or(tileset, "margin", 0),
+        tile_count: required_u32(tileset, "tilecount")?,
+        columns: required_u32(tileset, "columns")?,
+        image,
+        offset,
+        tiles,
+        properties: read_properties(tileset)?,
+    })
+}
+
+fn read_layers(parent: &Object) -> Result<Vec<Layer>> {
+    let mut layers = Vec::new();
+
+    for layer in array(parent, "layers")? {
+        let layer = as_object(layer)?;
+
+        match str_or(layer, "type", "") {
+            "tilelayer" => layers.push(Layer::Tile(read_tile_layer(layer)?)),
+            "objectgroup" => layers.push(Layer::Object(read_object_layer(layer)?)),
+            "group" => layers.push(Layer::Group(GroupLayer {
+                name: str_or(layer, "name", "").to_owned(),
+                visible: bool_or(layer, "visible", true),
+                opacity: f32_or(layer, "opacity", 1.0),
+                offset: read_offset(layer),
+                layers: read_layers(layer)?,
+                properties: read_properties(layer)?,
+            })),
+            _ => {}
+        }
+    }
+
+    Ok(layers)
+}
+
+// Chunks inherit their encoding from the parent layer, so that is passed in
+// separately from the object containing the tiles.
+fn read_tile_data(object: &Object, layer: &Object) -> Result<Vec<Option<Tile>>> {
+    match object.get("data") {
+        Some(Value::Array(values)) => values
+            .iter()
+            .map(|v| {
+                v.as_u64()
+                    .map(|gid| Tile::from_raw_gid(gid as u32))
+                    .ok_or_else(|| invalid(format!("invalid tile ID '{}'", v)))
+            })
+            .collect(),
+
+        Some(Value::String(data)) => match str_or(layer, "encoding", "csv") {
+            "base64" => {
+                encoding::decode_base64(data, layer.get("compression").and_then(Value::as_str))
+            }
+            "csv" => encoding::decode_csv(data),
+            other => Err(invalid(format!("unsupported encoding '{}'", other))),
+        },
+
+        _ => Err(invalid("missing or invalid tile data")),
+    }
+}
+
+fn read_tile_layer(object: &Object) -> Result<TileLayer> {
+    let mut layer = TileLayer {
+        name: str_or(object, "name", "").to_owned(),
+        visible: bool_or(object, "visible", true),
+        opacity: f32_or(object, "opacity", 1.0),
+        offset: read_offset(object),
+        origin: Vec2::zero(),
+        width: required_u32(object, "width")?,
+        height: required_u32(object, "height")?,
+        tiles: Vec::new(),
+        properties: read_properties(object)?,
+    };
+
+    match object.get("chunks") {
+        Some(Value::Array(chunks)) => {
+            let chunks = chunks
+                .iter()
+                .map(|chunk| {
+                    let chunk = as_object(chunk)?;
+
+                    Ok(RawChunk {
+                        x: i32_or(chunk, "x", 0),
+                        y: i32_or(chunk, "y", 0),
+                        width: required_u32(chunk, "width")?,
+                        height: required_u32(chunk, "height")?,
+                        tiles: read_tile_data(chunk, object)?,
+                    })
+                })
+                .collect::<Result<Vec<_>>>()?;
+
+            encoding::merge_chunks(&mut layer, &chunks)?;
+        }
+
+        _ => layer.tiles = read_tile_data(object, object)?,
+    }
+
+    encoding::check_tile_count(&layer)?;
+
+    Ok(layer)
+}
+
+fn read_object_layer(object: &Object) -> Result<ObjectLayer> {
+    Ok(ObjectLayer {
+        name: str_or(object, "name", "").to_owned(),
+        visible: bool_or(object, "visible", true),
+        opacity: f32_or(object, "opacity", 1.0),
+        offset: read_offset(object),
+        color: object
+            .get("color")
+            .and_then(Value::as_str)
+            .map(encoding::parse_color)
+            .transpose()?,
+        objects: array(object, "objects")?
+            .iter()
+            .map(|o| as_object(o).and_then(read_object))
+            .collect::<Result<_>>()?,
+        properties: read_properties(object)?,
+    })
+}
+
+fn read_object(object: &Object) -> Result<MapObject> {
+    let position = Vec2::new(f32_or(object, "x", 0.0), f32_or(object, "y", 0.0));
+    let width = f32_or(object, "width", 0.0);
+    let height = f32_or(object, "height", 0.0);
+    let tile = Tile::from_raw_gid(u32_or(object, "gid", 0));
+
+    let read_points = |key: &str| -> Result<Vec<Vec2<f32>>> {
+        array(object, key)?
+            .iter()
+            .map(|point| {
+                let point = as_object(point)?;
+                Ok(position + Vec2::new(f32_or(point, "x", 0.0), f32_or(point, "y", 0.0)))
+            })
+            .collect()
+    };
+
+    let shape = if bool_or(object, "ellipse", false) {
+        ObjectShape::Ellipse(Rectangle::new(position.x, position.y, width, height))
+    } else if bool_or(object, "point", false) {
+        ObjectShape::Point(position)
+    } else if object.contains_key("polygon") {
+        ObjectShape::Polygon(read_points("polygon")?)
+    } else if object.contains_key("polyline") {
+        ObjectShape::Polyline(read_points("polyline")?)
+    } else if tile.is_some() {
+        // Tile objects are positioned by their bottom-left corner.
+        ObjectShape::Rectangle(Rectangle::new(
+            position.x,
+            position.y - height,
+            width,
+            height,
+        ))
+    } else {
+        ObjectShape::Rectangle(Rectangle::new(position.x, position.y, width, height))
+    };
+
+    Ok(MapObject {
+        id: u32_or(object, "id", 0),
+        name: str_or(object, "name", "").to_owned(),
+        class: read_class(object),
+        position,
+        rotation: f32_or(object, "rotation", 0.0),
+        visible: bool_or(object, "visible", true),
+        tile,
+        shape,
+        properties: read_properties(object)?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::TetraError;
+
+    const MAP: &str = r##"{
+        "orientation": "orthogonal",
+        "width": 2,
+        "height": 2,
+        "tilewidth": 16,
+        "tileheight": 16,
+        "infinite": false,
+        "properties": [
+            { "name": "lives", "type": "int", "value": 3 },
+            { "name": "tint", "type": "color", "value": "#ff00ff00" }
+        ],
+        "tilesets": [
+            {
+                "firstgid": 1,
+                "name": "tiles",
+                "tilewidth": 16,
+                "tileheight": 16,
+                "tilecount": 4,
+                "columns": 2,
+                "image": "tiles.png",
+                "tiles": [
+                    {
+                        "id": 0,
+                        "animation": [
+                            { "tileid": 0, "duration": 250 },
+                            { "tileid": 3, "duration": 250 }
+                        ],
+                        "objectgroup": {
+                            "type": "objectgroup",
+                            "objects": [{ "id": 1, "x": 0, "y": 0, "width": 16, "height": 4 }]
+                        }
+                    }
+                ]
+            }
+        ],
+        "layers": [
+            {
+                "type": "tilelayer",
+                "name": "ground",
+                "width": 2,
+                "height": 2,
+                "data": [1, 0, 1073741826, 4]
+            },
+            {
+                "type": "tilelayer",
+                "name": "compressed",
+                "width": 1,
+                "height": 2,
+                "encoding": "base64",
+                "compression": "zlib",
+                "data": "eJxjZGBgYAJiAAAYAAQ="
+            },
+            {
+                "type": "objectgroup",
+                "name": "objects",
+                "objects": [
+                    {
+                        "id": 1,
+                        "name": "door",
+                        "class": "warp",
+                        "x": 32,
+                        "y": 16,
+                        "width": 16,
+                        "height": 32,
+                        "properties": [{ "name": "target", "type": "object", "value": 7 }]
+                    },
+                    { "id": 2, "x": 4, "y": 4, "width": 8, "height": 8, "ellipse": true },
+                    { "id": 3, "x": 1, "y": 2, "polyline": [{ "x": 0, "y": 0 }, { "x": 5, "y": 5 }] }
+                ]
+            },
+            { "type": "imagelayer", "name": "ignored" }
+        ]
+    }"##;
+
+    #[test]
+    fn parse_json_map() {
+        let map = parse_map(MAP, Path::new("maps")).unwrap();
+
+        assert_eq!(map.layers.len(), 3);
+        assert_eq!(map.properties.get("lives"), Some(&PropertyValue::Int(3)));
+        assert_eq!(
+            map.properties.get("tint"),
+            Some(&PropertyValue::Color(crate::graphics::Color::rgba8(
+                0, 255, 0, 255
+            )))
+        );
+
+        let tileset = &map.tilesets[0];
+
+        assert_eq!(tileset.image, Some(Path::new("maps").join("tiles.png")));
+        assert_eq!(tileset.tiles[&0].animation[1].tile_id, 3);
+        assert_eq!(
+            tileset.tiles[&0].animation[1].duration,
+            Duration::from_millis(250)
+        );
+        assert_eq!(tileset.tiles[&0].objects.len(), 1);
+
+        let ground = map.tile_layer("ground").unwrap();
+
+        assert_eq!(ground.get(1, 0), None);
+        assert!(ground.get(0, 1).unwrap().flip_vertical);
+        assert_eq!(ground.get(0, 1).unwrap().gid, 2);
+
+        let compressed = map.tile_layer("compressed").unwrap();
+
+        assert_eq!(compressed.get(0, 1).unwrap().gid, 2);
+    }
+
+    #[test]
+    fn parse_json_objects() {
+        let map = parse_map(MAP, Path::new("")).unwrap();
+        let objects = &map.object_layer("objects").unwrap().objects;
+
+        assert_eq!(objects[0].class, "warp");
+        assert_eq!(
+            objects[0].shape,
+            ObjectShape::Rectangle(Rectangle::new(32.0, 16.0, 16.0, 32.0))
+        );
+        assert_eq!(
+            objects[0].properties.get("target"),
+            Some(&PropertyValue::Object(7))
+        );
+        assert_eq!(
+            objects[1].shape,
+            ObjectShape::Ellipse(Rectangle::new(4.0, 4.0, 8.0, 8.0))
+        );
+        assert_eq!(
+            objects[2].shape,
+            ObjectShape::Polyline(vec![Vec2::new(1.0, 2.0), Vec2::new(6.0, 7.0)])
+        );
+    }
+
+    #[test]
+    fn reject_invalid_tile_data() {
+        let parse = |layer: &str| {
+            parse_map(
+                &format!(
+                    r#"{{"orientation": "orthogonal", "width": 2, "height": 2, "tilewidth": 8, "tileheight": 8, "layers": [{}]}}"#,
+                    layer
+                ),
+                Path::new(""),
+            )
+        };
+
+        let short =
+            r#"{"type": "tilelayer", "name": "short", "width": 2, "height": 2, "data": [1, 2, 3]}"#;
+        let empty_chunk = r#"{"type": "tilelayer", "name": "chunks", "width": 2, "height": 2, "chunks": [{"x": 0, "y": 0, "width": 0, "height": 1, "data": [1]}]}"#;
+        let short_chunk = r#"{"type": "tilelayer", "name": "chunks", "width": 2, "height": 2, "chunks": [{"x": 0, "y": 0, "width": 2, "height": 2, "data": [1, 2, 3]}]}"#;
+
+        assert!(parse(
+            r#"{"type": "tilelayer", "name": "ok", "width": 2, "height": 2, "data": [1, 2, 3, 4]}"#
+        )
+        .is_ok());
+
+        for layer in &[short, empty_chunk, short_chunk] {
+            assert!(matches!(parse(layer), Err(TetraError::InvalidTilemap(_))));
+        }
+    }
+}
@@ -0,0 +1,586 @@
+//! Parsing of Tiled's XML-based TMX and TSX formats.
+
+use std::path::Path;
+use std::str::FromStr;
+use std::time::Duration;
+
+use hashbrown::HashMap;
+use roxmltree::{Document, Node};
+
+use crate::graphics::tilemap::encoding::{self, invalid, RawChunk};
+use crate::graphics::tilemap::{
+    Frame, GroupLayer, Layer, MapData, MapObject, ObjectLayer, ObjectShape, Orientation,
+    Properties, Tile, TileData, TileLayer, Tileset,
+};
+use crate::graphics::Rectangle;
+use crate::math::Vec2;
+use crate::Result;
+
+pub(crate) fn parse_map(data: &str, base_dir: &Path) -> Result<MapData> {
+    let document = parse_document(data)?;
+    let map = document.root_element();
+
+    if map.tag_name().name() != "map" {
+        return Err(invalid("expected a <map> element"));
+    }
+
+    let orientation = match map.attribute("orientation").unwrap_or("orthogonal") {
+        "orthogonal" => Orientation::Orthogonal,
+        "isometric" => Orientation::Isometric,
+        "staggered" => Orientation::Staggered,
+        "hexagonal" => Orientation::Hexagonal,
+        other => return Err(invalid(format!("unknown orientation '{}'", other))),
+    };
+
+    let background_color = map
+        .attribute("backgroundcolor")
+        .map(encoding::parse_color)
+        .transpose()?;
+
+    let mut tilesets = Vec::new();
+
+    for node in elements(map, "tileset") {
+        let first_gid = required(node, "firstgid")?;
+
+        let tileset = match node.attribute("source") {
+            Some(source) => super::load_external_tileset(&base_dir.join(source), first_gid)?,
+            None => read_tileset(node, first_gid, base_dir)?,
+        };
+
+        tilesets.push(tileset);
+    }
+
+    tilesets.sort_by_key(|t| t.first_gid);
+
+    let infinite = optional(map, "infinite", 0u32)? != 0;
+
+    Ok(MapData {
+        orientation,
+        width: required(map, "width")?,
+        height: required(map, "height")?,
+        tile_width: required(map, "tilewidth")?,
+        tile_height: required(map, "tileheight")?,
+        infinite,
+        background_color,
+        tilesets,
+        layers: read_layers(map, infinite)?,
+        properties: read_properties(map)?,
+    })
+}
+
+pub(crate) fn parse_tileset(data: &str, first_gid: u32, base_dir: &Path) -> Result<Tileset> {
+    let document = parse_document(data)?;
+    let tileset = document.root_element();
+
+    if tileset.tag_name().name() != "tileset" {
+        return Err(invalid("expected a <tileset> element"));
+    }
+
+    read_tileset(tileset, first_gid, base_dir)
+}
+
+fn parse_document(data: &str) -> Result<Document<'_>> {
+    Document::parse(data).map_err(|e| invalid(format!("invalid XML: {}", e)))
+}
+
+fn elements<'a, 'input: 'a>(
+    node: Node<'a, 'input>,
+    name: &'static str,
+) -> impl Iterator<Item = Node<'a, 'input>> {
+    node.children()
+        .filter(move |n| n.is_element() && n.tag_name().name() == name)
+}
+
+fn required<T>(node: Node<'_, '_>, name: &str) -> Result<T>
+where
+    T: FromStr,
+{
+    let value = node.attribute(name).ok_or_else(|| {
+        invalid(format!(
+            "missing attribute '{}' on <{}>",
+            name,
+            node.tag_name().name()
+        ))
+    })?;
+
+    parse_attribute(node, name, value)
+}
+
+fn optional<T>(node: Node<'_, '_>, name: &str, default: T) -> Result<T>
+where
+    T: FromStr,
+{
+    match node.attribute(name) {
+        Some(value) => parse_attribute(node, name, value),
+        None => Ok(default),
+    }
+}
+
+fn parse_attribute<T>(node: Node<'_, '_>, name: &str, value: &str) -> Result<T>
+where
+    T: FromStr,
+{
+    value.parse().map_err(|_| {
+        invalid(format!(
+            "invalid value '{}' for attribute '{}' on <{}>",
+            value,
+            name,
+            node.tag_name().name()
+        ))
+    })
+}
+
+fn read_offset(node: Node<'_, '_>) -> Result<Vec2<f32>> {
+    Ok(Vec2::new(
+        optional(node, "offsetx", 0.0)?,
+        optional(node, "offsety", 0.0)?,
+    ))
+}
+
+fn read_visible(node: Node<'_, '_>) -> Result<bool> {
+    Ok(optional(node, "visible", 1u32)? != 0)
+}
+
+fn read_class(node: Node<'_, '_>) -> String {
+    node.attribute("class")
+        .or_else(|| node.attribute("type"))
+        .unwrap_or("")
+        .to_owned()
+}
+
+fn read_properties(node: Node<'_, '_>) -> Result<Properties> {
+    let mut properties = HashMap::new();
+
+    for list in elements(node, "properties") {
+        for property in elements(list, "property") {
+            let name = property
+                .attribute("name")
+                .ok_or_else(|| invalid("missing property name"))?;
+
+            // Multi-line string properties are stored as text rather than an attribute.
+            let value = property
+                .attribute("value")
+                .or_else(|| property.text())
+                .unwrap_or("");
+
+            let kind = property.attribute("type").unwrap_or("string");
+
+            properties.insert(name.to_owned(), encoding::parse_property(kind, value)?);
+        }
+    }
+
+    Ok(properties)
+}
+
+fn read_tileset(node: Node<'_, '_>, first_gid: u32, base_dir: &Path) -> Result<Tileset> {
+    let image = elements(node, "image")
+        .next()
+        .and_then(|i| i.attribute("source"))
+        .map(|source| base_dir.join(source));
+
+    let offset = match elements(node, "tileoffset").next() {
+        Some(offset) => Vec2::new(optional(offset, "x", 0.0)?, optional(offset, "y", 0.0)?),
+        None => Vec2::zero(),
+    };
+
+    let mut tiles = HashMap::new();
+
+    for tile in elements(node, "tile") {
+        let id = required(tile, "id")?;
+
+        let mut animation = Vec::new();
+
+        for frames in elements(tile, "animation") {
+            for frame in elements(frames, "frame") {
+                animation.push(Frame {
+                    tile_id: required(frame, "tileid")?,
+                    duration: Duration::from_millis(required(frame, "duration")?),
+                });
+            }
+        }
+
+        let mut objects = Vec::new();
+
+        for group in elements(tile, "objectgroup") {
+            objects.extend(read_object_layer(group)?.objects);
+        }
+
+        tiles.insert(
+            id,
+            TileData {
+                class: read_class(tile),
+                properties: read_properties(tile)?,
+                animation,
+                objects,
+            },
+        );
+    }
+
+    Ok(Tileset {
+        first_gid,
+        name: node.attribute("name").unwrap_or("").to_owned(),
+        tile_width: required(node, "tilewidth")?,
+        tile_height: required(node, "tileheight")?,
+        spacing: optional(node, "spacing", 0)?,
+        margin: optional(node, "margin", 0)?,
+        tile_count: required(node, "tilecount")?,
+        columns: required(node, "columns")?,
+        image,
+        offset,
+        tiles,
+        properties: read_properties(node)?,
+    })
+}
+
+fn read_layers(parent: Node<'_, '_>, infinite: bool) -> Result<Vec<Layer>> {
+    let mut layers = Vec::new();
+
+    for node in parent.children().filter(|n| n.is_element()) {
+        match node.tag_name().name() {
+            "layer" => layers.push(Layer::Tile(read_tile_layer(node, infinite)?)),
+            "objectgroup" => layers.push(Layer::Object(read_object_layer(node)?)),
+            "group" => layers.push(Layer::Group(GroupLayer {
+                name: node.attribute("name").unwrap_or("").to_owned(),
+                visible: read_visible(node)?,
+                opacity: optional(node, "opacity", 1.0)?,
+                offset: read_offset(node)?,
+                layers: read_layers(node, infinite)?,
+                properties: read_properties(node)?,
+            })),
+            _ => {}
+        }
+    }
+
+    Ok(layers)
+}
+
+// Chunks inherit their encoding from the parent <data> element, so that is passed
+// in separately from the node containing the tiles.
+fn read_tile_data(node: Node<'_, '_>, data: Node<'_, '_>) -> Result<Vec<Option<Tile>>> {
+    match data.attribute("encoding") {
+        Some("csv") => encoding::decode_csv(node.text().unwrap_or("")),
+        Some("base64") => {
+            encoding::decode_base64(node.text().unwrap_or(""), data.attribute("compression"))
+        }
+        Some(other) => Err(invalid(format!("unsupported encoding '{}'", other))),
+        None => elements(node, "tile")
+            .map(|t| optional(t, "gid", 0).map(Tile::from_raw_gid))
+            .collect(),
+    }
+}
+
+fn read_tile_layer(node: Node<'_, '_>, infinite: bool) -> Result<TileLayer> {
+    let data = elements(node, "data")
+        .next()
+        .ok_or_else(|| invalid("tile layer has no <data> element"))?;
+
+    let mut layer = TileLayer {
+        name: node.attribute("name").unwrap_or("").to_owned(),
+        visible: read_visible(node)?,
+        opacity: optional(node, "opacity", 1.0)?,
+        offset: read_offset(node)?,
+        origin: Vec2::zero(),
+        width: required(node, "width")?,
+        height: required(node, "height")?,
+        tiles: Vec::new(),
+        properties: read_properties(node)?,
+    };
+
+    let chunks = elements(data, "chunk").collect::<Vec<_>>();
+
+    // Infinite maps always store their tiles in chunks - a layer with no chunks is empty,
+    // rather than a grid with no tiles in it.
+    if chunks.is_empty() && !infinite {
+        layer.tiles = read_tile_data(data, data)?;
+    } else {
+        let chunks = chunks
+            .into_iter()
+            .map(|chunk| {
+                Ok(RawChunk {
+                    x: required(chunk, "x")?,
+                    y: required(chunk, "y")?,
+                    width: required(chunk, "width")?,
+                    height: required(chunk, "height")?,
+                    tiles: read_tile_data(chunk, data)?,
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        encoding::merge_chunks(&mut layer, &chunks)?;
+    }
+
+    encoding::check_tile_count(&layer)?;
+
+    Ok(layer)
+}
+
+fn read_object_layer(node: Node<'_, '_>) -> Result<ObjectLayer> {
+    Ok(ObjectLayer {
+        name: node.attribute("name").unwrap_or("").to_owned(),
+        visible: read_visible(node)?,
+        opacity: optional(node, "opacity", 1.0)?,
+        offset: read_offset(node)?,
+        color: node
+            .attribute("color")
+            .map(encoding::parse_color)
+            .transpose()?,
+        objects: elements(node, "object")
+            .map(read_object)
+            .collect::<Result<_>>()?,
+        properties: read_properties(node)?,
+    })
+}
+
+fn read_object(node: Node<'_, '_>) -> Result<MapObject> {
+    let position = Vec2::new(optional(node, "x", 0.0)?, optional(node, "y", 0.0)?);
+    let width = optional(node, "width", 0.0)?;
+    let height = optional(node, "height", 0.0)?;
+    let tile = Tile::from_raw_gid(optional(node, "gid", 0)?);
+
+    let read_points = |element: Node<'_, '_>| -> Result<Vec<Vec2<f32>>> {
+        element
+            .attribute("points")
+            .unwrap_or("")
+            .split_whitespace()
+            .map(|pair| {
+                let mut parts = pair.split(',').map(str::parse::<f32>);
+
+                match (parts.next(), parts.next()) {
+                    (Some(Ok(x)), Some(Ok(y))) => Ok(position + Vec2::new(x, y)),
+                    _ => Err(invalid(format!("invalid point '{}'", pair))),
+                }
+            })
+            .collect()
+    };
+
+    let mut shape = None;
+
+    for child in node.children().filter(|n| n.is_element()) {
+        shape = match child.tag_name().name() {
+            "ellipse" => Some(ObjectShape::Ellipse(Rectangle::new(
+                position.x, position.y, width, height,
+            ))),
+            "point" => Some(ObjectShape::Point(position)),
+            "polygon" => Some(ObjectShape::Polygon(read_points(child)?)),
+            "polyline" => Some(ObjectShape::Polyline(read_points(child)?)),
+            _ => continue,
+        };
+    }
+
+    let shape = shape.unwrap_or_else(|| {
+        if tile.is_some() {
+            // Tile objects are positioned by their bottom-left corner.
+            ObjectShape::Rectangle(Rectangle::new(
+                position.x,
+                position.y - height,
+                width,
+                height,
+            ))
+        } else {
+            ObjectShape::Rectangle(Rectangle::new(position.x, position.y, width, height))
+        }
+    });
+
+    Ok(MapObject {
+        id: optional(node, "id", 0)?,
+        name: node.attribute("name").unwrap_or("").to_owned(),
+        class: read_class(node),
+        position,
+        rotation: optional(node, "rotation", 0.0)?,
+        visible: read_visible(node)?,
+        tile,
+        shape,
+        properties: read_properties(node)?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graphics::tilemap::PropertyValue;
+    use crate::graphics::Color;
+    use crate::TetraError;
+
+    const MAP: &str = r##"<?xml version="1.0" encoding="UTF-8"?>
+<map version="1.9" orientation="orthogonal" width="2" height="2" tilewidth="16" tileheight="16" backgroundcolor="#336699">
+ <properties>
+  <property name="music" value="overworld.ogg"/>
+  <property name="gravity" type="float" value="9.8"/>
+ </properties>
+ <tileset firstgid="1" name="tiles" tilewidth="16" tileheight="16" tilecount="4" columns="2">
+  <image source="tiles.png" width="32" height="32"/>
+  <tile id="1" type="water">
+   <animation>
+    <frame tileid="1" duration="100"/>
+    <frame tileid="2" duration="100"/>
+   </animation>
+  </tile>
+ </tileset>
+ <layer id="1" name="ground" width="2" height="2" opacity="0.5">
+  <data encoding="csv">
+1,2,
+0,2147483651
+</data>
+ </layer>
+ <group name="things" offsetx="4">
+  <objectgroup name="objects" color="#ff0000">
+   <object id="1" name="spawn" type="player" x="8" y="8">
+    <point/>
+   </object>
+   <object id="2" x="0" y="0" width="16" height="8">
+    <properties>
+     <property name="solid" type="bool" value="true"/>
+    </properties>
+   </object>
+   <object id="3" x="10" y="20">
+    <polygon points="0,0 10,0 10,10"/>
+   </object>
+   <object id="4" gid="1" x="0" y="32" width="16" height="16"/>
+  </objectgroup>
+ </group>
+</map>
+"##;
+
+    #[test]
+    fn parse_tmx_map() {
+        let map = parse_map(MAP, Path::new("maps")).unwrap();
+
+        assert_eq!(map.orientation, Orientation::Orthogonal);
+        assert_eq!((map.width, map.height), (2, 2));
+        assert_eq!(map.background_color, Some(Color::rgb8(0x33, 0x66, 0x99)));
+        assert_eq!(
+            map.properties.get("music"),
+            Some(&PropertyValue::String("overworld.ogg".into()))
+        );
+        assert_eq!(
+            map.properties.get("gravity"),
+            Some(&PropertyValue::Float(9.8))
+        );
+
+        let tileset = &map.tilesets[0];
+
+        assert_eq!(tileset.image, Some(Path::new("maps").join("tiles.png")));
+        assert_eq!(tileset.tiles[&1].class, "water");
+        assert_eq!(tileset.tiles[&1].animation.len(), 2);
+
+        let ground = map.tile_layer("ground").unwrap();
+
+        assert_eq!(ground.opacity, 0.5);
+        assert_eq!(ground.get(0, 0).unwrap().gid, 1);
+        assert_eq!(ground.get(0, 1), None);
+        assert!(ground.get(1, 1).unwrap().flip_horizontal);
+        assert_eq!(ground.get(2, 0), None);
+    }
+
+    #[test]
+    fn parse_tmx_objects() {
+        let map = parse_map(MAP, Path::new("")).unwrap();
+        let objects = map.object_layer("objects").unwrap();
+
+        assert_eq!(objects.color, Some(Color::rgb8(255, 0, 0)));
+        assert_eq!(objects.objects.len(), 4);
+
+        let spawn = &objects.objects[0];
+
+        assert_eq!(spawn.class, "player");
+        assert_eq!(spawn.shape, ObjectShape::Point(Vec2::new(8.0, 8.0)));
+
+        let solid = &objects.objects[1];
+
+        assert_eq!(
+            solid.shape,
+            ObjectShape::Rectangle(Rectangle::new(0.0, 0.0, 16.0, 8.0))
+        );
+        assert_eq!(
+            solid.properties.get("solid"),
+            Some(&PropertyValue::Bool(true))
+        );
+
+        assert_eq!(
+            objects.objects[2].shape,
+            ObjectShape::Polygon(vec![
+                Vec2::new(10.0, 20.0),
+                Vec2::new(20.0, 20.0),
+                Vec2::new(20.0, 30.0)
+            ])
+        );
+
+        let tile = &objects.objects[3];
+
+        assert_eq!(tile.tile.unwrap().gid, 1);
+        assert_eq!(
+            tile.shape,
+            ObjectShape::Rectangle(Rectangle::new(0.0, 16.0, 16.0, 16.0))
+        );
+
+        assert_eq!(map.objects().count(), 4);
+    }
+
+    #[test]
+    fn parse_tmx_infinite_map() {
+        let map = parse_map(
+            r#"<map orientation="orthogonal" width="4" height="4" tilewidth="8" tileheight="8" infinite="1">
+                <layer name="chunks" width="4" height="4">
+                    <data encoding="csv">
+                        <chunk x="-2" y="0" width="2" height="1">1,2</chunk>
+                        <chunk x="0" y="1" width="2" height="1">3,0</chunk>
+                    </data>
+                </layer>
+            </map>"#,
+            Path::new(""),
+        )
+        .unwrap();
+
+        assert!(map.infinite);
+
+        let layer = map.tile_layer("chunks").unwrap();
+
+        assert_eq!(layer.origin, Vec2::new(-2, 0));
+        assert_eq!((layer.width, layer.height), (4, 2));
+        assert_eq!(layer.get(-2, 0).unwrap().gid, 1);
+        assert_eq!(layer.get(-1, 0).unwrap().gid, 2);
+        assert_eq!(layer.get(0, 1).unwrap().gid, 3);
+        assert_eq!(layer.get(1, 1), None);
+    }
+
+    #[test]
+    fn parse_tmx_infinite_map_empty_layer() {
+        let map = parse_map(
+            r#"<map orientation="orthogonal" width="30" height="20" tilewidth="8" tileheight="8" infinite="1">
+                <layer name="empty" width="30" height="20">
+                    <data encoding="csv"/>
+                </layer>
+            </map>"#,
+            Path::new(""),
+        )
+        .unwrap();
+
+        let layer = map.tile_layer("empty").unwrap();
+
+        assert_eq!((layer.width, layer.height), (0, 0));
+        assert!(layer.tiles.is_empty());
+        assert_eq!(layer.get(0, 0), None);
+    }
+
+    #[test]
+    fn reject_invalid_tile_data() {
+        let parse = |layer: &str| {
+            parse_map(
+                &format!(
+                    r#"<map orientation="orthogonal" width="2" height="2" tilewidth="8" tileheight="8">{}</map>"#,
+                    layer
+                ),
+                Path::new(""),
+            )
+        };
+
+        let short =
+            r#"<layer name="short" width="2" height="2"><data encoding="csv">1,2,3</data></layer>"#;
+        let empty_chunk = r#"<layer name="chunks" width="2" height="2"><data encoding="csv"><chunk x="0" y="0" width="0" height="1">1</chunk></data></layer>"#;
+        let short_chunk = r#"<layer name="chunks" width="2" height="2"><data encoding="csv"><chunk x="0" y="0" width="2" height="2">1,2,3</chunk></data></layer>"#;
+
+        for layer in &[short, empty_chunk, short_chunk] {
+            assert!(matches!(parse(layer), Err(TetraError::InvalidTilemap(_))));
+        }
+    }
+}
@@ -3,6 +3,6 @@ mod window_sdl;
 
 pub use device_gl::{
     GraphicsDevice, RawCanvas, RawIndexBuffer, RawRenderbuffer, RawShader, RawTexture,
-    RawVertexBuffer,
+    RawVertexBuffer, UniformLocation,
 };
 pub use window_sdl::{handle_events, Window};
@@ -3,15 +3,17 @@ use std::mem;
 use std::rc::Rc;
 use std::slice;
 
+use bytemuck::Pod;
 use glow::{Context as GlowContext, HasContext, PixelPackData, PixelUnpackData};
 
 use crate::error::{Result, TetraError};
 use crate::graphics::{
-    mesh::{BufferUsage, Vertex, VertexWinding},
-    StencilState, StencilTest,
+    mesh::{AttributeBinding, BufferUsage, VertexAttributeType, VertexLayout, VertexWinding},
+    DepthState, DepthTest, StencilState, StencilTest,
 };
 use crate::graphics::{
-    BlendAlphaMode, BlendMode, Color, FilterMode, GraphicsDeviceInfo, StencilAction,
+    BlendEquation, BlendFactor, BlendMode, Color, FilterMode, GraphicsDeviceInfo, ShaderType,
+    ShaderSource, ShaderVariable, StencilAction, TextureFormat, WrapMode,
 };
 use crate::math::{Mat2, Mat3, Mat4, Vec2, Vec3, Vec4};
 
@@ -21,7 +23,7 @@ type TextureId = <GlowContext as HasContext>::Texture;
 type FramebufferId = <GlowContext as HasContext>::Framebuffer;
 type RenderbufferId = <GlowContext as HasContext>::Renderbuffer;
 type VertexArrayId = <GlowContext as HasContext>::VertexArray;
-type UniformLocation = <GlowContext as HasContext>::UniformLocation;
+pub type UniformLocation = <GlowContext as HasContext>::UniformLocation;
 
 #[derive(Debug)]
 struct GraphicsState {
@@ -34,11 +36,13 @@ struct GraphicsState {
     current_read_framebuffer: Cell<Option<FramebufferId>>,
     current_draw_framebuffer: Cell<Option<FramebufferId>>,
     current_renderbuffer: Cell<Option<RenderbufferId>>,
+    enabled_attributes: Cell<u32>,
 
     vertex_array: VertexArrayId,
     resolve_framebuffer: FramebufferId,
 
     max_samples: u8,
+    max_vertex_attributes: u32,
 }
 
 pub struct GraphicsDevice {
@@ -58,6 +62,11 @@ impl GraphicsDevice {
                 glow::ONE_MINUS_SRC_ALPHA,
             );
 
+            // Rows of pixel data are tightly packed, even for the formats that
+            // have fewer than four bytes per pixel.
+            gl.pixel_store_i32(glow::UNPACK_ALIGNMENT, 1);
+            gl.pixel_store_i32(glow::PACK_ALIGNMENT, 1);
+
             // This is only needed for Core GL - if we wanted to be uber compatible, we'd
             // turn it off on older versions.
             let vertex_array = gl
@@ -76,6 +85,10 @@ impl GraphicsDevice {
 
             let max_samples = gl.get_parameter_i32(glow::MAX_SAMPLES) as u8;
 
+            // Enabled attributes are tracked in a `u32` bitmask, so no more than 32 can be used.
+            let max_vertex_attributes =
+                u32::min(gl.get_parameter_i32(glow::MAX_VERTEX_ATTRIBS) as u32, 32);
+
             let state = GraphicsState {
                 gl,
 
@@ -86,11 +99,13 @@ impl GraphicsDevice {
                 current_read_framebuffer: Cell::new(None),
                 current_draw_framebuffer: Cell::new(None),
                 current_renderbuffer: Cell::new(None),
+                enabled_attributes: Cell::new(0),
 
                 vertex_array,
                 resolve_framebuffer,
 
                 max_samples,
+                max_vertex_attributes,
             };
 
             Ok(GraphicsDevice {
@@ -182,6 +197,28 @@ impl GraphicsDevice {
         }
     }
 
+    pub fn set_depth_state(&mut self, state: DepthState) {
+        unsafe {
+            if state.enabled {
+                self.state.gl.enable(glow::DEPTH_TEST);
+            } else {
+                self.state.gl.disable(glow::DEPTH_TEST);
+            }
+
+            self.state.gl.depth_func(state.test.as_gl_enum());
+            self.state.gl.depth_mask(state.write);
+        }
+    }
+
+    pub fn clear_depth(&mut self, value: f32) {
+        unsafe {
+            // Tetra's depth values range from -1.0 to 1.0, but the depth buffer
+            // stores them in the range of 0.0 to 1.0.
+            self.state.gl.clear_depth_f32((value + 1.0) / 2.0);
+            self.state.gl.clear(glow::DEPTH_BUFFER_BIT);
+        }
+    }
+
     pub fn set_color_mask(&mut self, red: bool, green: bool, blue: bool, alpha: bool) {
         unsafe {
             self.state.gl.color_mask(red, green, blue, alpha);
@@ -191,8 +228,23 @@ impl GraphicsDevice {
     pub fn new_vertex_buffer(
         &mut self,
         count: usize,
+        layout: VertexLayout,
         usage: BufferUsage,
     ) -> Result<RawVertexBuffer> {
+        let max_location = self.state.max_vertex_attributes;
+
+        for attribute in layout.attributes() {
+            if let AttributeBinding::Location(location) = attribute.binding {
+                if location >= max_location {
+                    return Err(TetraError::PlatformError(format!(
+                        "failed to create vertex buffer: attribute location {} was requested, but the maximum is {}",
+                        location,
+                        max_location - 1
+                    )));
+                }
+            }
+        }
+
         unsafe {
             let id = self
                 .state
@@ -204,6 +256,7 @@ impl GraphicsDevice {
                 state: Rc::clone(&self.state),
                 id,
                 count,
+                layout,
             };
 
             self.bind_vertex_buffer(Some(buffer.id));
@@ -225,14 +278,18 @@ impl GraphicsDevice {
         }
     }
 
-    pub fn set_vertex_buffer_data(
-        &mut self,
-        buffer: &RawVertexBuffer,
-        data: &[Vertex],
-        offset: usize,
-    ) {
+    pub fn set_vertex_buffer_data<T>(&mut self, buffer: &RawVertexBuffer, data: &[T], offset: usize)
+    where
+        T: Pod,
+    {
         self.bind_vertex_buffer(Some(buffer.id));
 
+        assert_eq!(
+            std::mem::size_of::<T>(),
+            buffer.stride(),
+            "vertex data does not match the buffer's layout"
+        );
+
         assert!(
             data.len() + offset <= buffer.count(),
             "tried to write out of bounds buffer data"
@@ -249,42 +306,81 @@ impl GraphicsDevice {
         }
     }
 
-    fn set_vertex_attributes(&mut self, buffer: &RawVertexBuffer) {
-        // TODO: This only works because we don't let the user set custom
-        // attribute bindings - will need a rethink at that point!
+    fn set_vertex_attributes(
+        &mut self,
+        vertex_buffer: &RawVertexBuffer,
+        instance_buffer: Option<&RawVertexBuffer>,
+        shader: &RawShader,
+    ) {
+        let mut enabled = self.bind_attributes(vertex_buffer, shader, 0);
+
+        if let Some(instance_buffer) = instance_buffer {
+            enabled |= self.bind_attributes(instance_buffer, shader, 1);
+        }
+
+        // The vertex buffer needs to be rebound afterwards, as the draw call expects it
+        // to be the active buffer.
+        self.bind_vertex_buffer(Some(vertex_buffer.id));
+        self.set_enabled_attributes(enabled);
+    }
+
+    fn bind_attributes(
+        &mut self,
+        buffer: &RawVertexBuffer,
+        shader: &RawShader,
+        divisor: u32,
+    ) -> u32 {
+        let mut enabled = 0;
+
         unsafe {
             self.bind_vertex_buffer(Some(buffer.id));
 
-            self.state.gl.vertex_attrib_pointer_f32(
-                0,
-                2,
-                glow::FLOAT,
-                false,
-                buffer.stride() as i32,
-                0,
-            );
+            for attribute in buffer.layout.attributes() {
+                let location = match &attribute.binding {
+                    AttributeBinding::Location(location) => *location,
+                    AttributeBinding::Name(name) => {
+                        match self.state.gl.get_attrib_location(shader.id, name) {
+                            Some(location) => location,
+                            None => continue,
+                        }
+                    }
+                };
+
+                self.state.gl.vertex_attrib_pointer_f32(
+                    location,
+                    attribute.components as i32,
+                    attribute.ty.into(),
+                    attribute.normalized,
+                    buffer.stride() as i32,
+                    attribute.offset as i32,
+                );
 
-            self.state.gl.vertex_attrib_pointer_f32(
-                1,
-                2,
-                glow::FLOAT,
-                false,
-                buffer.stride() as i32,
-                8,
-            );
+                self.state.gl.vertex_attrib_divisor(location, divisor);
 
-            self.state.gl.vertex_attrib_pointer_f32(
-                2,
-                4,
-                glow::FLOAT,
-                false,
-                buffer.stride() as i32,
-                16,
-            );
+                enabled |= 1 << location;
+            }
+        }
+
+        enabled
+    }
+
+    fn set_enabled_attributes(&mut self, enabled: u32) {
+        let previous = self.state.enabled_attributes.get();
+
+        if previous != enabled {
+            unsafe {
+                for location in 0..32 {
+                    let mask = 1 << location;
 
-            self.state.gl.enable_vertex_attrib_array(0);
-            self.state.gl.enable_vertex_attrib_array(1);
-            self.state.gl.enable_vertex_attrib_array(2);
+                    if enabled & mask != 0 && previous & mask == 0 {
+                        self.state.gl.enable_vertex_attrib_array(location);
+                    } else if enabled & mask == 0 && previous & mask != 0 {
+                        self.state.gl.disable_vertex_attrib_array(location);
+                    }
+                }
+            }
+
+            self.state.enabled_attributes.set(enabled);
         }
     }
 
@@ -342,7 +438,11 @@ impl GraphicsDevice {
         }
     }
 
-    pub fn new_shader(&mut self, vertex_shader: &str, fragment_shader: &str) -> Result<RawShader> {
+    pub fn new_shader(
+        &mut self,
+        vertex_shader: &ShaderSource,
+        fragment_shader: &ShaderSource,
+    ) -> Result<RawShader> {
         unsafe {
             let program_id = self
                 .state
@@ -356,6 +456,7 @@ impl GraphicsDevice {
                 .bind_attrib_location(program_id, 0, "a_position");
             self.state.gl.bind_attrib_location(program_id, 1, "a_uv");
             self.state.gl.bind_attrib_location(program_id, 2, "a_color");
+            self.state.gl.bind_attrib_location(program_id, 3, "a_depth");
 
             let vertex_id = self
                 .state
@@ -363,13 +464,15 @@ impl GraphicsDevice {
                 .create_shader(glow::VERTEX_SHADER)
                 .map_err(TetraError::PlatformError)?;
 
-            self.state.gl.shader_source(vertex_id, vertex_shader);
+            self.state
+                .gl
+                .shader_source(vertex_id, vertex_shader.source());
             self.state.gl.compile_shader(vertex_id);
             self.state.gl.attach_shader(program_id, vertex_id);
 
             if !self.state.gl.get_shader_compile_status(vertex_id) {
                 return Err(TetraError::InvalidShader(
-                    self.state.gl.get_shader_info_log(vertex_id),
+                    vertex_shader.map_log(&self.state.gl.get_shader_info_log(vertex_id)),
                 ));
             }
 
@@ -379,13 +482,15 @@ impl GraphicsDevice {
                 .create_shader(glow::FRAGMENT_SHADER)
                 .map_err(TetraError::PlatformError)?;
 
-            self.state.gl.shader_source(fragment_id, fragment_shader);
+            self.state
+                .gl
+                .shader_source(fragment_id, fragment_shader.source());
             self.state.gl.compile_shader(fragment_id);
             self.state.gl.attach_shader(program_id, fragment_id);
 
             if !self.state.gl.get_shader_compile_status(fragment_id) {
                 return Err(TetraError::InvalidShader(
-                    self.state.gl.get_shader_info_log(fragment_id),
+                    fragment_shader.map_log(&self.state.gl.get_shader_info_log(fragment_id)),
                 ));
             }
 
@@ -416,6 +521,28 @@ impl GraphicsDevice {
         unsafe { self.state.gl.get_uniform_location(shader.id, name) }
     }
 
+    pub fn get_active_uniforms(&self, shader: &RawShader) -> Vec<ShaderVariable> {
+        unsafe {
+            let count = self.state.gl.get_active_uniforms(shader.id);
+
+            (0..count)
+                .filter_map(|i| self.state.gl.get_active_uniform(shader.id, i))
+                .filter_map(|u| shader_variable(u.name, u.utype, u.size))
+                .collect()
+        }
+    }
+
+    pub fn get_active_attributes(&self, shader: &RawShader) -> Vec<ShaderVariable> {
+        unsafe {
+            let count = self.state.gl.get_active_attributes(shader.id);
+
+            (0..count)
+                .filter_map(|i| self.state.gl.get_active_attribute(shader.id, i))
+                .filter_map(|a| shader_variable(a.name, a.atype, a.size))
+                .collect()
+        }
+    }
+
     pub fn set_uniform_i32(
         &mut self,
         shader: &RawShader,
@@ -589,13 +716,19 @@ impl GraphicsDevice {
     }
 
     pub fn set_blend_mode(&mut self, blend_mode: BlendMode) {
+        let state = blend_mode.to_state();
+
         unsafe {
-            self.state.gl.blend_equation(blend_mode.equation());
+            self.state.gl.blend_equation_separate(
+                state.rgb_equation.into(),
+                state.alpha_equation.into(),
+            );
+
             self.state.gl.blend_func_separate(
-                blend_mode.src_rgb(),
-                blend_mode.dst_rgb(),
-                blend_mode.src_alpha(),
-                blend_mode.dst_alpha(),
+                state.src_rgb.into(),
+                state.dst_rgb.into(),
+                state.src_alpha.into(),
+                state.dst_alpha.into(),
             );
         }
     }
@@ -604,9 +737,9 @@ impl GraphicsDevice {
         &mut self,
         width: i32,
         height: i32,
+        format: TextureFormat,
         filter_mode: FilterMode,
     ) -> Result<RawTexture> {
-        // TODO: I don't think we need mipmaps?
         unsafe {
             let id = self
                 .state
@@ -620,6 +753,7 @@ impl GraphicsDevice {
                 id,
                 width,
                 height,
+                format,
             };
 
             self.bind_default_texture(Some(texture.id));
@@ -627,13 +761,13 @@ impl GraphicsDevice {
             self.state.gl.tex_parameter_i32(
                 glow::TEXTURE_2D,
                 glow::TEXTURE_MIN_FILTER,
-                filter_mode.into(),
+                filter_mode.min_filter(),
             );
 
             self.state.gl.tex_parameter_i32(
                 glow::TEXTURE_2D,
                 glow::TEXTURE_MAG_FILTER,
-                filter_mode.into(),
+                filter_mode.mag_filter(),
             );
 
             self.state.gl.tex_parameter_i32(
@@ -652,6 +786,8 @@ impl GraphicsDevice {
                 .gl
                 .tex_parameter_i32(glow::TEXTURE_2D, glow::TEXTURE_BASE_LEVEL, 0);
 
+            // Mipmaps are only enabled if the texture requests them, as otherwise the
+            // texture would be incomplete until they were generated.
             self.state
                 .gl
                 .tex_parameter_i32(glow::TEXTURE_2D, glow::TEXTURE_MAX_LEVEL, 0);
@@ -661,12 +797,12 @@ impl GraphicsDevice {
             self.state.gl.tex_image_2d(
                 glow::TEXTURE_2D,
                 0,
-                glow::RGBA as i32, // love 2 deal with legacy apis
+                format.internal_format() as i32, // love 2 deal with legacy apis
                 width,
                 height,
                 0,
-                glow::RGBA,
-                glow::UNSIGNED_BYTE,
+                format.pixel_format(),
+                format.pixel_type(),
                 None,
             );
 
@@ -695,7 +831,7 @@ impl GraphicsDevice {
             "tried to write outside of texture bounds"
         );
 
-        let expected = (width * height * 4) as usize;
+        let expected = (width * height) as usize * texture.format.bytes_per_pixel();
         let actual = data.len();
 
         if expected > actual {
@@ -712,8 +848,8 @@ impl GraphicsDevice {
                 y,
                 width,
                 height,
-                glow::RGBA,
-                glow::UNSIGNED_BYTE,
+                texture.format.pixel_format(),
+                texture.format.pixel_type(),
                 PixelUnpackData::Slice(data),
             )
         }
@@ -726,6 +862,7 @@ impl GraphicsDevice {
 
         let mut buffer = vec![0; (texture.width * texture.height * 4) as usize];
 
+        // OpenGL will convert from the texture's format to RGBA8 for us.
         unsafe {
             self.state.gl.get_tex_image(
                 glow::TEXTURE_2D,
@@ -739,6 +876,25 @@ impl GraphicsDevice {
         buffer
     }
 
+    pub fn get_texture_raw_data(&mut self, texture: &RawTexture) -> Vec<u8> {
+        self.bind_default_texture(Some(texture.id));
+
+        let mut buffer =
+            vec![0; (texture.width * texture.height) as usize * texture.format.bytes_per_pixel()];
+
+        unsafe {
+            self.state.gl.get_tex_image(
+                glow::TEXTURE_2D,
+                0,
+                texture.format.pixel_format(),
+                texture.format.pixel_type(),
+                PixelPackData::Slice(&mut buffer),
+            );
+        }
+
+        buffer
+    }
+
     pub fn set_texture_filter_mode(&mut self, texture: &RawTexture, filter_mode: FilterMode) {
         self.bind_default_texture(Some(texture.id));
 
@@ -746,30 +902,91 @@ impl GraphicsDevice {
             self.state.gl.tex_parameter_i32(
                 glow::TEXTURE_2D,
                 glow::TEXTURE_MIN_FILTER,
-                filter_mode.into(),
+                filter_mode.min_filter(),
             );
 
             self.state.gl.tex_parameter_i32(
                 glow::TEXTURE_2D,
                 glow::TEXTURE_MAG_FILTER,
-                filter_mode.into(),
+                filter_mode.mag_filter(),
             );
         }
     }
 
+    pub fn set_texture_wrap_mode(
+        &mut self,
+        texture: &RawTexture,
+        horizontal: WrapMode,
+        vertical: WrapMode,
+    ) {
+        self.bind_default_texture(Some(texture.id));
+
+        unsafe {
+            self.state
+                .gl
+                .tex_parameter_i32(glow::TEXTURE_2D, glow::TEXTURE_WRAP_S, horizontal.into());
+
+            self.state
+                .gl
+                .tex_parameter_i32(glow::TEXTURE_2D, glow::TEXTURE_WRAP_T, vertical.into());
+        }
+    }
+
+    pub fn set_texture_border_color(&mut self, texture: &RawTexture, color: Color) {
+        self.bind_default_texture(Some(texture.id));
+
+        unsafe {
+            self.state.gl.tex_parameter_f32_slice(
+                glow::TEXTURE_2D,
+                glow::TEXTURE_BORDER_COLOR,
+                &[color.r, color.g, color.b, color.a],
+            );
+        }
+    }
+
+    pub fn generate_texture_mipmaps(&mut self, texture: &RawTexture) {
+        self.bind_default_texture(Some(texture.id));
+
+        unsafe {
+            // 1000 is the default max level in OpenGL - the driver will stop
+            // generating levels once it reaches 1x1.
+            self.state
+                .gl
+                .tex_parameter_i32(glow::TEXTURE_2D, glow::TEXTURE_MAX_LEVEL, 1000);
+
+            self.state.gl.generate_mipmap(glow::TEXTURE_2D);
+        }
+    }
+
     pub fn attach_texture_to_sampler(&mut self, texture: &RawTexture, unit: u32) -> Result {
         self.bind_texture(Some(texture.id), unit)
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub fn new_canvas(
         &mut self,
         width: i32,
         height: i32,
+        formats: &[TextureFormat],
         filter_mode: FilterMode,
         samples: u8,
         with_stencil_buffer: bool,
+        with_depth_buffer: bool,
     ) -> Result<RawCanvasWithAttachments> {
         unsafe {
+            let max_attachments = i32::min(
+                self.state.gl.get_parameter_i32(glow::MAX_COLOR_ATTACHMENTS),
+                self.state.gl.get_parameter_i32(glow::MAX_DRAW_BUFFERS),
+            ) as usize;
+
+            if formats.len() > max_attachments {
+                return Err(TetraError::PlatformError(format!(
+                    "failed to create canvas: {} color attachments were requested, but the maximum is {}",
+                    formats.len(),
+                    max_attachments
+                )));
+            }
+
             let previous_read = self.state.current_read_framebuffer.get();
             let previous_draw = self.state.current_draw_framebuffer.get();
 
@@ -786,38 +1003,56 @@ impl GraphicsDevice {
 
             self.bind_framebuffer(Some(canvas.id));
 
-            let color = self.new_texture(width, height, filter_mode)?;
+            let mut color = Vec::with_capacity(formats.len());
+            let mut draw_buffers = Vec::with_capacity(formats.len());
 
-            self.state.gl.framebuffer_texture_2d(
-                glow::FRAMEBUFFER,
-                glow::COLOR_ATTACHMENT0,
-                glow::TEXTURE_2D,
-                Some(color.id),
-                0,
-            );
+            for (i, &format) in formats.iter().enumerate() {
+                let texture = self.new_texture(width, height, format, filter_mode)?;
+                let attachment = glow::COLOR_ATTACHMENT0 + i as u32;
+
+                self.state.gl.framebuffer_texture_2d(
+                    glow::FRAMEBUFFER,
+                    attachment,
+                    glow::TEXTURE_2D,
+                    Some(texture.id),
+                    0,
+                );
+
+                color.push(texture);
+                draw_buffers.push(attachment);
+            }
+
+            // The draw buffers are part of the framebuffer's state, so this only needs
+            // to be set once.
+            self.state.gl.draw_buffers(&draw_buffers);
 
             self.clear(Color::rgba(0.0, 0.0, 0.0, 0.0));
 
             let actual_samples = u8::min(samples, self.state.max_samples);
 
-            let multisample_color = if actual_samples > 0 {
-                let renderbuffer = self.new_color_renderbuffer(width, height, actual_samples)?;
+            let mut multisample_color = Vec::new();
 
-                self.state.gl.framebuffer_renderbuffer(
-                    glow::FRAMEBUFFER,
-                    glow::COLOR_ATTACHMENT0,
-                    glow::RENDERBUFFER,
-                    Some(renderbuffer.id),
-                );
+            if actual_samples > 0 {
+                for (&format, &attachment) in formats.iter().zip(&draw_buffers) {
+                    let renderbuffer =
+                        self.new_color_renderbuffer(width, height, format, actual_samples)?;
 
-                self.clear(Color::rgba(0.0, 0.0, 0.0, 0.0));
+                    self.state.gl.framebuffer_renderbuffer(
+                        glow::FRAMEBUFFER,
+                        attachment,
+                        glow::RENDERBUFFER,
+                        Some(renderbuffer.id),
+                    );
 
-                Some(renderbuffer)
-            } else {
-                None
-            };
+                    multisample_color.push(renderbuffer);
+                }
 
-            let depth_stencil = if with_stencil_buffer {
+                self.clear(Color::rgba(0.0, 0.0, 0.0, 0.0));
+            }
+
+            // Both buffers share a single attachment, as combined depth/stencil formats
+            // are the most widely supported.
+            let depth_stencil = if with_stencil_buffer || with_depth_buffer {
                 let renderbuffer =
                     self.new_depth_stencil_renderbuffer(width, height, actual_samples)?;
 
@@ -829,7 +1064,7 @@ impl GraphicsDevice {
                 );
 
                 self.clear_stencil(0);
-                // TODO: Clear the depth buffer, if we start using it
+                self.clear_depth(-1.0);
 
                 Some(renderbuffer)
             } else {
@@ -863,7 +1098,7 @@ impl GraphicsDevice {
         self.bind_framebuffer(canvas.map(|f| f.id));
     }
 
-    pub fn resolve(&mut self, canvas: &RawCanvas, texture: &RawTexture) {
+    pub fn resolve(&mut self, canvas: &RawCanvas, textures: &[&RawTexture]) {
         unsafe {
             let previous_read = self.state.current_read_framebuffer.get();
             let previous_draw = self.state.current_draw_framebuffer.get();
@@ -871,26 +1106,36 @@ impl GraphicsDevice {
             self.bind_read_framebuffer(Some(canvas.id));
             self.bind_draw_framebuffer(Some(self.state.resolve_framebuffer));
 
-            self.state.gl.framebuffer_texture_2d(
-                glow::DRAW_FRAMEBUFFER,
-                glow::COLOR_ATTACHMENT0,
-                glow::TEXTURE_2D,
-                Some(texture.id),
-                0,
-            );
+            // Each attachment is blitted separately, as a blit can only read from
+            // one color buffer at a time.
+            for (i, texture) in textures.iter().enumerate() {
+                self.state
+                    .gl
+                    .read_buffer(glow::COLOR_ATTACHMENT0 + i as u32);
 
-            self.state.gl.blit_framebuffer(
-                0,
-                0,
-                texture.width,
-                texture.height,
-                0,
-                0,
-                texture.width,
-                texture.height,
-                glow::COLOR_BUFFER_BIT,
-                glow::NEAREST,
-            );
+                self.state.gl.framebuffer_texture_2d(
+                    glow::DRAW_FRAMEBUFFER,
+                    glow::COLOR_ATTACHMENT0,
+                    glow::TEXTURE_2D,
+                    Some(texture.id),
+                    0,
+                );
+
+                self.state.gl.blit_framebuffer(
+                    0,
+                    0,
+                    texture.width,
+                    texture.height,
+                    0,
+                    0,
+                    texture.width,
+                    texture.height,
+                    glow::COLOR_BUFFER_BIT,
+                    glow::NEAREST,
+                );
+            }
+
+            self.state.gl.read_buffer(glow::COLOR_ATTACHMENT0);
 
             self.bind_read_framebuffer(previous_read);
             self.bind_draw_framebuffer(previous_draw);
@@ -901,9 +1146,10 @@ impl GraphicsDevice {
         &mut self,
         width: i32,
         height: i32,
+        format: TextureFormat,
         samples: u8,
     ) -> Result<RawRenderbuffer> {
-        self.new_renderbuffer(width, height, glow::RGBA, samples)
+        self.new_renderbuffer(width, height, format.internal_format(), samples)
     }
 
     pub fn new_depth_stencil_renderbuffer(
@@ -972,6 +1218,7 @@ impl GraphicsDevice {
         self.draw_instanced(
             vertex_buffer,
             index_buffer,
+            None,
             texture,
             shader,
             offset,
@@ -985,6 +1232,7 @@ impl GraphicsDevice {
         &mut self,
         vertex_buffer: &RawVertexBuffer,
         index_buffer: Option<&RawIndexBuffer>,
+        instance_buffer: Option<&RawVertexBuffer>,
         texture: &RawTexture,
         shader: &RawShader,
         offset: usize,
@@ -994,7 +1242,7 @@ impl GraphicsDevice {
         self.bind_vertex_buffer(Some(vertex_buffer.id));
         self.bind_default_texture(Some(texture.id));
         self.bind_program(Some(shader.id));
-        self.set_vertex_attributes(vertex_buffer);
+        self.set_vertex_attributes(vertex_buffer, instance_buffer, shader);
 
         match index_buffer {
             Some(index_buffer) => {
@@ -1194,68 +1442,154 @@ impl From<VertexWinding> for u32 {
 }
 
 #[doc(hidden)]
-impl From<FilterMode> for i32 {
-    fn from(filter_mode: FilterMode) -> i32 {
-        match filter_mode {
+impl FilterMode {
+    pub(crate) fn min_filter(&self) -> i32 {
+        match self {
             FilterMode::Nearest => glow::NEAREST as i32,
             FilterMode::Linear => glow::LINEAR as i32,
+            FilterMode::NearestMipmap => glow::NEAREST_MIPMAP_LINEAR as i32,
+            FilterMode::Trilinear => glow::LINEAR_MIPMAP_LINEAR as i32,
+        }
+    }
+
+    pub(crate) fn mag_filter(&self) -> i32 {
+        match self {
+            FilterMode::Nearest | FilterMode::NearestMipmap => glow::NEAREST as i32,
+            FilterMode::Linear | FilterMode::Trilinear => glow::LINEAR as i32,
         }
     }
 }
 
 #[doc(hidden)]
-impl BlendMode {
-    pub(crate) fn equation(&self) -> u32 {
+impl TextureFormat {
+    pub(crate) fn internal_format(&self) -> u32 {
         match self {
-            BlendMode::Alpha(_) => glow::FUNC_ADD,
-            BlendMode::Add(_) => glow::FUNC_ADD,
-            BlendMode::Subtract(_) => glow::FUNC_REVERSE_SUBTRACT,
-            BlendMode::Multiply => glow::FUNC_ADD,
+            TextureFormat::R8 => glow::R8,
+            TextureFormat::Rg8 => glow::RG8,
+            TextureFormat::Rgba8 => glow::RGBA8,
+            TextureFormat::Rgba16F => glow::RGBA16F,
+            TextureFormat::Rgba32F => glow::RGBA32F,
+            TextureFormat::Srgba8 => glow::SRGB8_ALPHA8,
         }
     }
 
-    pub(crate) fn src_rgb(&self) -> u32 {
-        match self {
-            BlendMode::Alpha(blend_alpha) => match blend_alpha {
-                BlendAlphaMode::Multiply => glow::SRC_ALPHA,
-                BlendAlphaMode::Premultiplied => glow::ONE,
-            },
-            BlendMode::Add(blend_alpha) => match blend_alpha {
-                BlendAlphaMode::Multiply => glow::SRC_ALPHA,
-                BlendAlphaMode::Premultiplied => glow::ONE,
-            },
-            BlendMode::Subtract(blend_alpha) => match blend_alpha {
-                BlendAlphaMode::Multiply => glow::SRC_ALPHA,
-                BlendAlphaMode::Premultiplied => glow::ONE,
-            },
-            BlendMode::Multiply => glow::DST_COLOR,
-        }
-    }
-
-    pub(crate) fn src_alpha(&self) -> u32 {
+    pub(crate) fn pixel_format(&self) -> u32 {
         match self {
-            BlendMode::Alpha(_) => glow::ONE,
-            BlendMode::Add(_) => glow::ZERO,
-            BlendMode::Subtract(_) => glow::ZERO,
-            BlendMode::Multiply => glow::DST_COLOR,
+            TextureFormat::R8 => glow::RED,
+            TextureFormat::Rg8 => glow::RG,
+            TextureFormat::Rgba8
+            | TextureFormat::Rgba16F
+            | TextureFormat::Rgba32F
+            | TextureFormat::Srgba8 => glow::RGBA,
         }
     }
 
-    pub(crate) fn dst_rgb(&self) -> u32 {
+    pub(crate) fn pixel_type(&self) -> u32 {
         match self {
-            BlendMode::Alpha(_) => glow::ONE_MINUS_SRC_ALPHA,
-            BlendMode::Add(_) => glow::ONE,
-            BlendMode::Subtract(_) => glow::ONE,
-            BlendMode::Multiply => glow::ZERO,
+            TextureFormat::Rgba16F => glow::HALF_FLOAT,
+            TextureFormat::Rgba32F => glow::FLOAT,
+            TextureFormat::R8
+            | TextureFormat::Rg8
+            | TextureFormat::Rgba8
+            | TextureFormat::Srgba8 => glow::UNSIGNED_BYTE,
         }
     }
+}
 
-    pub(crate) fn dst_alpha(&self) -> u32 {
-        match self {
-            BlendMode::Alpha(_) => glow::ONE_MINUS_SRC_ALPHA,
-            BlendMode::Add(_) => glow::ONE,
-            BlendMode::Subtract(_) => glow::ONE,
-            BlendMode::Multiply => glow::ZERO,
+#[doc(hidden)]
+impl From<WrapMode> for i32 {
+    fn from(wrap_mode: WrapMode) -> i32 {
+        match wrap_mode {
+            WrapMode::ClampToEdge => glow::CLAMP_TO_EDGE as i32,
+            WrapMode::ClampToBorder => glow::CLAMP_TO_BORDER as i32,
+            WrapMode::Repeat => glow::REPEAT as i32,
+            WrapMode::MirroredRepeat => glow::MIRRORED_REPEAT as i32,
+        }
+    }
+}
+
+fn shader_variable(mut name: String, gl_type: u32, size: i32) -> Option<ShaderVariable> {
+    // Some drivers report built-in variables, which aren't useful to the user.
+    if name.starts_with("gl_") {
+        return None;
+    }
+
+    // Arrays are reported as their first element.
+    if name.ends_with("[0]") {
+        name.truncate(name.len() - 3);
+    }
+
+    let ty = match gl_type {
+        glow::FLOAT => ShaderType::Float,
+        glow::FLOAT_VEC2 => ShaderType::Vec2,
+        glow::FLOAT_VEC3 => ShaderType::Vec3,
+        glow::FLOAT_VEC4 => ShaderType::Vec4,
+        glow::INT => ShaderType::Int,
+        glow::INT_VEC2 => ShaderType::IVec2,
+        glow::INT_VEC3 => ShaderType::IVec3,
+        glow::INT_VEC4 => ShaderType::IVec4,
+        glow::UNSIGNED_INT => ShaderType::UInt,
+        glow::UNSIGNED_INT_VEC2 => ShaderType::UVec2,
+        glow::UNSIGNED_INT_VEC3 => ShaderType::UVec3,
+        glow::UNSIGNED_INT_VEC4 => ShaderType::UVec4,
+        glow::BOOL => ShaderType::Bool,
+        glow::FLOAT_MAT2 => ShaderType::Mat2,
+        glow::FLOAT_MAT3 => ShaderType::Mat3,
+        glow::FLOAT_MAT4 => ShaderType::Mat4,
+        glow::SAMPLER_2D => ShaderType::Sampler2D,
+        _ => ShaderType::Other,
+    };
+
+    Some(ShaderVariable {
+        name,
+        ty,
+        array_size: size.max(1) as usize,
+    })
+}
+
+#[doc(hidden)]
+impl From<VertexAttributeType> for u32 {
+    fn from(ty: VertexAttributeType) -> u32 {
+        match ty {
+            VertexAttributeType::F32 => glow::FLOAT,
+            VertexAttributeType::I8 => glow::BYTE,
+            VertexAttributeType::U8 => glow::UNSIGNED_BYTE,
+            VertexAttributeType::I16 => glow::SHORT,
+            VertexAttributeType::U16 => glow::UNSIGNED_SHORT,
+            VertexAttributeType::I32 => glow::INT,
+            VertexAttributeType::U32 => glow::UNSIGNED_INT,
+        }
+    }
+}
+
+#[doc(hidden)]
+impl From<BlendEquation> for u32 {
+    fn from(equation: BlendEquation) -> u32 {
+        match equation {
+            BlendEquation::Add => glow::FUNC_ADD,
+            BlendEquation::Subtract => glow::FUNC_SUBTRACT,
+            BlendEquation::ReverseSubtract => glow::FUNC_REVERSE_SUBTRACT,
+            BlendEquation::Min => glow::MIN,
+            BlendEquation::Max => glow::MAX,
+        }
+    }
+}
+
+#[doc(hidden)]
+impl From<BlendFactor> for u32 {
+    fn from(factor: BlendFactor) -> u32 {
+        match factor {
+            BlendFactor::Zero => glow::ZERO,
+            BlendFactor::One => glow::ONE,
+            BlendFactor::SrcColor => glow::SRC_COLOR,
+            BlendFactor::OneMinusSrcColor => glow::ONE_MINUS_SRC_COLOR,
+            BlendFactor::DstColor => glow::DST_COLOR,
+            BlendFactor::OneMinusDstColor => glow::ONE_MINUS_DST_COLOR,
+            BlendFactor::SrcAlpha => glow::SRC_ALPHA,
+            BlendFactor::OneMinusSrcAlpha => glow::ONE_MINUS_SRC_ALPHA,
+            BlendFactor::DstAlpha => glow::DST_ALPHA,
+            BlendFactor::OneMinusDstAlpha => glow::ONE_MINUS_DST_ALPHA,
+            BlendFactor::SrcAlphaSaturate => glow::SRC_ALPHA_SATURATE,
         }
     }
 }
@@ -1276,6 +1610,22 @@ impl StencilTest {
     }
 }
 
+#[doc(hidden)]
+impl DepthTest {
+    pub(crate) fn as_gl_enum(self) -> u32 {
+        match self {
+            DepthTest::Never => glow::NEVER,
+            DepthTest::LessThan => glow::LESS,
+            DepthTest::LessThanOrEqualTo => glow::LEQUAL,
+            DepthTest::EqualTo => glow::EQUAL,
+            DepthTest::NotEqualTo => glow::NOTEQUAL,
+            DepthTest::GreaterThan => glow::GREATER,
+            DepthTest::GreaterThanOrEqualTo => glow::GEQUAL,
+            DepthTest::Always => glow::ALWAYS,
+        }
+    }
+}
+
 #[doc(hidden)]
 impl StencilAction {
     pub(crate) fn as_gl_enum(self) -> u32 {
@@ -1298,6 +1648,7 @@ pub struct RawVertexBuffer {
     id: BufferId,
 
     count: usize,
+    layout: VertexLayout,
 }
 
 impl RawVertexBuffer {
@@ -1308,7 +1659,12 @@ impl RawVertexBuffer {
 
     // The size of each vertex, in bytes.
     pub fn stride(&self) -> usize {
-        std::mem::size_of::<Vertex>()
+        self.layout.stride()
+    }
+
+    /// The layout of the vertices in the buffer.
+    pub fn layout(&self) -> &VertexLayout {
+        &self.layout
     }
 
     /// The size of the buffer, in bytes.
@@ -1409,6 +1765,7 @@ pub struct RawTexture {
 
     width: i32,
     height: i32,
+    format: TextureFormat,
 }
 
 impl RawTexture {
@@ -1419,6 +1776,10 @@ impl RawTexture {
     pub fn height(&self) -> i32 {
         self.height
     }
+
+    pub fn format(&self) -> TextureFormat {
+        self.format
+    }
 }
 
 impl PartialEq for RawTexture {
@@ -1471,8 +1832,8 @@ impl Drop for RawCanvas {
 
 pub struct RawCanvasWithAttachments {
     pub canvas: RawCanvas,
-    pub color: RawTexture,
-    pub multisample_color: Option<RawRenderbuffer>,
+    pub color: Vec<RawTexture>,
+    pub multisample_color: Vec<RawRenderbuffer>,
     pub depth_stencil: Option<RawRenderbuffer>,
 }
 
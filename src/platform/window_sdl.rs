@@ -82,6 +82,10 @@ impl Window {
             gl_attr.set_stencil_size(8);
         }
 
+        if settings.depth_buffer {
+            gl_attr.set_depth_size(24);
+        }
+
         if settings.screen_saver_enabled {
             video_sys.enable_screen_saver();
         } else {
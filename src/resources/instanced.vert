@@ -0,0 +1,30 @@
+#version 150
+
+in vec2 a_position;
+in vec2 a_uv;
+in vec4 a_color;
+in float a_depth;
+
+in vec2 a_instance_position;
+in vec2 a_instance_scale;
+in float a_instance_rotation;
+in vec4 a_instance_color;
+in vec4 a_instance_region;
+
+uniform mat4 u_projection;
+
+out vec2 v_uv;
+out vec4 v_color;
+
+void main() {
+    v_color = a_color * a_instance_color;
+    v_uv = a_instance_region.xy + a_uv * a_instance_region.zw;
+
+    float s = sin(a_instance_rotation);
+    float c = cos(a_instance_rotation);
+
+    vec2 scaled = a_position * a_instance_scale;
+    vec2 rotated = vec2(scaled.x * c - scaled.y * s, scaled.x * s + scaled.y * c);
+
+    gl_Position = u_projection * vec4(rotated + a_instance_position, a_depth, 1.0);
+}
@@ -3,6 +3,7 @@
 in vec2 a_position;
 in vec2 a_uv;
 in vec4 a_color;
+in float a_depth;
 
 uniform mat4 u_projection;
 
@@ -13,5 +14,5 @@ void main() {
     v_color = a_color;
     v_uv = a_uv;
 
-    gl_Position = u_projection * vec4(a_position, 0.0, 1.0);
+    gl_Position = u_projection * vec4(a_position, a_depth, 1.0);
 }
\ No newline at end of file
@@ -52,6 +52,8 @@ pub(crate) struct TimeContext {
     pub(crate) tick_rate: Option<Duration>,
     pub(crate) delta_time: Duration,
     pub(crate) accumulator: Duration,
+    pub(crate) elapsed_time: Duration,
+    pub(crate) frame_count: u64,
 }
 
 impl TimeContext {
@@ -77,6 +79,8 @@ impl TimeContext {
             tick_rate,
             delta_time: Duration::from_secs(0),
             accumulator: Duration::from_secs(0),
+            elapsed_time: Duration::from_secs(0),
+            frame_count: 0,
         }
     }
 }
@@ -84,6 +88,8 @@ impl TimeContext {
 pub(crate) fn reset(ctx: &mut Context) {
     ctx.time.delta_time = Duration::from_secs(0);
     ctx.time.accumulator = Duration::from_secs(0);
+    ctx.time.elapsed_time = Duration::from_secs(0);
+    ctx.time.frame_count = 0;
 }
 
 /// Returns the amount of time that has passed since the last update or draw.
@@ -100,6 +106,18 @@ pub fn get_delta_time(ctx: &Context) -> Duration {
     ctx.time.delta_time
 }
 
+/// Returns the amount of time that has passed since the game loop started.
+pub fn get_elapsed_time(ctx: &Context) -> Duration {
+    ctx.time.elapsed_time
+}
+
+/// Returns the number of frames that have been completed since the game loop started.
+///
+/// This is incremented after each draw, so it will be `0` during the first frame.
+pub fn get_frame_count(ctx: &Context) -> u64 {
+    ctx.time.frame_count
+}
+
 /// Returns the amount of time that has accumulated between updates.
 ///
 /// When using a fixed time step, as time passes, this value will increase;